@@ -0,0 +1,249 @@
+use flate2::read::GzDecoder;
+use indicatif::ProgressBar;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::cli::HashAlgo;
+use crate::hasher::hash_reader;
+use crate::scanner::{ScanOptions, wanted_member};
+
+// Between an archive's path and a path inside it, as in `photos.zip!/2024/beach.jpg`
+pub const SEPARATOR: &str = "!/";
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn kind(path: &Path) -> Option<Kind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(Kind::Zip)
+    } else if name.ends_with(".tar") {
+        Some(Kind::Tar)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(Kind::TarGz)
+    } else {
+        None
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    kind(path).is_some()
+}
+
+// A file stored in an archive, compared like any other but never deleted or linked
+pub struct Member {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+// Splits a member's path into its archive and the name inside it. A real file whose path
+// merely looks like that, e.g. under a directory called `x.zip!`, is left alone.
+pub fn split(path: &Path) -> Option<(PathBuf, &str)> {
+    let text = path.to_str()?;
+    let (archive, name) = text
+        .match_indices(SEPARATOR)
+        .map(|(i, _)| (Path::new(&text[..i]), &text[i + SEPARATOR.len()..]))
+        .find(|(archive, _)| is_archive(archive))?;
+    if fs::symlink_metadata(path).is_ok() {
+        return None;
+    }
+    Some((archive.to_path_buf(), name))
+}
+
+pub fn is_member(path: &Path) -> bool {
+    split(path).is_some()
+}
+
+fn member_path(archive: &Path, name: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}{}", archive.display(), SEPARATOR, name))
+}
+
+fn open_error(e: &io::Error) -> io::Error {
+    io::Error::new(e.kind(), e.to_string())
+}
+
+// Regular files only; directories and links inside archives have no contents to compare
+fn list(archive: &Path) -> io::Result<Vec<(String, u64)>> {
+    let file = fs::File::open(archive)?;
+    let kind = kind(archive).ok_or_else(|| io::Error::other("not an archive"))?;
+    let mut entries = Vec::new();
+    match kind {
+        Kind::Zip => {
+            let mut zip = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+            for i in 0..zip.len() {
+                let entry = zip.by_index_raw(i).map_err(io::Error::other)?;
+                if entry.is_file() {
+                    let name = entry.name().map_err(io::Error::other)?.into_owned();
+                    entries.push((name, entry.size()));
+                }
+            }
+        }
+        Kind::Tar => list_tar(file, &mut entries)?,
+        Kind::TarGz => list_tar(GzDecoder::new(file), &mut entries)?,
+    }
+    Ok(entries)
+}
+
+fn list_tar(reader: impl Read, entries: &mut Vec<(String, u64)>) -> io::Result<()> {
+    for entry in tar::Archive::new(reader).entries()? {
+        let entry = entry?;
+        if entry.header().entry_type().is_file() {
+            entries.push((entry.path()?.to_string_lossy().into_owned(), entry.size()));
+        }
+    }
+    Ok(())
+}
+
+pub struct ArchiveScan {
+    pub members: Vec<Member>,
+    // Archives that couldn't be read, so their contents weren't compared
+    pub errors: usize,
+}
+
+// Lists what's inside every archive, subject to the same size limits and filters as the
+// files around them
+pub fn scan_archives(archives: &[PathBuf], options: &ScanOptions) -> ArchiveScan {
+    let mut scan = ArchiveScan {
+        members: Vec::new(),
+        errors: 0,
+    };
+    for archive in archives {
+        let entries = match list(archive) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!(
+                    "  Warning: cannot read archive {}: {}",
+                    archive.display(),
+                    e
+                );
+                scan.errors += 1;
+                continue;
+            }
+        };
+        let canonical = fs::canonicalize(archive).unwrap_or_else(|_| archive.clone());
+        for (name, size) in entries {
+            if wanted_member(&member_path(&canonical, &name), size, options) {
+                scan.members.push(Member {
+                    path: member_path(archive, &name),
+                    size,
+                });
+            }
+        }
+    }
+    scan
+}
+
+// Streams one archive through the hasher, hashing only the given members of it, which is
+// the only way to get at a compressed tar's entries anyway
+pub fn hash_members<'a>(
+    archive: &Path,
+    members: &[(u64, &'a PathBuf)],
+    algo: HashAlgo,
+    bar: &ProgressBar,
+) -> Vec<(&'a PathBuf, io::Result<String>)> {
+    let wanted: HashMap<&str, u64> = members
+        .iter()
+        .filter_map(|(size, path)| split(path).map(|(_, name)| (name, *size)))
+        .collect();
+    let mut hashes = match hash_wanted(archive, &wanted, algo, bar) {
+        Ok(hashes) => hashes,
+        Err(e) => {
+            let hashes = wanted
+                .keys()
+                .map(|name| (name.to_string(), Err(open_error(&e))));
+            hashes.collect()
+        }
+    };
+    members
+        .iter()
+        .map(|(size, path)| {
+            let name = split(path).map(|(_, name)| name).unwrap_or_default();
+            let result = hashes.remove(name).unwrap_or_else(|| {
+                bar.inc(*size);
+                Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "no longer in the archive",
+                ))
+            });
+            (*path, result)
+        })
+        .collect()
+}
+
+fn hash_wanted(
+    archive: &Path,
+    wanted: &HashMap<&str, u64>,
+    algo: HashAlgo,
+    bar: &ProgressBar,
+) -> io::Result<HashMap<String, io::Result<String>>> {
+    let file = fs::File::open(archive)?;
+    let mut hashes = HashMap::new();
+    match kind(archive) {
+        Some(Kind::Zip) => {
+            let mut zip = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+            for (name, size) in wanted {
+                let result = match zip.by_name(name) {
+                    Ok(mut entry) => {
+                        hash_reader(&mut entry, &member_path(archive, name), *size, algo, bar)
+                    }
+                    Err(e) => {
+                        bar.inc(*size);
+                        Err(io::Error::other(e))
+                    }
+                };
+                hashes.insert(name.to_string(), result);
+            }
+        }
+        Some(Kind::Tar) => hash_tar(archive, file, wanted, algo, bar, &mut hashes)?,
+        Some(Kind::TarGz) => hash_tar(
+            archive,
+            GzDecoder::new(file),
+            wanted,
+            algo,
+            bar,
+            &mut hashes,
+        )?,
+        None => {}
+    }
+    Ok(hashes)
+}
+
+fn hash_tar(
+    archive: &Path,
+    reader: impl Read,
+    wanted: &HashMap<&str, u64>,
+    algo: HashAlgo,
+    bar: &ProgressBar,
+    hashes: &mut HashMap<String, io::Result<String>>,
+) -> io::Result<()> {
+    for entry in tar::Archive::new(reader).entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        // A name stored twice is the later copy once extracted, so that's the one hashed
+        if let Some(size) = wanted.get(name.as_str()) {
+            let result = hash_reader(&mut entry, &member_path(archive, &name), *size, algo, bar);
+            hashes.insert(name, result);
+        }
+    }
+    Ok(())
+}
+
+// On-disk size, or the stored size of a file inside an archive
+pub fn size(path: &Path) -> u64 {
+    if let Ok(meta) = path.metadata() {
+        return meta.len();
+    }
+    let Some((archive, name)) = split(path) else {
+        return 0;
+    };
+    list(&archive)
+        .ok()
+        .and_then(|entries| entries.into_iter().find(|(n, _)| n == name))
+        .map_or(0, |(_, size)| size)
+}
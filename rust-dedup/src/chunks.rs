@@ -0,0 +1,193 @@
+use colored::Colorize;
+use fastcdc::v2020::StreamCDC;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::format::format_size;
+use crate::progress;
+use crate::scanner::file_id;
+
+// Around what deduplicating backup tools use. Smaller chunks find more of what's shared
+// but cost more memory, as every distinct chunk is kept track of.
+const MIN_CHUNK: usize = 16 * 1024;
+const AVERAGE_CHUNK: usize = 64 * 1024;
+const MAX_CHUNK: usize = 256 * 1024;
+// How many of the files sharing the most with others are listed
+const TOP_FILES: usize = 10;
+
+// The first half of a chunk's BLAKE3 hash: plenty to tell chunks apart, in half the memory
+type ChunkId = [u8; 16];
+// A file's chunks in order, with their lengths
+type Chunks = Vec<(ChunkId, u32)>;
+
+struct Chunked<'a> {
+    path: &'a PathBuf,
+    size: u64,
+    chunks: Chunks,
+}
+
+// Where a chunk was seen, to tell sharing between files from repeats inside one
+struct Seen {
+    length: u32,
+    files: u32,
+    last_file: usize,
+}
+
+pub struct ChunkReport {
+    // Files that couldn't be read, so the savings may be underestimated
+    pub errors: usize,
+    pub saved_bytes: u64,
+}
+
+fn chunk_file(path: &Path, bar: &ProgressBar) -> io::Result<Chunks> {
+    let file = fs::File::open(path)?;
+    let mut chunks = Vec::new();
+    for chunk in StreamCDC::new(file, MIN_CHUNK, AVERAGE_CHUNK, MAX_CHUNK) {
+        let chunk = chunk?;
+        let mut id = ChunkId::default();
+        id.copy_from_slice(&blake3::hash(&chunk.data).as_bytes()[..16]);
+        chunks.push((id, chunk.length as u32));
+        bar.inc(chunk.length as u64);
+    }
+    Ok(chunks)
+}
+
+// Splits every file into content-defined chunks (FastCDC), so data shared between files
+// that aren't identical is found too, wherever in the files it is. Prints how much storing
+// each distinct chunk once would save, against what removing whole duplicates does.
+pub fn report(files: &[PathBuf], threads: usize) -> ChunkReport {
+    // As when hashing, hardlinks are one copy of the data already
+    let mut inodes: HashSet<(u64, u64)> = HashSet::new();
+    let mut sized: Vec<(&PathBuf, u64)> = Vec::new();
+    for file in files {
+        if let Ok(meta) = file.metadata()
+            && file_id(&meta).is_none_or(|id| inodes.insert(id))
+        {
+            sized.push((file, meta.len()));
+        }
+    }
+
+    let total_bytes: u64 = sized.iter().map(|(_, size)| size).sum();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("failed to start chunking threads");
+    let bar = progress::hash_bar(total_bytes);
+    bar.set_prefix("chunking");
+    let results: Vec<(&PathBuf, u64, io::Result<Chunks>)> = pool.install(|| {
+        sized
+            .par_iter()
+            .map(|(path, size)| (*path, *size, chunk_file(path, &bar)))
+            .collect()
+    });
+    bar.finish_and_clear();
+
+    let mut errors = 0;
+    let mut chunked: Vec<Chunked> = Vec::new();
+    for (path, size, result) in results {
+        match result {
+            Ok(chunks) => chunked.push(Chunked { path, size, chunks }),
+            Err(e) => {
+                eprintln!("  Warning: could not chunk {}: {}", path.display(), e);
+                errors += 1;
+            }
+        }
+    }
+
+    let mut seen: HashMap<ChunkId, Seen> = HashMap::new();
+    for (i, file) in chunked.iter().enumerate() {
+        for (id, length) in &file.chunks {
+            let entry = seen.entry(*id).or_insert(Seen {
+                length: *length,
+                files: 0,
+                last_file: usize::MAX,
+            });
+            if entry.last_file != i {
+                entry.files += 1;
+                entry.last_file = i;
+            }
+        }
+    }
+    let chunk_count: usize = chunked.iter().map(|file| file.chunks.len()).sum();
+    let read_bytes: u64 = chunked.iter().map(|file| file.size).sum();
+    let unique_bytes: u64 = seen.values().map(|chunk| chunk.length as u64).sum();
+    let saved_bytes = read_bytes - unique_bytes;
+
+    // Files made of the same chunks in the same order are identical
+    let mut whole: HashMap<(u64, blake3::Hash), usize> = HashMap::new();
+    for file in &chunked {
+        let mut hasher = blake3::Hasher::new();
+        for (id, _) in &file.chunks {
+            hasher.update(id);
+        }
+        *whole.entry((file.size, hasher.finalize())).or_default() += 1;
+    }
+    let whole_file_bytes: u64 = whole
+        .iter()
+        .map(|((size, _), copies)| size * (*copies as u64 - 1))
+        .sum();
+
+    let percent = |part: u64, of: u64| part as f64 * 100.0 / of.max(1) as f64;
+    println!(
+        "\n{} Split {} file(s), {} into {} chunk(s) of {} on average",
+        "=>".yellow().bold(),
+        chunked.len().to_string().cyan(),
+        format_size(read_bytes),
+        chunk_count.to_string().cyan(),
+        format_size(AVERAGE_CHUNK as u64)
+    );
+    println!(
+        "  Distinct chunks:             {} ({})",
+        seen.len(),
+        format_size(unique_bytes)
+    );
+    println!(
+        "  Chunk dedup would save:      {} ({:.1}%)",
+        format_size(saved_bytes).red().bold(),
+        percent(saved_bytes, read_bytes)
+    );
+    println!(
+        "  Whole-file dedup would save: {} ({:.1}%)",
+        format_size(whole_file_bytes),
+        percent(whole_file_bytes, read_bytes)
+    );
+
+    let mut sharing: Vec<(&Chunked, u64)> = chunked
+        .iter()
+        .map(|file| {
+            let shared = file
+                .chunks
+                .iter()
+                .filter(|(id, _)| seen[id].files > 1)
+                .map(|(_, length)| *length as u64)
+                .sum();
+            (file, shared)
+        })
+        .filter(|(_, shared)| *shared > 0)
+        .collect();
+    sharing.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.path.cmp(b.0.path)));
+    if !sharing.is_empty() {
+        println!(
+            "\n{} Files sharing the most with others:",
+            "##".blue().bold()
+        );
+        for (file, shared) in sharing.iter().take(TOP_FILES) {
+            println!(
+                "  {} of {} ({:.0}%)  {}",
+                format_size(*shared).yellow(),
+                format_size(file.size),
+                percent(*shared, file.size),
+                file.path.display()
+            );
+        }
+    }
+
+    ChunkReport {
+        errors,
+        saved_bytes,
+    }
+}
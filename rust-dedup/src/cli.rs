@@ -1,21 +1,401 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LinkMode {
+    /// Replace duplicates with hardlinks (same filesystem only)
+    Hard,
+    /// Replace duplicates with symlinks to the kept copy
+    Sym,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable report, offering to act on each group
+    Text,
+    /// One JSON document with every duplicate group
+    Json,
+    /// One JSON line per duplicate group, written as soon as it's found rather than once
+    /// they all are; for trees with too many duplicates to hold in memory
+    Ndjson,
+    /// One row per file, grouped by hash
+    Csv,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeepStrategy {
+    /// The first copy found, under the earliest root given
+    First,
+    /// The copy modified longest ago
+    Oldest,
+    /// The copy modified most recently
+    Newest,
+    /// The copy with the shortest path
+    ShortestPath,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortOrder {
+    /// Most wasted space first
+    Size,
+    /// Most copies first
+    Count,
+    /// By the path of the copy kept
+    Path,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScriptKind {
+    /// POSIX shell, with `rm`
+    Sh,
+    /// PowerShell, with `Remove-Item`
+    Powershell,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MmapMode {
+    /// Map files of 64 MiB and up, read smaller ones
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color only when writing to a terminal and NO_COLOR isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    /// Fast cryptographic hash
+    Blake3,
+    /// Fastest; not cryptographic, but fine for spotting copies
+    Xxh3,
+    /// Slowest; matches checksums from other tools
+    Sha256,
+}
+
+impl HashAlgo {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgo::Blake3 => "blake3",
+            HashAlgo::Xxh3 => "xxh3",
+            HashAlgo::Sha256 => "sha256",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Find duplicates; the same as giving no subcommand
+    Scan(Box<ScanArgs>),
+
+    /// Act on the duplicates listed in a manifest from `scan --write-manifest`
+    Apply {
+        /// Manifest written by `scan --write-manifest`, edited or not
+        manifest: PathBuf,
+
+        #[command(flatten)]
+        action: ApplyAction,
+
+        /// Where --trash/--quarantine write the manifest used to restore
+        #[arg(long, value_name = "FILE")]
+        restore_manifest: Option<PathBuf>,
+
+        /// Give the kept copy the modification time of a duplicate it replaces, if that's older
+        #[arg(long)]
+        preserve_metadata: bool,
+
+        /// Only check the manifest against the files and show what would be done
+        #[arg(short, long)]
+        dry_run: bool,
+    },
+
+    /// Merge JSON reports from several machines or mounts into one cross-source view
+    MergeReports {
+        /// Reports written with `--output json`
+        #[arg(required = true)]
+        reports: Vec<PathBuf>,
+
+        /// Format of the merged report
+        #[arg(short, long, value_enum, default_value = "text")]
+        output: OutputFormat,
+    },
+
+    /// Put back files moved aside by --trash or --quarantine
+    Restore {
+        /// Manifest written by the run that moved them
+        manifest: PathBuf,
+    },
+}
 
 #[derive(Parser)]
-#[command(name = "rust-dedup", about = "Find and remove duplicate files")]
+#[command(
+    name = "rust-dedup",
+    about = "Find and remove duplicate files",
+    args_conflicts_with_subcommands = true,
+    after_help = "Defaults for --min-size, --algo, --exclude and --keep can be set in \
+                  ~/.config/rust-dedup/config.toml, e.g. `exclude = [\"*.tmp\"]`; flags given \
+                  here win.\n\n\
+                  Exit status: 0 if no duplicates were found, 1 if some were, 2 on errors \
+                  (including files that couldn't be scanned or hashed)"
+)]
 pub struct Args {
-    /// Directory to scan for duplicates
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// When to use colors in the output
+    #[arg(long, value_enum, default_value = "auto", global = true)]
+    pub color: ColorChoice,
+
+    /// Only print the report, warnings and errors: no banners, counts or progress bars
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+}
+
+// What `apply` does with the files a manifest lists for removal; exactly one is required
+#[derive(clap::Args)]
+#[group(required = true, multiple = false)]
+pub struct ApplyAction {
+    /// Delete them
+    #[arg(long)]
+    pub delete: bool,
+
+    /// Replace them with links to the kept copy
+    #[arg(long, value_enum)]
+    pub link: Option<LinkMode>,
+
+    /// Share the kept copy's data with them copy-on-write
+    #[arg(long)]
+    pub reflink: bool,
+
+    /// Move them to the system trash
+    #[arg(long)]
+    pub trash: bool,
+
+    /// Move them into this directory
+    #[arg(long, value_name = "DIR")]
+    pub quarantine: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct ScanArgs {
+    /// Directories to scan for duplicates; copies under earlier ones are the ones kept
     #[arg(default_value = ".")]
-    pub path: String,
+    pub paths: Vec<String>,
 
     /// Minimum file size in bytes to consider (skip tiny files)
     #[arg(short, long, default_value = "1")]
     pub min_size: u64,
 
-    /// Delete duplicates without asking (keeps the first found copy)
+    /// Maximum file size in bytes to consider (skip huge files such as VM images)
+    #[arg(long)]
+    pub max_size: Option<u64>,
+
+    /// How many directory levels below each root to descend (0 = only the root itself)
+    #[arg(long)]
+    pub max_depth: Option<usize>,
+
+    /// Only consider files with these extensions, e.g. `--ext jpg,png,raw` (case-insensitive)
+    #[arg(long, value_name = "EXT", value_delimiter = ',')]
+    pub ext: Vec<String>,
+
+    /// Leave out files and directories matching this pattern, in .gitignore syntax, e.g.
+    /// `*.tmp` or `node_modules/`. Repeat for several
+    #[arg(long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    /// Only consider files inside this directory, which can be anywhere below the roots.
+    /// Repeat to allow several
+    #[arg(long, value_name = "DIR")]
+    pub only_under: Vec<PathBuf>,
+
+    /// Only report groups of at least this many identical files, e.g. to hunt down assets
+    /// copied all over rather than ordinary pairs
+    #[arg(long, value_name = "N", default_value = "2", value_parser = clap::value_parser!(u64).range(2..))]
+    pub min_copies: u64,
+
+    /// Which copy of each group to keep; the others are the duplicates
+    #[arg(long, value_enum, default_value = "first")]
+    pub keep: KeepStrategy,
+
+    /// Delete duplicates without asking (keeps the copy --keep picks). Deleting for good
+    /// also takes --yes-really-delete; linking or moving them aside doesn't
     #[arg(short, long, default_value = "false")]
     pub force: bool,
 
+    /// Confirm that --force is to delete duplicates for good, rather than link them or move
+    /// them to the trash or a quarantine directory
+    #[arg(long, requires = "force")]
+    pub yes_really_delete: bool,
+
     /// Only show duplicates, don't offer to delete
     #[arg(short, long, default_value = "false")]
     pub dry_run: bool,
+
+    /// Number of threads used for hashing (0 = one per CPU core)
+    #[arg(short, long, default_value = "0")]
+    pub threads: usize,
+
+    /// Read at most this many MB per second while hashing, across all threads, so other
+    /// work on the machine keeps its share of the disk or network
+    #[arg(long, value_name = "MB/s", value_parser = clap::value_parser!(u64).range(1..))]
+    pub io_limit: Option<u64>,
+
+    /// Run at low CPU and IO priority, so other processes go first
+    #[arg(long)]
+    pub nice: bool,
+
+    /// Hash used to compare file contents
+    #[arg(short, long, value_enum, default_value = "blake3")]
+    pub algo: HashAlgo,
+
+    /// When to hash files through a memory map instead of reading them; falls back to
+    /// reading when a file can't be mapped
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "auto")]
+    pub mmap: MmapMode,
+
+    /// Also compare the files inside zip and tar archives (.zip, .tar, .tar.gz, .tgz), shown
+    /// as `archive.zip!/path/in/zip`. They're only reported, never deleted or linked
+    #[arg(long, conflicts_with = "watch")]
+    pub scan_archives: bool,
+
+    /// Save hashing progress to a state file as it goes (--resume=FILE, default
+    /// .rust-dedup-resume.json), and pick up from it if it's already there, so re-running an
+    /// interrupted scan doesn't hash everything again. Removed once hashing is done
+    #[arg(long, value_name = "STATE", num_args = 0..=1, require_equals = true, default_missing_value = ".rust-dedup-resume.json")]
+    pub resume: Option<PathBuf>,
+
+    /// Treat text files as duplicates when they only differ in line endings (CRLF/LF) or
+    /// trailing whitespace
+    #[arg(long, conflicts_with = "watch")]
+    pub fuzzy_text: bool,
+
+    /// Windows: also scan hidden files and directories, which are skipped otherwise
+    #[arg(long)]
+    pub include_hidden: bool,
+
+    /// Windows: also scan system files and directories, which are skipped otherwise
+    #[arg(long)]
+    pub include_system: bool,
+
+    /// Skip files not owned by the invoking user
+    #[arg(long, default_value = "false")]
+    pub owned_only: bool,
+
+    /// Skip files the invoking user can't write to
+    #[arg(long, default_value = "false")]
+    pub writable_only: bool,
+
+    /// Also leave out what .gitignore files exclude, e.g. target/ or node_modules/. Files
+    /// matched by a .dedupignore are always left out
+    #[arg(long)]
+    pub respect_gitignore: bool,
+
+    /// Follow symlinks to files and directories, e.g. network shares linked into the tree
+    #[arg(short = 'L', long, default_value = "false")]
+    pub follow_symlinks: bool,
+
+    /// Stay on the filesystem of each root instead of crossing into other mounts
+    #[arg(short = 'x', long, default_value = "false")]
+    pub one_filesystem: bool,
+
+    /// Replace duplicates with links to the kept copy instead of deleting them
+    #[arg(short, long, value_enum)]
+    pub link: Option<LinkMode>,
+
+    /// Share the kept copy's data with duplicates copy-on-write instead of deleting them.
+    /// Each stays a separate file; needs Btrfs, XFS or APFS
+    #[arg(long, conflicts_with_all = ["link", "trash", "quarantine"])]
+    pub reflink: bool,
+
+    /// Move duplicates to the system trash instead of deleting them
+    #[arg(long, conflicts_with_all = ["link", "quarantine"])]
+    pub trash: bool,
+
+    /// Move duplicates into this directory instead of deleting them
+    #[arg(long, value_name = "DIR", conflicts_with = "link")]
+    pub quarantine: Option<PathBuf>,
+
+    /// Where --trash/--quarantine write the manifest used to restore
+    #[arg(long, value_name = "FILE")]
+    pub manifest: Option<PathBuf>,
+
+    /// Give the kept copy the modification time of a duplicate it replaces, if that's older
+    #[arg(long)]
+    pub preserve_metadata: bool,
+
+    /// Afterwards, remove directories under the roots that removing duplicates left empty
+    #[arg(long, conflicts_with_all = ["link", "reflink"])]
+    pub prune_empty_dirs: bool,
+
+    /// Group files by name instead of content, wherever they are, e.g. to find copies of a
+    /// config file that have drifted apart. Only reports
+    #[arg(long, group = "name_mode", conflicts_with_all = ["force", "link", "reflink", "trash", "quarantine", "interactive_tui", "write_manifest", "emit_script", "watch", "output", "perceptual", "fuzzy_text", "scan_archives", "reference", "resume", "chunked"])]
+    pub by_name: bool,
+
+    /// Like --by-name, but only files of the same size go together
+    #[arg(long, group = "name_mode", conflicts_with_all = ["force", "link", "reflink", "trash", "quarantine", "interactive_tui", "write_manifest", "emit_script", "watch", "output", "perceptual", "fuzzy_text", "scan_archives", "reference", "resume", "chunked"])]
+    pub by_name_and_size: bool,
+
+    /// Compare names case-insensitively with --by-name or --by-name-and-size
+    #[arg(long, requires = "name_mode")]
+    pub ignore_case: bool,
+
+    /// Experimental: split files into content-defined chunks (FastCDC) and report how much
+    /// a deduplicating filesystem or backup tool could save, including on files that are
+    /// only partly the same. Only reports
+    #[arg(long, conflicts_with_all = ["force", "link", "reflink", "trash", "quarantine", "interactive_tui", "write_manifest", "emit_script", "watch", "output", "perceptual", "fuzzy_text", "scan_archives", "reference", "resume"])]
+    pub chunked: bool,
+
+    /// Write the duplicates to this file for review instead of acting on them; run
+    /// `rust-dedup apply FILE` afterwards
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["force", "link", "reflink", "trash", "quarantine", "interactive_tui"])]
+    pub write_manifest: Option<PathBuf>,
+
+    /// Only report files that already have a copy under DIR, e.g. everything in Downloads
+    /// that's also in an archive. Copies in DIR are always the ones kept, and never changed
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["interactive_tui", "watch", "perceptual"])]
+    pub reference: Option<String>,
+
+    /// Keep running after the scan and report files that show up as copies of ones already
+    /// there, e.g. in a downloads folder. Only reports; doesn't delete or link
+    #[arg(short, long, conflicts_with_all = ["force", "link", "reflink", "trash", "quarantine", "interactive_tui", "write_manifest", "output"])]
+    pub watch: bool,
+
+    /// Print a script with the commands that would delete the duplicates instead of deleting
+    /// them, e.g. to review it and run it through a change process
+    #[arg(long, value_enum, value_name = "SHELL", conflicts_with_all = ["force", "link", "reflink", "trash", "quarantine", "interactive_tui", "write_manifest", "watch", "output"])]
+    pub emit_script: Option<ScriptKind>,
+
+    /// Choose which copies to keep in a full-screen picker instead of a y/N prompt per group
+    #[arg(long, conflicts_with_all = ["force", "dry_run"])]
+    pub interactive_tui: bool,
+
+    /// Also look for images that look the same but aren't identical, e.g. re-encoded JPEGs.
+    /// They're only reported, never acted on
+    #[arg(long)]
+    pub perceptual: bool,
+
+    /// How many of the 64 perceptual hash bits two images may differ in and still match
+    #[arg(long, default_value = "8", requires = "perceptual", value_parser = clap::value_parser!(u32).range(0..=64))]
+    pub perceptual_threshold: u32,
+
+    /// Order of the groups in reports, manifests and scripts; ties are broken by path, so the
+    /// same tree always gives the same order
+    #[arg(long, value_enum, default_value = "size")]
+    pub sort: SortOrder,
+
+    /// Report format; everything but text only reports, nothing is deleted or linked
+    #[arg(short, long, value_enum, default_value = "text")]
+    pub output: OutputFormat,
 }
@@ -0,0 +1,74 @@
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::cli::{HashAlgo, KeepStrategy, ScanArgs};
+
+// Defaults for a scan, so long exclude lists don't have to be typed out every time. Keys
+// are named like the flags they stand in for:
+//
+//     min-size = 4096
+//     algo = "xxh3"
+//     exclude = ["*.tmp", "node_modules/"]
+//     keep = "oldest"
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    min_size: Option<u64>,
+    algo: Option<HashAlgo>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    keep: Option<KeepStrategy>,
+}
+
+// ~/.config/rust-dedup/config.toml, or under $XDG_CONFIG_HOME if that's set; on Windows,
+// under %APPDATA%
+pub fn path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|dir| dir.join("rust-dedup").join("config.toml"))
+}
+
+fn load() -> Result<Config, String> {
+    let Some(path) = path() else {
+        return Ok(Config::default());
+    };
+    match fs::read_to_string(&path) {
+        Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(format!("cannot read {}: {}", path.display(), e)),
+    }
+}
+
+// Fills in what wasn't given on the command line. Exclude patterns from both apply, the
+// config's first.
+pub fn apply(args: &mut ScanArgs, matches: &ArgMatches) -> Result<(), String> {
+    let config = load()?;
+    let from_command_line = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    if let Some(min_size) = config.min_size
+        && !from_command_line("min_size")
+    {
+        args.min_size = min_size;
+    }
+    if let Some(algo) = config.algo
+        && !from_command_line("algo")
+    {
+        args.algo = algo;
+    }
+    if let Some(keep) = config.keep
+        && !from_command_line("keep")
+    {
+        args.keep = keep;
+    }
+    args.exclude.splice(0..0, config.exclude);
+    Ok(())
+}
@@ -12,4 +12,4 @@ pub fn format_size(bytes: u64) -> String {
     } else {
         format!("{} B", bytes)
     }
-}
\ No newline at end of file
+}
@@ -1,64 +1,546 @@
+use indicatif::ProgressBar;
+use rayon::prelude::*;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use xxhash_rust::xxh3::Xxh3;
 
-fn hash_file(path: &Path) -> io::Result<String> {
+use crate::archive::{self, Member};
+use crate::cli::{HashAlgo, MmapMode};
+use crate::format::format_size;
+use crate::progress::{self, FileProgress};
+use crate::resume::Resume;
+use crate::scanner::file_id;
+
+// Large reads let BLAKE3 hash several chunks at once
+const BUFFER_SIZE: usize = 64 * 1024;
+// How much of a file is looked at to tell text from binary, as git and grep do
+const SNIFF_SIZE: usize = 8 * 1024;
+// Files at least this big are mapped rather than read with --mmap auto: below it the
+// mapping costs more than the copies it saves
+const MMAP_AUTO_THRESHOLD: u64 = 64 * 1024 * 1024;
+// Slices of a mapping handed to the hasher at once, so the progress bar still moves
+const MMAP_CHUNK: usize = 4 * 1024 * 1024;
+// Marks hashes of normalized text, which only ever match other normalized text
+pub const TEXT_HASH_PREFIX: &str = "text:";
+
+// Incremental content hash, fed one buffer at a time
+trait Hasher {
+    fn update(&mut self, data: &[u8]);
+    fn finish(self: Box<Self>) -> String;
+}
+
+impl Hasher for blake3::Hasher {
+    fn update(&mut self, data: &[u8]) {
+        blake3::Hasher::update(self, data);
+    }
+
+    fn finish(self: Box<Self>) -> String {
+        self.finalize().to_hex().to_string()
+    }
+}
+
+impl Hasher for Xxh3 {
+    fn update(&mut self, data: &[u8]) {
+        Xxh3::update(self, data);
+    }
+
+    // The 128-bit variant, so unrelated files practically never collide
+    fn finish(self: Box<Self>) -> String {
+        format!("{:032x}", self.digest128())
+    }
+}
+
+impl Hasher for Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+
+    fn finish(self: Box<Self>) -> String {
+        format!("{:x}", self.finalize())
+    }
+}
+
+// Passes text on as if it had LF line endings and no trailing whitespace on any line,
+// so the same document saved on different systems or by different editors hashes the same.
+// Whitespace is held back until it's known whether the line goes on after it.
+struct NormalizedText {
+    inner: Box<dyn Hasher>,
+    pending: Vec<u8>,
+    out: Vec<u8>,
+}
+
+impl NormalizedText {
+    fn new(inner: Box<dyn Hasher>) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+            out: Vec::with_capacity(BUFFER_SIZE),
+        }
+    }
+}
+
+impl Hasher for NormalizedText {
+    fn update(&mut self, data: &[u8]) {
+        self.out.clear();
+        for &byte in data {
+            match byte {
+                b' ' | b'\t' | b'\r' => self.pending.push(byte),
+                b'\n' => {
+                    self.pending.clear();
+                    self.out.push(b'\n');
+                }
+                _ => {
+                    self.out.append(&mut self.pending);
+                    self.out.push(byte);
+                }
+            }
+        }
+        self.inner.update(&self.out);
+    }
+
+    // Whitespace still held back ends the last line
+    fn finish(self: Box<Self>) -> String {
+        self.inner.finish()
+    }
+}
+
+// No NUL bytes at the start; binary formats practically always have some
+pub fn is_text(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    let mut start = Vec::with_capacity(SNIFF_SIZE);
+    if file
+        .take(SNIFF_SIZE as u64)
+        .read_to_end(&mut start)
+        .is_err()
+    {
+        return false;
+    }
+    !start.contains(&0)
+}
+
+fn new_hasher(algo: HashAlgo) -> Box<dyn Hasher> {
+    match algo {
+        HashAlgo::Blake3 => Box::new(blake3::Hasher::new()),
+        HashAlgo::Xxh3 => Box::new(Xxh3::new()),
+        HashAlgo::Sha256 => Box::new(Sha256::new()),
+    }
+}
+
+// Set once from --mmap before hashing starts; files this size and up are mapped
+static MMAP_THRESHOLD: AtomicU64 = AtomicU64::new(MMAP_AUTO_THRESHOLD);
+
+pub fn set_mmap(mode: MmapMode) {
+    let threshold = match mode {
+        MmapMode::Auto => MMAP_AUTO_THRESHOLD,
+        MmapMode::Always => 0,
+        MmapMode::Never => u64::MAX,
+    };
+    MMAP_THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+// Set once from --io-limit; bytes per second across all hashing threads, 0 for no limit
+static IO_LIMIT: AtomicU64 = AtomicU64::new(0);
+// When reading may go on without going over the limit
+static NEXT_READ: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub fn set_io_limit(mb_per_sec: Option<u64>) {
+    IO_LIMIT.store(
+        mb_per_sec.map_or(0, |mb| mb * 1024 * 1024),
+        Ordering::Relaxed,
+    );
+}
+
+// Called after every read: books its bytes against the limit and sleeps until they're
+// paid for. Time spent idle isn't saved up, so there's no burst after a pause.
+fn throttle(bytes: usize) {
+    let limit = IO_LIMIT.load(Ordering::Relaxed);
+    if limit == 0 {
+        return;
+    }
+    let now = Instant::now();
+    let until = {
+        let mut next = NEXT_READ.lock().unwrap();
+        let start = next.filter(|next| *next > now).unwrap_or(now);
+        let until = start + Duration::from_secs_f64(bytes as f64 / limit as f64);
+        *next = Some(until);
+        until
+    };
+    std::thread::sleep(until - now);
+}
+
+// Hashes straight from the page cache instead of copying through a buffer. Returns false
+// when the file can't be mapped (e.g. some network filesystems, or an empty file), so the
+// caller reads it instead.
+fn feed_mapped(file: &fs::File, hasher: &mut dyn Hasher, progress: &mut FileProgress) -> bool {
+    // SAFETY: the mapping is only read, and dropped before returning. Another process
+    // truncating the file meanwhile would fault the read, a risk every mmap-based tool takes.
+    let Ok(map) = (unsafe { memmap2::Mmap::map(file) }) else {
+        return false;
+    };
+    #[cfg(unix)]
+    map.advise(memmap2::Advice::Sequential).ok();
+    for chunk in map.chunks(MMAP_CHUNK) {
+        hasher.update(chunk);
+        throttle(chunk.len());
+        progress.advance(chunk.len() as u64);
+    }
+    true
+}
+
+fn feed(path: &Path, hasher: &mut dyn Hasher, progress: &mut FileProgress) -> io::Result<()> {
     let mut file = fs::File::open(path)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 8192];
+    let size = file.metadata()?.len();
+    if size > 0
+        && size >= MMAP_THRESHOLD.load(Ordering::Relaxed)
+        && feed_mapped(&file, hasher, progress)
+    {
+        return Ok(());
+    }
+    read_into(&mut file, hasher, progress)
+}
 
+fn read_into(
+    reader: &mut dyn Read,
+    hasher: &mut dyn Hasher,
+    progress: &mut FileProgress,
+) -> io::Result<()> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
     loop {
-        let bytes_read = file.read(&mut buffer)?;
+        let bytes_read = reader.read(&mut buffer)?;
         if bytes_read == 0 {
-            break;
+            return Ok(());
         }
         hasher.update(&buffer[..bytes_read]);
+        throttle(bytes_read);
+        progress.advance(bytes_read as u64);
     }
+}
+
+pub fn hash_file(path: &Path, size: u64, algo: HashAlgo, bar: &ProgressBar) -> io::Result<String> {
+    hash_with(path, size, new_hasher(algo), bar)
+}
+
+// Hash of the normalized text, marked with TEXT_HASH_PREFIX
+pub fn hash_text(path: &Path, size: u64, algo: HashAlgo, bar: &ProgressBar) -> io::Result<String> {
+    let hasher = Box::new(NormalizedText::new(new_hasher(algo)));
+    hash_with(path, size, hasher, bar).map(|hash| format!("{}{}", TEXT_HASH_PREFIX, hash))
+}
+
+// For contents that aren't a file of their own, e.g. an entry streamed out of an archive;
+// `name` is what the progress bar calls it
+pub fn hash_reader(
+    reader: &mut dyn Read,
+    name: &Path,
+    size: u64,
+    algo: HashAlgo,
+    bar: &ProgressBar,
+) -> io::Result<String> {
+    let mut hasher = new_hasher(algo);
+    let mut progress = FileProgress::new(bar, name, size);
+    let result = read_into(reader, hasher.as_mut(), &mut progress);
+    progress.finish();
+    result.map(|()| hasher.finish())
+}
 
-    Ok(format!("{:x}", hasher.finalize()))
+fn hash_with(
+    path: &Path,
+    size: u64,
+    mut hasher: Box<dyn Hasher>,
+    bar: &ProgressBar,
+) -> io::Result<String> {
+    let mut progress = FileProgress::new(bar, path, size);
+    let result = feed(path, hasher.as_mut(), &mut progress);
+    progress.finish();
+    result.map(|()| hasher.finish())
 }
 
-pub fn find_duplicates(files: &[PathBuf]) -> HashMap<String, Vec<PathBuf>> {
-    // Phase 1: Group by file size (fast pre-filter)
+// Phase 1: Group by file size (fast pre-filter). Hardlinks share one copy of the data,
+// so removing one frees nothing: only the first path to each inode is considered. With
+// `fuzzy_text`, text files are set apart, as they match whatever their size.
+type SizeGroups<'a> = (HashMap<u64, Vec<&'a PathBuf>>, Vec<(u64, &'a PathBuf)>);
+
+fn group_by_size(files: &[PathBuf], fuzzy_text: bool) -> SizeGroups<'_> {
     let mut size_groups: HashMap<u64, Vec<&PathBuf>> = HashMap::new();
+    let mut texts: Vec<(u64, &PathBuf)> = Vec::new();
+    let mut inodes: HashSet<(u64, u64)> = HashSet::new();
+    let mut hardlinked = 0;
     for file in files {
         if let Ok(meta) = file.metadata() {
+            if let Some(id) = file_id(&meta)
+                && !inodes.insert(id)
+            {
+                hardlinked += 1;
+                continue;
+            }
+            if fuzzy_text && is_text(file) {
+                texts.push((meta.len(), file));
+                continue;
+            }
             size_groups.entry(meta.len()).or_default().push(file);
         }
     }
+    if hardlinked > 0 {
+        progress::status(format_args!(
+            "  Skipped {} hardlink(s) to files already counted",
+            hardlinked
+        ));
+    }
+    (size_groups, texts)
+}
+
+fn thread_pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("failed to start hashing threads")
+}
+
+// Hashes a file on disk, unless an interrupted run already did
+fn hash_candidate(
+    file: &Path,
+    size: u64,
+    source: Source,
+    algo: HashAlgo,
+    bar: &ProgressBar,
+    resume: Option<&Resume>,
+) -> io::Result<String> {
+    if let Some(hash) = resume.and_then(|resume| resume.cached(file)) {
+        bar.inc(size);
+        return Ok(hash);
+    }
+    let result = if source == Source::Text {
+        hash_text(file, size, algo, bar)
+    } else {
+        hash_file(file, size, algo, bar)
+    };
+    if let (Some(resume), Ok(hash)) = (resume, &result) {
+        resume.record(file, hash);
+    }
+    result
+}
+
+fn print_throughput(total: usize, total_bytes: u64, started: Instant) {
+    if total > 0 {
+        let elapsed = started.elapsed();
+        progress::status(format_args!(
+            "  Hashed {} file(s), {} in {:.1}s ({}/s)",
+            total,
+            format_size(total_bytes),
+            elapsed.as_secs_f64(),
+            format_size((total_bytes as f64 / elapsed.as_secs_f64().max(0.001)) as u64)
+        ));
+    }
+}
+
+pub struct HashResult {
+    // Hash -> every path with that content, in scan order
+    pub duplicates: HashMap<String, Vec<PathBuf>>,
+    // Files that couldn't be read, so may have duplicates that weren't found
+    pub errors: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Source {
+    File,
+    // Compared by normalized contents
+    Text,
+    // Inside an archive, which has to be streamed to get at it
+    Member,
+}
+
+// With `fuzzy_text`, text files are compared by their normalized contents instead. Those
+// can have any size, so every text file is hashed. Archive members are compared alongside
+// the files and come after them in each group, so a file on disk is the copy kept.
+// With `resume`, files hashed by an interrupted run aren't read again.
+pub fn find_duplicates(
+    files: &[PathBuf],
+    members: &[Member],
+    threads: usize,
+    algo: HashAlgo,
+    fuzzy_text: bool,
+    resume: Option<&Resume>,
+) -> HashResult {
+    let (size_groups, texts) = group_by_size(files, fuzzy_text);
+    let mut in_archives: HashMap<u64, Vec<&PathBuf>> = HashMap::new();
+    for member in members {
+        in_archives
+            .entry(member.size)
+            .or_default()
+            .push(&member.path);
+    }
 
     // Phase 2: Only hash files that share a size with at least one other file
     let mut hash_groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
-    let candidates: Vec<&&PathBuf> = size_groups
-        .values()
-        .filter(|group| group.len() > 1)
-        .flatten()
+    let shared_size = |size: &u64| {
+        let on_disk = size_groups.get(size).map_or(0, Vec::len);
+        let archived = in_archives.get(size).map_or(0, Vec::len);
+        on_disk + archived > 1
+    };
+    let mut candidates: Vec<(u64, &PathBuf, Source)> = size_groups
+        .iter()
+        .filter(|(size, _)| shared_size(size))
+        .flat_map(|(size, group)| group.iter().map(|file| (*size, *file, Source::File)))
         .collect();
-
-    let total = candidates.len();
-    for (i, file) in candidates.iter().enumerate() {
-        print!("\r  Hashing file {}/{}", i + 1, total);
-        io::stdout().flush().ok();
-
-        match hash_file(file) {
-            Ok(hash) => {
-                hash_groups
-                    .entry(hash)
-                    .or_default()
-                    .push(file.to_path_buf());
-            }
-            Err(e) => {
-                eprintln!("\n  Warning: could not hash {}: {}", file.display(), e);
+    if texts.len() > 1 {
+        candidates.extend(
+            texts
+                .iter()
+                .map(|(size, file)| (*size, *file, Source::Text)),
+        );
+    }
+    // Grouped by archive, each streamed once for all of its members that need hashing
+    let mut by_archive: HashMap<PathBuf, Vec<(u64, &PathBuf)>> = HashMap::new();
+    for (size, group) in in_archives.iter().filter(|(size, _)| shared_size(size)) {
+        for path in group {
+            candidates.push((*size, path, Source::Member));
+            if let Some((archive, _)) = archive::split(path) {
+                by_archive.entry(archive).or_default().push((*size, path));
             }
         }
     }
 
-    if total > 0 {
-        println!();
+    if let Some(resume) = resume {
+        let on_disk: Vec<&PathBuf> = candidates
+            .iter()
+            .filter(|(_, _, source)| *source != Source::Member)
+            .map(|(_, file, _)| *file)
+            .collect();
+        resume.start(&on_disk);
+    }
+
+    let total = candidates.len();
+    let total_bytes: u64 = candidates.iter().map(|(size, _, _)| size).sum();
+    let pool = thread_pool(threads);
+
+    let started = Instant::now();
+    let bar = progress::hash_bar(total_bytes);
+    let done = AtomicUsize::new(0);
+    bar.set_prefix(format!("0/{} files", total));
+    let finished = |file: &PathBuf, result: &io::Result<String>| {
+        if let Err(e) = result {
+            bar.suspend(|| eprintln!("  Warning: could not hash {}: {}", file.display(), e));
+        }
+        let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+        bar.set_prefix(format!("{}/{} files", done, total));
+    };
+    let hashes: Vec<(&PathBuf, io::Result<String>)> = pool.install(|| {
+        let mut hashes: Vec<(&PathBuf, io::Result<String>)> = candidates
+            .par_iter()
+            .filter(|(_, _, source)| *source != Source::Member)
+            .map(|(size, file, source)| {
+                let result = hash_candidate(file, *size, *source, algo, &bar, resume);
+                finished(file, &result);
+                (*file, result)
+            })
+            .collect();
+        let archived: Vec<(&PathBuf, io::Result<String>)> = by_archive
+            .par_iter()
+            .flat_map_iter(|(archive, members)| archive::hash_members(archive, members, algo, &bar))
+            .inspect(|(file, result)| finished(file, result))
+            .collect();
+        hashes.extend(archived);
+        hashes
+    });
+    bar.finish_and_clear();
+
+    let mut errors = 0;
+    for (file, result) in hashes {
+        match result {
+            Ok(hash) => hash_groups
+                .entry(hash)
+                .or_default()
+                .push(file.to_path_buf()),
+            Err(_) => errors += 1,
+        }
     }
 
+    print_throughput(total, total_bytes, started);
+
     hash_groups.retain(|_, paths| paths.len() > 1);
-    hash_groups
-}
\ No newline at end of file
+    HashResult {
+        duplicates: hash_groups,
+        errors,
+    }
+}
+
+// Like find_duplicates for files on disk, but each group goes to `emit` as soon as every
+// file of its size is hashed, instead of all of them being collected first: for trees with
+// so many duplicates that holding them would take too much memory. Groups come in no
+// particular order, each in scan order. Returns how many files couldn't be hashed.
+pub fn stream_duplicates(
+    files: &[PathBuf],
+    threads: usize,
+    algo: HashAlgo,
+    resume: Option<&Resume>,
+    emit: impl Fn(String, Vec<PathBuf>) + Sync,
+) -> usize {
+    let (size_groups, _) = group_by_size(files, false);
+    let size_groups: Vec<(u64, Vec<&PathBuf>)> = size_groups
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .collect();
+    if let Some(resume) = resume {
+        let candidates: Vec<&PathBuf> = size_groups
+            .iter()
+            .flat_map(|(_, group)| group.iter().copied())
+            .collect();
+        resume.start(&candidates);
+    }
+
+    let total: usize = size_groups.iter().map(|(_, group)| group.len()).sum();
+    let total_bytes: u64 = size_groups
+        .iter()
+        .map(|(size, group)| size * group.len() as u64)
+        .sum();
+    let pool = thread_pool(threads);
+
+    let started = Instant::now();
+    let bar = progress::hash_bar(total_bytes);
+    let done = AtomicUsize::new(0);
+    let errors = AtomicUsize::new(0);
+    bar.set_prefix(format!("0/{} files", total));
+    pool.install(|| {
+        size_groups.par_iter().for_each(|(size, group)| {
+            let hashes: Vec<(&PathBuf, io::Result<String>)> = group
+                .par_iter()
+                .map(|file| {
+                    (
+                        *file,
+                        hash_candidate(file, *size, Source::File, algo, &bar, resume),
+                    )
+                })
+                .collect();
+            let mut by_hash: HashMap<String, Vec<PathBuf>> = HashMap::new();
+            for (file, result) in hashes {
+                match result {
+                    Ok(hash) => by_hash.entry(hash).or_default().push(file.to_path_buf()),
+                    Err(e) => {
+                        bar.suspend(|| {
+                            eprintln!("  Warning: could not hash {}: {}", file.display(), e)
+                        });
+                        errors.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+            let done = done.fetch_add(group.len(), Ordering::Relaxed) + group.len();
+            bar.set_prefix(format!("{}/{} files", done, total));
+            for (hash, paths) in by_hash {
+                if paths.len() > 1 {
+                    bar.suspend(|| emit(hash, paths));
+                }
+            }
+        });
+    });
+    bar.finish_and_clear();
+
+    print_throughput(total, total_bytes, started);
+    errors.into_inner()
+}
@@ -0,0 +1,22 @@
+//! Finds duplicate files by content and deals with them: the pipeline behind the
+//! `rust_dedup` binary, which scans with [`scanner::scan_files`], hashes with
+//! [`hasher::find_duplicates`] and reports or acts with [`reporter::report_and_handle`].
+
+pub mod archive;
+pub mod chunks;
+pub mod cli;
+pub mod config;
+pub mod format;
+pub mod hasher;
+pub mod manifest;
+pub mod merge;
+pub mod names;
+pub mod phash;
+pub mod picker;
+pub mod progress;
+pub mod quarantine;
+pub mod reporter;
+pub mod resume;
+pub mod scanner;
+pub mod script;
+pub mod watch;
@@ -1,36 +1,524 @@
-mod cli;
-mod format;
-mod hasher;
-mod reporter;
-mod scanner;
-
-use clap::Parser;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
 use colored::Colorize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
-use cli::Args;
+use cli::{ApplyAction, Args, ColorChoice, Command, LinkMode, OutputFormat, ScanArgs};
 use format::format_size;
 use hasher::find_duplicates;
-use reporter::report_and_handle;
-use scanner::scan_files;
+use quarantine::{Bin, Quarantine};
+use reporter::{
+    Action, Handled, apply_selection, print_same_names, print_similar, report_and_handle,
+    write_report,
+};
+use resume::Resume;
+use rust_dedup::{
+    archive, chunks, cli, config, format, hasher, manifest, merge, names, phash, picker, progress,
+    quarantine, reporter, resume, scanner, script, watch,
+};
+use scanner::{Filter, ScanOptions, scan_files};
+
+// Exit status, for gating scripts and CI jobs on the result
+const EXIT_NO_DUPLICATES: i32 = 0;
+const EXIT_DUPLICATES: i32 = 1;
+// Also used when the run completed but some files couldn't be looked at, since the
+// absence of duplicates can't be trusted then
+const EXIT_ERROR: i32 = 2;
+
+// Progress and status lines go to stderr and the report to stdout, so both have to be
+// terminals: either one redirected to a file would otherwise fill it with escape codes
+fn use_color(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && io::stdout().is_terminal()
+                && io::stderr().is_terminal()
+        }
+    }
+}
+
+// Moving aside takes precedence, then linking, then cloning; deleting is the default
+fn choose_action(
+    quarantine: Option<PathBuf>,
+    trash: bool,
+    link: Option<LinkMode>,
+    reflink: bool,
+    manifest: Option<PathBuf>,
+) -> Action {
+    let bin = match quarantine {
+        Some(dir) => Some(Bin::Dir(dir)),
+        None if trash => Some(Bin::Trash),
+        None => None,
+    };
+    match (bin, link) {
+        (Some(bin), _) => Action::MoveAside(Quarantine::new(bin, manifest)),
+        (None, Some(mode)) => Action::Link(mode),
+        (None, None) if reflink => Action::Reflink,
+        (None, None) => Action::Delete,
+    }
+}
+
+// Raises the niceness of this thread and the ones started after it, which is all of the
+// hashing threads, and on Linux puts its IO in the lowest best-effort class. Both are
+// hints; the scan runs either way.
+fn lower_priority() {
+    #[cfg(unix)]
+    {
+        // SAFETY: plain syscalls on the current thread, taking no pointers
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 10) } != 0 {
+            eprintln!(
+                "Warning: could not lower CPU priority: {}",
+                io::Error::last_os_error()
+            );
+        }
+        #[cfg(target_os = "linux")]
+        {
+            const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+            const IOPRIO_CLASS_BE: libc::c_long = 2;
+            const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+            let lowest = (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | 7;
+            // SAFETY: as above
+            if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, lowest) } != 0 {
+                eprintln!(
+                    "Warning: could not lower IO priority: {}",
+                    io::Error::last_os_error()
+                );
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    eprintln!("Warning: --nice is not supported on this platform");
+}
 
 fn main() {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    colored::control::set_override(use_color(args.color));
+    progress::set_quiet(args.quiet);
+
+    let result = match args.command {
+        None => std::process::exit(scan(args.scan, &matches)),
+        Some(Command::Scan(scan_args)) => {
+            let matches = matches.subcommand_matches("scan").unwrap_or(&matches);
+            std::process::exit(scan(*scan_args, matches))
+        }
+        Some(Command::Apply {
+            manifest,
+            action,
+            restore_manifest,
+            preserve_metadata,
+            dry_run,
+        }) => {
+            reporter::set_preserve_metadata(preserve_metadata);
+            let ApplyAction {
+                delete: _,
+                link,
+                reflink,
+                trash,
+                quarantine,
+            } = action;
+            let mut action = choose_action(quarantine, trash, link, reflink, restore_manifest);
+            manifest::apply(&manifest, &mut action, dry_run)
+        }
+        Some(Command::MergeReports { reports, output }) => merge::merge_reports(&reports, output),
+        Some(Command::Restore { manifest }) => quarantine::restore(&manifest),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(EXIT_ERROR);
+    }
+}
 
-    println!(
+// `matches` are the ones the arguments were parsed from, to tell flags given from defaults
+fn scan(mut args: ScanArgs, matches: &ArgMatches) -> i32 {
+    if let Err(e) = config::apply(&mut args, matches) {
+        eprintln!("Error: {}", e);
+        return EXIT_ERROR;
+    }
+    if let Some(max) = args.max_size
+        && max < args.min_size
+    {
+        eprintln!(
+            "Error: --max-size ({}) is below --min-size ({})",
+            max, args.min_size
+        );
+        return EXIT_ERROR;
+    }
+
+    // A script that got --force by mistake stops here, before anything is even scanned
+    let deletes = args.link.is_none() && !args.reflink && !args.trash && args.quarantine.is_none();
+    if args.force && deletes && !args.dry_run && !args.yes_really_delete {
+        eprintln!(
+            "Error: --force deletes duplicates for good; add --yes-really-delete to confirm, \
+             or use --trash or --quarantine DIR to keep them recoverable"
+        );
+        return EXIT_ERROR;
+    }
+
+    if args.output == OutputFormat::Ndjson
+        && (args.fuzzy_text
+            || args.scan_archives
+            || args.perceptual
+            || args.reference.is_some()
+            || args.write_manifest.is_some())
+    {
+        eprintln!(
+            "Error: --output ndjson only streams exact matches between files on disk; it can't be \
+             combined with --fuzzy-text, --scan-archives, --perceptual, --reference or --write-manifest"
+        );
+        return EXIT_ERROR;
+    }
+
+    // Before anything is read, and before the hashing threads are started
+    if args.nice {
+        lower_priority();
+    }
+
+    // Progress goes to stderr so stdout carries only the report, e.g. when piping JSON
+    progress::status(format_args!(
         "{} Scanning {} ...",
         "=>".blue().bold(),
-        args.path.bold()
-    );
+        args.paths.join(", ").bold()
+    ));
+
+    let mut filters = Vec::new();
+    if !args.ext.is_empty() {
+        let extensions = args
+            .ext
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_lowercase());
+        filters.push(Filter::Extensions(extensions.collect()));
+    }
+    if !args.only_under.is_empty() {
+        // Compared against canonical file paths, so this has to be one too
+        let dirs: Result<Vec<PathBuf>, String> = args
+            .only_under
+            .iter()
+            .map(|dir| {
+                fs::canonicalize(dir).map_err(|e| format!("--only-under {}: {}", dir.display(), e))
+            })
+            .collect();
+        match dirs {
+            Ok(dirs) => filters.push(Filter::Under(dirs)),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return EXIT_ERROR;
+            }
+        }
+    }
 
-    let files = scan_files(&args.path, args.min_size);
-    println!(
-        "  Found {} file(s) (min size: {})",
+    let options = ScanOptions {
+        min_size: args.min_size,
+        max_size: args.max_size,
+        max_depth: args.max_depth,
+        owned_only: args.owned_only,
+        writable_only: args.writable_only,
+        follow_symlinks: args.follow_symlinks,
+        one_filesystem: args.one_filesystem,
+        filters,
+        scan_archives: args.scan_archives,
+        respect_gitignore: args.respect_gitignore,
+        excludes: args.exclude.clone(),
+        include_hidden: args.include_hidden,
+        include_system: args.include_system,
+    };
+    let scan = scan_files(&args.paths, &options);
+    let mut files = scan.files;
+    let resume = args
+        .resume
+        .as_deref()
+        .map(|path| Resume::open(path, args.algo, args.fuzzy_text));
+    if let Some(resume) = &resume {
+        files.retain(|file| !resume.is_own(file));
+    }
+    let max_size = args
+        .max_size
+        .map(|max| format!(", max size: {}", format_size(max)))
+        .unwrap_or_default();
+    progress::status(format_args!(
+        "  Found {} file(s) (min size: {}{})",
         files.len().to_string().cyan(),
-        format_size(args.min_size)
+        format_size(args.min_size),
+        max_size
+    ));
+    if scan.excluded_not_owned > 0 {
+        progress::status(format_args!(
+            "  Excluded {} file(s) owned by other users",
+            scan.excluded_not_owned.to_string().yellow()
+        ));
+    }
+    if scan.excluded_not_writable > 0 {
+        progress::status(format_args!(
+            "  Excluded {} file(s) you can't write to",
+            scan.excluded_not_writable.to_string().yellow()
+        ));
+    }
+
+    let mut errors = scan.errors;
+    // Scanned like the rest but listed first, so its copies are the ones kept. What's in it
+    // is only compared, so whether it could be deleted doesn't matter.
+    let reference: Option<HashSet<PathBuf>> = args.reference.as_ref().map(|dir| {
+        let reference_options = ScanOptions {
+            owned_only: false,
+            writable_only: false,
+            ..options.clone()
+        };
+        let mut scanned = scan_files(std::slice::from_ref(dir), &reference_options);
+        if let Some(resume) = &resume {
+            scanned.files.retain(|file| !resume.is_own(file));
+        }
+        errors += scanned.errors;
+        progress::status(format_args!(
+            "  Found {} file(s) in the reference {}",
+            scanned.files.len().to_string().cyan(),
+            dir.bold()
+        ));
+        let reference: HashSet<PathBuf> = scanned.files.iter().cloned().collect();
+        files.retain(|file| !reference.contains(file));
+        files.splice(0..0, scanned.files);
+        reference
+    });
+
+    let archived = archive::scan_archives(&scan.archives, &options);
+    if args.scan_archives {
+        progress::status(format_args!(
+            "  Found {} file(s) inside {} archive(s)",
+            archived.members.len().to_string().cyan(),
+            scan.archives.len()
+        ));
+    }
+
+    if args.by_name || args.by_name_and_size {
+        progress::status(format_args!(
+            "{} Looking for files with the same name...",
+            "=>".blue().bold()
+        ));
+        let groups = names::find_same_names(
+            &files,
+            args.ignore_case,
+            args.by_name_and_size,
+            args.min_copies as usize,
+        );
+        print_same_names(&groups);
+        return if errors > 0 {
+            EXIT_ERROR
+        } else if groups.is_empty() {
+            EXIT_NO_DUPLICATES
+        } else {
+            EXIT_DUPLICATES
+        };
+    }
+
+    if args.chunked {
+        progress::status(format_args!(
+            "{} Looking for shared chunks...",
+            "=>".blue().bold()
+        ));
+        let chunked = chunks::report(&files, args.threads);
+        return if errors + chunked.errors > 0 {
+            EXIT_ERROR
+        } else if chunked.saved_bytes > 0 {
+            EXIT_DUPLICATES
+        } else {
+            EXIT_NO_DUPLICATES
+        };
+    }
+
+    progress::status(format_args!(
+        "{} Looking for duplicates ({})...",
+        "=>".blue().bold(),
+        args.algo.name()
+    ));
+    hasher::set_mmap(args.mmap);
+    hasher::set_io_limit(args.io_limit);
+
+    // Written to stdout as each group is found, so they're never all held at once
+    if args.output == OutputFormat::Ndjson {
+        let found = AtomicUsize::new(0);
+        let write_failed = AtomicBool::new(false);
+        let min_copies = args.min_copies as usize;
+        let hash_errors = hasher::stream_duplicates(
+            &files,
+            args.threads,
+            args.algo,
+            resume.as_ref(),
+            |hash, mut paths| {
+                if paths.len() < min_copies {
+                    return;
+                }
+                reporter::choose_keep_in(&mut paths, args.keep);
+                found.fetch_add(1, Ordering::Relaxed);
+                if let Err(e) = reporter::write_group_line(&mut io::stdout().lock(), &hash, &paths)
+                    && !write_failed.swap(true, Ordering::Relaxed)
+                {
+                    eprintln!("Error writing report: {}", e);
+                }
+            },
+        );
+        if let Some(resume) = resume {
+            resume.finish();
+        }
+        return if errors + hash_errors > 0 || write_failed.into_inner() {
+            EXIT_ERROR
+        } else if found.into_inner() == 0 {
+            EXIT_NO_DUPLICATES
+        } else {
+            EXIT_DUPLICATES
+        };
+    }
+
+    let hashed = find_duplicates(
+        &files,
+        &archived.members,
+        args.threads,
+        args.algo,
+        args.fuzzy_text,
+        resume.as_ref(),
     );
+    if let Some(resume) = resume {
+        resume.finish();
+    }
+    let mut duplicates = hashed.duplicates;
+    reporter::choose_keep(&mut duplicates, args.keep);
+    // Only copies of something in the reference are left, and only outside of it. The copy
+    // kept is the reference's that --keep likes best.
+    if let Some(reference) = &reference {
+        duplicates.retain(|_, paths| {
+            let Some(keep) = paths.iter().find(|path| reference.contains(*path)).cloned() else {
+                return false;
+            };
+            paths.retain(|path| !reference.contains(path));
+            paths.insert(0, keep);
+            paths.len() > 1
+        });
+    }
+
+    // Extra copies of an exact match would only repeat it among the similar images, even
+    // when the match has too few copies to be reported
+    let similar = args.perceptual.then(|| {
+        progress::status(format_args!(
+            "{} Looking for similar images...",
+            "=>".blue().bold()
+        ));
+        let copies: HashSet<&PathBuf> = duplicates.values().flat_map(|paths| &paths[1..]).collect();
+        let distinct: Vec<PathBuf> = files
+            .iter()
+            .filter(|f| !copies.contains(f))
+            .cloned()
+            .collect();
+        phash::find_similar(&distinct, args.threads, args.perceptual_threshold)
+    });
+
+    // Smaller groups aren't reported or acted on, and don't count towards the exit status
+    let min_copies = args.min_copies as usize;
+    duplicates.retain(|_, paths| paths.len() >= min_copies);
+    let mut exit_code = if errors + archived.errors + hashed.errors > 0 {
+        EXIT_ERROR
+    } else if duplicates.is_empty() {
+        EXIT_NO_DUPLICATES
+    } else {
+        EXIT_DUPLICATES
+    };
+
+    if let Some(path) = &args.write_manifest {
+        match manifest::write(path, &duplicates, args.sort, args.algo) {
+            Ok(groups) => progress::status(format_args!(
+                "{} Wrote {} group(s) to {}; review it, then run `rust-dedup apply {} --delete`",
+                "=>".blue().bold(),
+                groups.to_string().cyan(),
+                path.display(),
+                path.display()
+            )),
+            Err(e) => {
+                eprintln!("Error writing manifest {}: {}", path.display(), e);
+                return EXIT_ERROR;
+            }
+        }
+    }
 
-    println!("{} Looking for duplicates...", "=>".blue().bold());
-    let duplicates = find_duplicates(&files);
+    if let Some(kind) = args.emit_script {
+        if let Err(e) = script::write_script(&duplicates, kind, args.sort, &mut io::stdout().lock())
+        {
+            eprintln!("Error writing script: {}", e);
+            return EXIT_ERROR;
+        }
+        return exit_code;
+    }
 
-    report_and_handle(&duplicates, args.dry_run, args.force);
-}
\ No newline at end of file
+    match args.output {
+        OutputFormat::Text => {
+            reporter::set_preserve_metadata(args.preserve_metadata);
+            let mut action = choose_action(
+                args.quarantine,
+                args.trash,
+                args.link,
+                args.reflink,
+                args.manifest,
+            );
+            let roots: Vec<PathBuf> = args.paths.iter().map(PathBuf::from).collect();
+            let prune = args.prune_empty_dirs.then_some(roots.as_slice());
+            let handled = if args.interactive_tui && !duplicates.is_empty() {
+                match picker::pick(&duplicates, action.verb()) {
+                    Ok(Some(selection)) => apply_selection(&selection, &mut action, prune),
+                    Ok(None) => {
+                        println!("{}", "Nothing changed.".dimmed());
+                        Handled::default()
+                    }
+                    Err(e) => {
+                        eprintln!("Error running the picker: {}", e);
+                        return EXIT_ERROR;
+                    }
+                }
+            } else {
+                // With a manifest written, acting is left to `apply`; watching only reports
+                let dry_run = args.dry_run || args.write_manifest.is_some() || args.watch;
+                report_and_handle(
+                    &duplicates,
+                    args.sort,
+                    dry_run,
+                    args.force,
+                    &mut action,
+                    prune,
+                )
+            };
+            // A duplicate that couldn't be deleted, linked or moved is an error like any other
+            if handled.failed > 0 {
+                exit_code = EXIT_ERROR;
+            }
+            if let Some(similar) = &similar {
+                print_similar(similar);
+            }
+        }
+        format => {
+            if let Err(e) = write_report(
+                &duplicates,
+                format,
+                args.sort,
+                args.algo,
+                similar.as_deref(),
+            ) {
+                eprintln!("Error writing report: {}", e);
+                return EXIT_ERROR;
+            }
+        }
+    }
+    if args.watch
+        && let Err(e) = watch::watch(
+            &args.paths,
+            &options,
+            args.algo,
+            min_copies,
+            &files,
+            &duplicates,
+        )
+    {
+        eprintln!("Error: {}", e);
+        return EXIT_ERROR;
+    }
+    exit_code
+}
@@ -0,0 +1,225 @@
+use colored::Colorize;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::cli::{HashAlgo, SortOrder};
+use crate::hasher::{TEXT_HASH_PREFIX, hash_file, hash_text};
+use crate::picker::Selection;
+use crate::progress;
+use crate::quarantine::now_secs;
+use crate::reporter::{Action, apply_selection, sorted_groups};
+use crate::scanner::file_id;
+
+// Bumped whenever a field changes meaning, so an older `apply` refuses a newer manifest
+// instead of misreading it
+const SCHEMA_VERSION: u32 = 1;
+
+// What `rust-dedup apply` will do, written by `scan --write-manifest` for someone to review
+// first. Editing it is expected: swap the `keep` path with one from `remove`, or drop paths
+// and whole groups that should be left alone. Not to be confused with the restore manifest
+// of --trash/--quarantine, which records what was already done.
+#[derive(Serialize, Deserialize)]
+struct DupesManifest {
+    version: u32,
+    created_at: u64,
+    // The hashes below are checked again with the same algorithm before anything is touched
+    algorithm: HashAlgo,
+    groups: Vec<ManifestGroup>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestGroup {
+    hash: String,
+    size: u64,
+    keep: PathBuf,
+    remove: Vec<PathBuf>,
+}
+
+// Absolute paths, so the manifest can be applied from any working directory
+pub fn absolute(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Writes every duplicate group with its first copy marked to keep, returning how many.
+// Copies inside archives are left out, as nothing can be done with them.
+pub fn write(
+    path: &Path,
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+    order: SortOrder,
+    algo: HashAlgo,
+) -> io::Result<usize> {
+    // Same order as the reports, so the two are easy to compare
+    let groups: Vec<ManifestGroup> = sorted_groups(duplicates, order)
+        .into_iter()
+        .filter_map(|(hash, paths)| {
+            let paths: Vec<&PathBuf> = paths.iter().filter(|p| !archive::is_member(p)).collect();
+            (paths.len() > 1).then(|| ManifestGroup {
+                hash: hash.clone(),
+                size: archive::size(paths[0]),
+                keep: absolute(paths[0]),
+                remove: paths[1..].iter().map(|p| absolute(p)).collect(),
+            })
+        })
+        .collect();
+    let manifest = DupesManifest {
+        version: SCHEMA_VERSION,
+        created_at: now_secs(),
+        algorithm: algo,
+        groups,
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(io::Error::other)?;
+    fs::write(path, json)?;
+    Ok(manifest.groups.len())
+}
+
+fn read(path: &Path) -> Result<DupesManifest, String> {
+    let bytes = fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let manifest: DupesManifest = serde_json::from_slice(&bytes).map_err(|e| {
+        format!(
+            "{} is not a rust-dedup duplicates manifest: {}",
+            path.display(),
+            e
+        )
+    })?;
+    if manifest.version > SCHEMA_VERSION {
+        return Err(format!(
+            "{} was written by a newer rust-dedup (manifest version {}, this one reads up to {})",
+            path.display(),
+            manifest.version,
+            SCHEMA_VERSION
+        ));
+    }
+    Ok(manifest)
+}
+
+// Every listed file must still have the size and content it had when scanned: anything
+// edited, replaced or gone since then may no longer be a duplicate of the kept copy.
+// Returns the files to remove, which leaves out any that are the kept file itself.
+fn verify(
+    group: &ManifestGroup,
+    algo: HashAlgo,
+    bar: &ProgressBar,
+) -> Result<Vec<PathBuf>, String> {
+    let keep_meta =
+        fs::metadata(&group.keep).map_err(|e| format!("{}: {}", group.keep.display(), e))?;
+    let keep_id = file_id(&keep_meta);
+    let keep_canonical = absolute(&group.keep);
+    // A hand edit can name the kept file again, e.g. through a symlinked directory or a
+    // hardlink, and removing that would remove the copy being kept
+    let (same, remove): (Vec<&PathBuf>, Vec<&PathBuf>) = group.remove.iter().partition(|path| {
+        absolute(path) == keep_canonical
+            || (keep_id.is_some() && fs::metadata(path).ok().as_ref().and_then(file_id) == keep_id)
+    });
+    for path in same {
+        bar.suspend(|| {
+            eprintln!(
+                "  {} {}: the same file as {}",
+                "Skipped".yellow(),
+                path.display(),
+                group.keep.display()
+            )
+        });
+        bar.inc(group.size);
+    }
+    // Copies found with --fuzzy-text may differ in size, and are checked the same way again
+    let text = group.hash.starts_with(TEXT_HASH_PREFIX);
+    for path in std::iter::once(&group.keep).chain(remove.iter().copied()) {
+        let size = fs::metadata(path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?
+            .len();
+        if size != group.size && !text {
+            bar.inc(group.size);
+            return Err(format!("{} changed size since the scan", path.display()));
+        }
+        let hash = if text {
+            hash_text(path, size, algo, bar)
+        } else {
+            hash_file(path, size, algo, bar)
+        };
+        let hash = hash.map_err(|e| format!("{}: {}", path.display(), e))?;
+        if hash != group.hash {
+            return Err(format!("{} changed since the scan", path.display()));
+        }
+    }
+    Ok(remove.into_iter().cloned().collect())
+}
+
+pub fn apply(path: &Path, action: &mut Action, dry_run: bool) -> Result<(), String> {
+    let manifest = read(path)?;
+    let groups: Vec<&ManifestGroup> = manifest
+        .groups
+        .iter()
+        .filter(|g| !g.remove.is_empty())
+        .collect();
+
+    progress::status(format_args!(
+        "{} Checking {} group(s) against the files ({})...",
+        "=>".blue().bold(),
+        groups.len().to_string().cyan(),
+        manifest.algorithm.name()
+    ));
+    let total_bytes: u64 = groups
+        .iter()
+        .map(|g| g.size * (g.remove.len() as u64 + 1))
+        .sum();
+    let bar = progress::hash_bar(total_bytes);
+    let checked: Vec<Result<Vec<PathBuf>, String>> = groups
+        .par_iter()
+        .map(|group| verify(group, manifest.algorithm, &bar))
+        .collect();
+    bar.finish_and_clear();
+
+    let mut selection = Vec::new();
+    let mut skipped = 0;
+    for (group, result) in groups.iter().zip(checked) {
+        match result {
+            Ok(remove) => selection.push(Selection {
+                keep: group.keep.clone(),
+                remove,
+                size: group.size,
+            }),
+            Err(reason) => {
+                eprintln!(
+                    "  {} group {}: {}",
+                    "Skipped".yellow(),
+                    &group.hash[..group.hash.len().min(12)],
+                    reason
+                );
+                skipped += 1;
+            }
+        }
+    }
+
+    if dry_run {
+        let verb = action.verb().to_lowercase();
+        for group in &selection {
+            println!("\n{} Keeping {}", "##".blue().bold(), group.keep.display());
+            for dupe in &group.remove {
+                println!("  Would {}: {}", verb, dupe.display());
+            }
+        }
+    } else {
+        let handled = apply_selection(&selection, action, None);
+        if handled.failed > 0 {
+            return Err(format!(
+                "couldn't {} {} duplicate(s)",
+                action.verb().to_lowercase(),
+                handled.failed
+            ));
+        }
+    }
+
+    if skipped > 0 {
+        return Err(format!(
+            "{} group(s) skipped because their files changed since the scan; scan again to include them",
+            skipped
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::{HashAlgo, OutputFormat};
+use crate::format::format_size;
+
+// The parts of a `--output json` report needed to merge it; other fields are ignored
+#[derive(Deserialize)]
+struct SourceReport {
+    // Reports from before --algo existed were all SHA-256
+    #[serde(default = "legacy_algorithm")]
+    algorithm: HashAlgo,
+    groups: Vec<SourceGroup>,
+}
+
+fn legacy_algorithm() -> HashAlgo {
+    HashAlgo::Sha256
+}
+
+#[derive(Deserialize)]
+struct SourceGroup {
+    hash: String,
+    size: u64,
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct SourcedPath {
+    // The report this copy was listed in
+    source: String,
+    path: PathBuf,
+}
+
+#[derive(Serialize)]
+struct MergedGroup {
+    hash: String,
+    size: u64,
+    wasted_bytes: u64,
+    // How many reports hold a copy
+    sources: usize,
+    copies: Vec<SourcedPath>,
+}
+
+#[derive(Serialize)]
+struct MergedReport {
+    algorithm: HashAlgo,
+    reports: Vec<String>,
+    total_groups: usize,
+    total_wasted_bytes: u64,
+    groups: Vec<MergedGroup>,
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    group: usize,
+    hash: &'a str,
+    size: u64,
+    sources: usize,
+    source: &'a str,
+    path: &'a Path,
+}
+
+fn read_report(path: &Path) -> Result<SourceReport, String> {
+    let bytes = fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| format!("{} is not a rust-dedup JSON report: {}", path.display(), e))
+}
+
+// Groups from every report that share hash and size are the same content. Each report only
+// lists files that were duplicated within its own source, so a file held once per source
+// doesn't show up here.
+fn merge(reports: &[PathBuf]) -> Result<MergedReport, String> {
+    let mut merged: HashMap<(String, u64), Vec<SourcedPath>> = HashMap::new();
+    let mut algorithm: Option<(HashAlgo, &Path)> = None;
+    for report_path in reports {
+        let source = report_path.display().to_string();
+        let report = read_report(report_path)?;
+        // Hashes from different algorithms never match, which would hide every overlap
+        match algorithm {
+            Some((first, first_path)) if first != report.algorithm => {
+                return Err(format!(
+                    "{} was hashed with {} but {} with {}; rerun with the same --algo",
+                    first_path.display(),
+                    first.name(),
+                    report_path.display(),
+                    report.algorithm.name()
+                ));
+            }
+            Some(_) => {}
+            None => algorithm = Some((report.algorithm, report_path)),
+        }
+        for group in report.groups {
+            let copies = merged.entry((group.hash, group.size)).or_default();
+            copies.extend(group.paths.into_iter().map(|path| SourcedPath {
+                source: source.clone(),
+                path,
+            }));
+        }
+    }
+
+    let mut groups: Vec<MergedGroup> = merged
+        .into_iter()
+        .map(|((hash, size), copies)| {
+            let mut sources: Vec<&str> = copies.iter().map(|c| c.source.as_str()).collect();
+            sources.sort_unstable();
+            sources.dedup();
+            MergedGroup {
+                sources: sources.len(),
+                wasted_bytes: size * (copies.len() as u64 - 1),
+                hash,
+                size,
+                copies,
+            }
+        })
+        .collect();
+    // Content spread over the most sources first, then by hash for a stable order
+    groups.sort_by(|a, b| b.sources.cmp(&a.sources).then_with(|| a.hash.cmp(&b.hash)));
+
+    Ok(MergedReport {
+        algorithm: algorithm.map_or(HashAlgo::Blake3, |(algo, _)| algo),
+        reports: reports.iter().map(|r| r.display().to_string()).collect(),
+        total_groups: groups.len(),
+        total_wasted_bytes: groups.iter().map(|g| g.wasted_bytes).sum(),
+        groups,
+    })
+}
+
+fn print_text(report: &MergedReport) {
+    let cross_source = report.groups.iter().filter(|g| g.sources > 1).count();
+    println!(
+        "{} Merged {} report(s): {} duplicate group(s), {} spanning several sources, wasting {}",
+        "=>".yellow().bold(),
+        report.reports.len().to_string().cyan(),
+        report.total_groups.to_string().cyan(),
+        cross_source.to_string().cyan(),
+        format_size(report.total_wasted_bytes).red().bold()
+    );
+
+    for (i, group) in report.groups.iter().enumerate() {
+        println!(
+            "\n{} Group {} — {} each, {} copies in {} source(s):",
+            "##".blue().bold(),
+            (i + 1).to_string().bold(),
+            format_size(group.size).yellow(),
+            group.copies.len(),
+            group.sources
+        );
+        for copy in &group.copies {
+            println!(
+                "  {} {}",
+                format!("[{}]", copy.source).dimmed(),
+                copy.path.display()
+            );
+        }
+    }
+}
+
+pub fn merge_reports(reports: &[PathBuf], format: OutputFormat) -> Result<(), String> {
+    let report = merge(reports)?;
+    let write_error = |e: io::Error| format!("cannot write the merged report: {}", e);
+
+    match format {
+        OutputFormat::Text => print_text(&report),
+        OutputFormat::Json => {
+            let mut out = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut out, &report).map_err(|e| write_error(e.into()))?;
+            writeln!(out).map_err(write_error)?;
+        }
+        OutputFormat::Ndjson => {
+            let mut out = io::stdout().lock();
+            for group in &report.groups {
+                serde_json::to_writer(&mut out, group).map_err(|e| write_error(e.into()))?;
+                writeln!(out).map_err(write_error)?;
+            }
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout().lock());
+            for (i, group) in report.groups.iter().enumerate() {
+                for copy in &group.copies {
+                    writer
+                        .serialize(CsvRow {
+                            group: i + 1,
+                            hash: &group.hash,
+                            size: group.size,
+                            sources: group.sources,
+                            source: &copy.source,
+                            path: &copy.path,
+                        })
+                        .map_err(|e| write_error(e.into()))?;
+                }
+            }
+            writer.flush().map_err(write_error)?;
+        }
+    }
+    Ok(())
+}
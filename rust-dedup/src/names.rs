@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub struct NameGroup {
+    // As the first file found spells it
+    pub name: String,
+    // Only with the sizes compared too; what they all have
+    pub size: Option<u64>,
+    pub paths: Vec<PathBuf>,
+}
+
+// Files sharing a name wherever they are, whatever is in them: copies of a config file
+// that have drifted apart, say, which a content hash can't match. With `with_size`, only
+// files of the same size too.
+pub fn find_same_names(
+    files: &[PathBuf],
+    ignore_case: bool,
+    with_size: bool,
+    min_copies: usize,
+) -> Vec<NameGroup> {
+    let mut by_name: HashMap<(String, Option<u64>), NameGroup> = HashMap::new();
+    for file in files {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        let key_name = if ignore_case {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        };
+        let size = if with_size {
+            match file.metadata() {
+                Ok(meta) => Some(meta.len()),
+                // Gone since the scan
+                Err(_) => continue,
+            }
+        } else {
+            None
+        };
+        by_name
+            .entry((key_name, size))
+            .or_insert_with(|| NameGroup {
+                name: name.to_string(),
+                size,
+                paths: Vec::new(),
+            })
+            .paths
+            .push(file.clone());
+    }
+
+    let mut groups: Vec<NameGroup> = by_name
+        .into_values()
+        .filter(|group| group.paths.len() >= min_copies)
+        .collect();
+    groups.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.size.cmp(&b.size)));
+    groups
+}
@@ -0,0 +1,112 @@
+use image::ImageFormat;
+use image::imageops::FilterType;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::progress;
+
+#[derive(Serialize)]
+pub struct SimilarImage {
+    pub path: PathBuf,
+    // Differing hash bits (of 64) from the first image in the group
+    pub distance: u32,
+}
+
+#[derive(Serialize)]
+pub struct SimilarGroup {
+    pub images: Vec<SimilarImage>,
+}
+
+// Files the enabled decoders can read, going by extension
+fn is_image(path: &Path) -> bool {
+    ImageFormat::from_path(path).is_ok_and(|format| format.reading_enabled())
+}
+
+// dHash: shrink to 9x8 grayscale and record whether each pixel is brighter than its right
+// neighbour. Re-encoding, resizing and small color shifts barely move those gradients.
+fn dhash(path: &Path) -> image::ImageResult<u64> {
+    let pixels = image::open(path)?
+        .resize_exact(9, 8, FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = pixels.get_pixel(x, y)[0] > pixels.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | brighter as u64;
+        }
+    }
+    Ok(hash)
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+// Groups images whose hashes are at most `threshold` bits apart. Similarity is chained, so
+// a group can hold two images further apart than that through one between them.
+pub fn find_similar(files: &[PathBuf], threads: usize, threshold: u32) -> Vec<SimilarGroup> {
+    let images: Vec<&PathBuf> = files.iter().filter(|f| is_image(f)).collect();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("failed to start hashing threads");
+
+    let bar = progress::image_bar(images.len() as u64);
+    let hashed: Vec<(&PathBuf, u64)> = pool.install(|| {
+        images
+            .par_iter()
+            .filter_map(|path| {
+                let hash = dhash(path);
+                bar.inc(1);
+                match hash {
+                    Ok(hash) => Some((*path, hash)),
+                    Err(e) => {
+                        bar.suspend(|| {
+                            eprintln!("  Warning: could not decode {}: {}", path.display(), e)
+                        });
+                        None
+                    }
+                }
+            })
+            .collect()
+    });
+    bar.finish_and_clear();
+
+    // Every pair is compared; with 64-bit hashes that stays quick for tens of thousands
+    let mut parents: Vec<usize> = (0..hashed.len()).collect();
+    for i in 0..hashed.len() {
+        for j in i + 1..hashed.len() {
+            if (hashed[i].1 ^ hashed[j].1).count_ones() <= threshold {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[b.max(a)] = a.min(b);
+            }
+        }
+    }
+
+    // Members stay in scan order, so the first image of a group is from the earliest root
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); hashed.len()];
+    for i in 0..hashed.len() {
+        members[find_root(&mut parents, i)].push(i);
+    }
+    members
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| {
+            let first = hashed[group[0]].1;
+            SimilarGroup {
+                images: group
+                    .into_iter()
+                    .map(|i| SimilarImage {
+                        path: hashed[i].0.clone(),
+                        distance: (hashed[i].1 ^ first).count_ones(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,317 @@
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+use crate::archive;
+use crate::format::format_size;
+
+// What the user chose for one group: the copy everything else is handled against,
+// and the files to delete (or link, trash, ...)
+pub struct Selection {
+    pub keep: PathBuf,
+    pub remove: Vec<PathBuf>,
+    pub size: u64,
+}
+
+struct Group {
+    size: u64,
+    paths: Vec<PathBuf>,
+    remove: Vec<bool>,
+    // Copies inside an archive: shown, but never removed, nor the one copy kept
+    member: Vec<bool>,
+}
+
+impl Group {
+    // Copies on disk that stay
+    fn kept(&self) -> usize {
+        self.remove
+            .iter()
+            .zip(&self.member)
+            .filter(|(remove, member)| !**remove && !**member)
+            .count()
+    }
+}
+
+struct Picker {
+    groups: Vec<Group>,
+    // (group, file) for every file row, in display order
+    rows: Vec<(usize, usize)>,
+    cursor: usize,
+    verb: &'static str,
+    confirming: bool,
+    message: Option<&'static str>,
+}
+
+impl Picker {
+    fn new(duplicates: &HashMap<String, Vec<PathBuf>>, verb: &'static str) -> Self {
+        let mut groups: Vec<Group> = duplicates
+            .values()
+            .map(|paths| {
+                let size = archive::size(&paths[0]);
+                let member: Vec<bool> = paths.iter().map(|p| archive::is_member(p)).collect();
+                // Same default as the prompt: keep the first copy found on disk
+                let first = member.iter().position(|m| !m);
+                let remove = member
+                    .iter()
+                    .enumerate()
+                    .map(|(i, m)| !m && Some(i) != first)
+                    .collect();
+                Group {
+                    size,
+                    paths: paths.clone(),
+                    remove,
+                    member,
+                }
+            })
+            .collect();
+        // Biggest savings first
+        groups.sort_by_key(|g| std::cmp::Reverse(g.size * (g.paths.len() as u64 - 1)));
+        let rows = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(g, group)| (0..group.paths.len()).map(move |f| (g, f)))
+            .collect();
+        Self {
+            groups,
+            rows,
+            cursor: 0,
+            verb,
+            confirming: false,
+            message: None,
+        }
+    }
+
+    fn marked(&self) -> (usize, u64) {
+        self.groups.iter().fold((0, 0), |(count, bytes), g| {
+            let n = g.remove.iter().filter(|r| **r).count();
+            (count + n, bytes + n as u64 * g.size)
+        })
+    }
+
+    fn toggle(&mut self) {
+        let (g, f) = self.rows[self.cursor];
+        let group = &mut self.groups[g];
+        if group.member[f] {
+            self.message = Some("Copies inside an archive are left as they are");
+            return;
+        }
+        if !group.remove[f] && group.kept() == 1 {
+            self.message = Some("Every group keeps at least one copy");
+            return;
+        }
+        group.remove[f] = !group.remove[f];
+    }
+
+    fn keep_only(&mut self) {
+        let (g, f) = self.rows[self.cursor];
+        let group = &mut self.groups[g];
+        if group.member[f] {
+            self.message = Some("A copy inside an archive can't be the only one kept");
+            return;
+        }
+        for (i, (remove, member)) in group.remove.iter_mut().zip(&group.member).enumerate() {
+            *remove = i != f && !member;
+        }
+    }
+
+    fn keep_all(&mut self) {
+        let (g, _) = self.rows[self.cursor];
+        self.groups[g].remove.fill(false);
+    }
+
+    // Moves to the first file of the next (or previous) group
+    fn jump_group(&mut self, forward: bool) {
+        let (g, _) = self.rows[self.cursor];
+        let target = if forward {
+            (g + 1).min(self.groups.len() - 1)
+        } else {
+            g.saturating_sub(1)
+        };
+        if let Some(row) = self.rows.iter().position(|(rg, _)| *rg == target) {
+            self.cursor = row;
+        }
+    }
+
+    fn selection(self) -> Vec<Selection> {
+        self.groups
+            .into_iter()
+            .map(|group| {
+                let mut keep = None;
+                let mut remove = Vec::new();
+                for ((path, marked), member) in
+                    group.paths.iter().zip(group.remove).zip(group.member)
+                {
+                    match (marked, member, &keep) {
+                        (false, false, None) => keep = Some(path.clone()),
+                        (true, _, _) => remove.push(path.clone()),
+                        _ => {}
+                    }
+                }
+                // A group found only inside archives has nothing to act on
+                let keep = keep.unwrap_or_else(|| group.paths[0].clone());
+                Selection {
+                    keep,
+                    remove,
+                    size: group.size,
+                }
+            })
+            .collect()
+    }
+
+    fn render(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(1),
+                Constraint::Length(3),
+            ])
+            .split(f.area());
+
+        let (count, bytes) = self.marked();
+        let header = Paragraph::new(format!(
+            "{} group(s) — {} file(s) marked, {}",
+            self.groups.len(),
+            count,
+            format_size(bytes)
+        ))
+        .block(Block::default().title(" rust-dedup ").borders(Borders::ALL));
+        f.render_widget(header, chunks[0]);
+
+        // Group headings aren't selectable, so track which list item the cursor lands on
+        let mut items = Vec::new();
+        let mut selected = 0;
+        let mut row = 0;
+        for (g, group) in self.groups.iter().enumerate() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!(
+                    "Group {} — {} each, {} copies",
+                    g + 1,
+                    format_size(group.size),
+                    group.paths.len()
+                ),
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::BOLD),
+            ))));
+            for ((path, remove), member) in group.paths.iter().zip(&group.remove).zip(&group.member)
+            {
+                if row == self.cursor {
+                    selected = items.len();
+                }
+                let (label, color) = if *member {
+                    ("[archived]".to_string(), Color::DarkGray)
+                } else if *remove {
+                    (format!("[{}]", self.verb.to_lowercase()), Color::Red)
+                } else {
+                    ("[keep]".to_string(), Color::Green)
+                };
+                items.push(ListItem::new(Line::from(vec![
+                    Span::styled(format!("  {:<12}", label), Style::default().fg(color)),
+                    Span::raw(path.display().to_string()),
+                ])));
+                row += 1;
+            }
+        }
+        let mut state = ListState::default().with_selected(Some(selected));
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+
+        let footer = if self.confirming {
+            Span::styled(
+                format!(
+                    "{} {} file(s), {}? [y/N]",
+                    self.verb,
+                    count,
+                    format_size(bytes)
+                ),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if let Some(message) = self.message {
+            Span::styled(message, Style::default().fg(Color::Yellow))
+        } else {
+            Span::raw(
+                "↑/↓ move  PgUp/PgDn group  space toggle  o keep only this  a keep all  enter confirm  q quit",
+            )
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(footer)).block(Block::default().borders(Borders::ALL)),
+            chunks[2],
+        );
+    }
+
+    // Some(selection) once confirmed, None if the user quits
+    fn run(
+        mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> io::Result<Option<Vec<Selection>>> {
+        loop {
+            terminal.draw(|f| self.render(f))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            self.message = None;
+
+            if self.confirming {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(Some(self.selection())),
+                    _ => self.confirming = false,
+                }
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.cursor = (self.cursor + 1).min(self.rows.len() - 1)
+                }
+                KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+                KeyCode::PageDown => self.jump_group(true),
+                KeyCode::PageUp => self.jump_group(false),
+                KeyCode::Char(' ') => self.toggle(),
+                KeyCode::Char('o') => self.keep_only(),
+                KeyCode::Char('a') => self.keep_all(),
+                KeyCode::Enter => self.confirming = true,
+                _ => {}
+            }
+        }
+    }
+}
+
+// Lets the user pick, per group, which copies to keep before anything is touched
+pub fn pick(
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+    verb: &'static str,
+) -> io::Result<Option<Vec<Selection>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = Picker::new(duplicates, verb).run(&mut terminal);
+
+    // Restore the terminal whatever happened in the picker
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
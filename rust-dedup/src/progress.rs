@@ -0,0 +1,132 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::format::format_size;
+
+// How often a worker reports on the file it's in the middle of
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+// Set once from --quiet before any output
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+// Banners and counts on stderr that only say how the run is going; warnings and errors
+// are printed directly so --quiet never hides them
+pub fn status(line: impl Display) {
+    if !quiet() {
+        eprintln!("{}", line);
+    }
+}
+
+// The bars draw on stderr and stay hidden when it isn't a terminal, so logs and pipes
+// only get the status lines
+
+// Follows --color like the rest of the output
+fn bar_style(template: &str) -> ProgressStyle {
+    let bar = if colored::control::SHOULD_COLORIZE.should_colorize() {
+        "{bar:30.cyan/blue}"
+    } else {
+        "{bar:30}"
+    };
+    ProgressStyle::with_template(&template.replace("{bar}", bar))
+        .expect("valid progress template")
+        .progress_chars("=> ")
+}
+
+// Walking has no known total, so this only counts what's been found so far
+pub fn scan_spinner() -> ProgressBar {
+    if quiet() {
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner().with_style(
+        ProgressStyle::with_template("  {spinner} Scanning: {pos} file(s) found {wide_msg}")
+            .expect("valid progress template"),
+    );
+    spinner.enable_steady_tick(Duration::from_millis(120));
+    spinner
+}
+
+// Measured in bytes rather than files: one large file can take longer than thousands of
+// small ones, and the ETA should reflect that
+pub fn hash_bar(total_bytes: u64) -> ProgressBar {
+    if quiet() {
+        return ProgressBar::hidden();
+    }
+    ProgressBar::new(total_bytes).with_style(bar_style(
+        "  Hashing [{bar}] {bytes}/{total_bytes} ({bytes_per_sec}, ETA {eta}) {prefix} {wide_msg}",
+    ))
+}
+
+// One file's way through a hash bar. Big files can take minutes, so every REPORT_INTERVAL
+// the bar's message shows the file and how far into it the reading is; with several
+// workers, that's whichever reported last.
+pub struct FileProgress<'a> {
+    bar: &'a ProgressBar,
+    path: &'a Path,
+    size: u64,
+    done: u64,
+    last_report: Instant,
+}
+
+impl<'a> FileProgress<'a> {
+    pub fn new(bar: &'a ProgressBar, path: &'a Path, size: u64) -> Self {
+        Self {
+            bar,
+            path,
+            size,
+            done: 0,
+            last_report: Instant::now(),
+        }
+    }
+
+    pub fn advance(&mut self, bytes: u64) {
+        self.done += bytes;
+        self.bar.inc(bytes);
+        if self.last_report.elapsed() >= REPORT_INTERVAL {
+            self.last_report = Instant::now();
+            self.bar.set_message(format!(
+                "{} ({} / {})",
+                shorten(self.path, 50),
+                format_size(self.done),
+                format_size(self.size)
+            ));
+        }
+    }
+
+    // Whatever wasn't read (an error, or a file that shrank since the scan) still counts
+    // as done, or the bar would never finish
+    pub fn finish(self) {
+        self.bar.inc(self.size.saturating_sub(self.done));
+    }
+}
+
+// Keeps the end of long paths, where the file name is
+fn shorten(path: &Path, max: usize) -> String {
+    let display = path.display().to_string();
+    let count = display.chars().count();
+    if count <= max {
+        return display;
+    }
+    let tail: String = display.chars().skip(count - (max - 1)).collect();
+    format!("…{}", tail)
+}
+
+// Decoding one image takes about as long as another, so this one counts files
+pub fn image_bar(total: u64) -> ProgressBar {
+    if quiet() {
+        return ProgressBar::hidden();
+    }
+    ProgressBar::new(total).with_style(bar_style(
+        "  Decoding images [{bar}] {pos}/{len} (ETA {eta})",
+    ))
+}
@@ -0,0 +1,286 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::scanner::long_path;
+
+// Where duplicates go instead of being deleted
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bin {
+    /// The operating system's trash / recycle bin
+    Trash,
+    /// A directory of the user's choosing, mirroring each file's original path
+    Dir(PathBuf),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    original: PathBuf,
+    kept: PathBuf,
+    // Where the file was moved to; the system trash keeps track of that itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stored: Option<PathBuf>,
+}
+
+// Everything one run moved aside, so `rust-dedup restore` can put it back
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    created_at: u64,
+    bin: Bin,
+    entries: Vec<Entry>,
+}
+
+pub struct Quarantine {
+    path: PathBuf,
+    manifest: Manifest,
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Renames where possible, otherwise copies and removes the source (e.g. across devices)
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let (from, to) = (long_path(from), long_path(to));
+    if fs::rename(&from, &to).is_ok() {
+        return Ok(());
+    }
+    // Across filesystems a symlink is recreated, rather than copied as the file it points to
+    #[cfg(unix)]
+    if fs::symlink_metadata(&from)?.file_type().is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(&from)?, &to)?;
+        return fs::remove_file(&from).inspect_err(|_| {
+            fs::remove_file(&to).ok();
+        });
+    }
+    fs::copy(&from, &to)?;
+    fs::remove_file(&from).inspect_err(|_| {
+        fs::remove_file(&to).ok();
+    })
+}
+
+// Resolves the directory but not the entry itself, so a followed symlink is moved (and
+// recorded) as the link that was scanned rather than the file it points to
+fn absolute_entry(path: &Path) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let entry = fs::canonicalize(parent)?.join(name);
+    fs::symlink_metadata(&entry)?;
+    Ok(entry)
+}
+
+impl Quarantine {
+    // The manifest defaults to the quarantine directory, or the working directory for the trash
+    pub fn new(bin: Bin, manifest: Option<PathBuf>) -> Self {
+        let created_at = now_secs();
+        // Made absolute up front, as the directory may not exist yet to be canonicalized
+        let bin = match bin {
+            Bin::Dir(dir) => Bin::Dir(std::path::absolute(&dir).unwrap_or(dir)),
+            Bin::Trash => Bin::Trash,
+        };
+        let path = manifest.unwrap_or_else(|| {
+            let name = format!("dedup-manifest-{}.json", created_at);
+            match &bin {
+                Bin::Dir(dir) => dir.join(name),
+                Bin::Trash => PathBuf::from(name),
+            }
+        });
+        Self {
+            path,
+            manifest: Manifest {
+                created_at,
+                bin,
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    pub fn bin(&self) -> &Bin {
+        &self.manifest.bin
+    }
+
+    pub fn manifest_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn moved(&self) -> usize {
+        self.manifest.entries.len()
+    }
+
+    pub fn move_aside(&mut self, keep: &Path, dupe: &Path) -> io::Result<()> {
+        // Absolute paths, so restoring works from any working directory
+        let original = absolute_entry(dupe)?;
+        let kept = fs::canonicalize(keep)?;
+        let stored = match &self.manifest.bin {
+            Bin::Trash => {
+                trash::delete(&original).map_err(io::Error::other)?;
+                None
+            }
+            Bin::Dir(dir) => {
+                let relative: PathBuf = original
+                    .components()
+                    .filter(|c| matches!(c, Component::Normal(_)))
+                    .collect();
+                let mut stored = dir.join(relative);
+                // A previous run may have quarantined a file from the same path
+                if stored.exists() {
+                    let mut name = stored.file_name().unwrap_or_default().to_os_string();
+                    name.push(format!(".{}", self.manifest.created_at));
+                    stored.set_file_name(name);
+                }
+                move_file(&original, &stored)?;
+                Some(stored)
+            }
+        };
+        self.manifest.entries.push(Entry {
+            original,
+            kept,
+            stored,
+        });
+        Ok(())
+    }
+
+    // Written after every group, so an interrupted run can still be undone
+    pub fn save(&self) -> io::Result<()> {
+        if self.manifest.entries.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_vec_pretty(&self.manifest).map_err(io::Error::other)?;
+        fs::write(&self.path, json)
+    }
+}
+
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn restore_from_trash(originals: &[&Path]) -> Result<(), String> {
+    use std::collections::HashMap;
+
+    let items = trash::os_limited::list().map_err(|e| format!("cannot read the trash: {}", e))?;
+    // The same path may have been trashed more than once; bring back the latest
+    let mut latest: HashMap<PathBuf, trash::TrashItem> = HashMap::new();
+    for item in items {
+        let path = item.original_path();
+        if !originals.contains(&path.as_path()) {
+            continue;
+        }
+        if latest
+            .get(&path)
+            .is_none_or(|seen| seen.time_deleted < item.time_deleted)
+        {
+            latest.insert(path, item);
+        }
+    }
+    if latest.len() < originals.len() {
+        for original in originals {
+            if !latest.contains_key(*original) {
+                eprintln!(
+                    "  {} {} is no longer in the trash",
+                    "Missing:".yellow(),
+                    original.display()
+                );
+            }
+        }
+    }
+    trash::os_limited::restore_all(latest.into_values())
+        .map_err(|e| format!("cannot restore from the trash: {}", e))
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn restore_from_trash(_originals: &[&Path]) -> Result<(), String> {
+    Err("this platform's trash can't be restored from programmatically; use the system's trash instead".to_string())
+}
+
+pub fn restore(manifest_path: &Path) -> Result<(), String> {
+    let bytes = fs::read(manifest_path)
+        .map_err(|e| format!("cannot read {}: {}", manifest_path.display(), e))?;
+    let manifest: Manifest = serde_json::from_slice(&bytes).map_err(|e| {
+        format!(
+            "{} is not a rust-dedup manifest: {}",
+            manifest_path.display(),
+            e
+        )
+    })?;
+
+    // Never overwrite something that has since appeared at the original path
+    let (pending, occupied): (Vec<&Entry>, Vec<&Entry>) = manifest
+        .entries
+        .iter()
+        .partition(|entry| !entry.original.exists());
+    for entry in &occupied {
+        eprintln!(
+            "  {} {} already exists",
+            "Skipped:".yellow(),
+            entry.original.display()
+        );
+    }
+
+    let mut restored = 0usize;
+    match &manifest.bin {
+        Bin::Trash => {
+            let originals: Vec<&Path> = pending.iter().map(|e| e.original.as_path()).collect();
+            restore_from_trash(&originals)?;
+            for original in originals.iter().filter(|p| p.exists()) {
+                println!("  {} {}", "Restored:".green(), original.display());
+                restored += 1;
+            }
+        }
+        Bin::Dir(_) => {
+            for entry in pending {
+                let Some(stored) = &entry.stored else {
+                    continue;
+                };
+                match move_file(stored, &entry.original) {
+                    Ok(()) => {
+                        println!("  {} {}", "Restored:".green(), entry.original.display());
+                        restored += 1;
+                    }
+                    Err(e) => eprintln!("  Error restoring {}: {}", entry.original.display(), e),
+                }
+            }
+        }
+    }
+
+    println!(
+        "\n{} Restored {} of {} file(s)",
+        "=>".green().bold(),
+        restored.to_string().cyan(),
+        manifest.entries.len()
+    );
+    if restored < manifest.entries.len() {
+        return Err("some files could not be restored".to_string());
+    }
+    Ok(())
+}
@@ -1,19 +1,587 @@
 use colored::Colorize;
-use std::collections::HashMap;
-use std::fs;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, Metadata};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
 
+use crate::archive;
+use crate::cli::{HashAlgo, KeepStrategy, LinkMode, OutputFormat, SortOrder};
 use crate::format::format_size;
+use crate::names::NameGroup;
+use crate::phash::SimilarGroup;
+use crate::picker::Selection;
+use crate::quarantine::{Bin, Quarantine};
+use crate::scanner::long_path;
 
+#[cfg(unix)]
+fn same_device(a: &Path, b: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(fs::metadata(a)?.dev() == fs::metadata(b)?.dev())
+}
+
+#[cfg(not(unix))]
+fn same_device(_a: &Path, _b: &Path) -> io::Result<bool> {
+    // fs::hard_link reports cross-volume links itself here
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    // Directories can't be opened for syncing here; rename is still atomic
+    Ok(())
+}
+
+// Set once from --preserve-metadata before anything is acted on
+static PRESERVE_METADATA: AtomicBool = AtomicBool::new(false);
+
+pub fn set_preserve_metadata(preserve: bool) {
+    PRESERVE_METADATA.store(preserve, Ordering::Relaxed);
+}
+
+#[cfg(unix)]
+fn timespec(time: SystemTime) -> Option<libc::timespec> {
+    let since = time.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some(libc::timespec {
+        tv_sec: since.as_secs() as libc::time_t,
+        tv_nsec: since.subsec_nanos() as _,
+    })
+}
+
+// Sets access and modification times; on a symlink, the link's own. Unlike opening the
+// file to set them, this works on read-only files too.
+#[cfg(unix)]
+fn set_times(path: &Path, accessed: SystemTime, modified: SystemTime) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let (Some(atime), Some(mtime)) = (timespec(accessed), timespec(modified)) else {
+        // Before the epoch; not worth the trouble
+        return Ok(());
+    };
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let times = [atime, mtime];
+    // SAFETY: a valid NUL-terminated path and the two timespecs utimensat reads
+    let result = unsafe {
+        libc::utimensat(
+            libc::AT_FDCWD,
+            c_path.as_ptr(),
+            times.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_times(path: &Path, accessed: SystemTime, modified: SystemTime) -> io::Result<()> {
+    let times = fs::FileTimes::new()
+        .set_accessed(accessed)
+        .set_modified(modified);
+    fs::File::options().write(true).open(path)?.set_times(times)
+}
+
+// Puts the replaced duplicate's owner and timestamps back on what now sits at its path, so
+// listings and backups don't see it as new. Best effort: only root can give a file to
+// someone else, for one. The mode comes along where the replacement has one of its own.
+fn restore_metadata(path: &Path, original: &Metadata) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::os::unix::fs::lchown(path, Some(original.uid()), Some(original.gid())).ok();
+    }
+    if let (Ok(accessed), Ok(modified)) = (original.accessed(), original.modified()) {
+        set_times(path, accessed, modified).ok();
+    }
+}
+
+// With --preserve-metadata the kept copy takes the older modification time, so it still
+// dates from when the content first turned up
+fn keep_older_mtime(keep: &Path, dupe: &Metadata) {
+    let Ok(kept) = fs::metadata(keep) else {
+        return;
+    };
+    if let (Ok(accessed), Ok(kept_modified), Ok(dupe_modified)) =
+        (kept.accessed(), kept.modified(), dupe.modified())
+        && dupe_modified < kept_modified
+    {
+        set_times(keep, accessed, dupe_modified).ok();
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn reflink_error(e: io::Error) -> io::Error {
+    match e.raw_os_error() {
+        Some(libc::EXDEV) => {
+            io::Error::other("kept copy is on another filesystem, reflinks can't cross devices")
+        }
+        Some(libc::EOPNOTSUPP | libc::ENOTTY | libc::EINVAL) => io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the filesystem doesn't support reflinks (Btrfs, XFS and APFS do); try --link hard",
+        ),
+        _ => e,
+    }
+}
+
+// Clones in place, so the duplicate keeps its inode, owner and permissions. The kernel
+// swaps the extents atomically: the file has either its old data or the shared one.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn reflink(keep: &Path, dupe: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+    let src = fs::File::open(keep)?;
+    let dst = fs::OpenOptions::new().write(true).open(dupe)?;
+    if unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE, src.as_raw_fd()) } != 0 {
+        return Err(reflink_error(io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+// clonefile only creates new files, so the clone takes the duplicate's place like a link would
+#[cfg(target_os = "macos")]
+fn reflink(keep: &Path, dupe: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let src = CString::new(keep.as_os_str().as_bytes())?;
+    let permissions = fs::metadata(dupe)?.permissions();
+    replace_with(keep, dupe, |temp| {
+        let dst = CString::new(temp.as_os_str().as_bytes())?;
+        if unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) } != 0 {
+            return Err(reflink_error(io::Error::last_os_error()));
+        }
+        if let Err(e) = fs::set_permissions(temp, permissions) {
+            fs::remove_file(temp).ok();
+            return Err(e);
+        }
+        Ok(())
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn reflink(_keep: &Path, _dupe: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reflinks aren't supported on this platform",
+    ))
+}
+
+// Swaps `dupe` for whatever `create` makes at a temporary path in the same directory.
+// The order is: create, check it against the kept copy, make the directory entry durable,
+// then rename over the duplicate. Until the rename the duplicate is untouched, and any
+// failure before it removes the temporary again, so a crash at any point leaves either the
+// duplicate or its replacement at the path, never neither.
+fn replace_with(
+    keep: &Path,
+    dupe: &Path,
+    create: impl FnOnce(&Path) -> io::Result<()>,
+) -> io::Result<()> {
+    let dir = match dupe.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = dupe.file_name().unwrap_or_default().to_string_lossy();
+    let temp = dir.join(format!(".{}.dedup-{}.tmp", name, std::process::id()));
+
+    create(&temp)?;
+    let swapped = (|| {
+        // Following the replacement must land on the kept copy's contents
+        if fs::metadata(&temp)?.len() != fs::metadata(keep)?.len() {
+            return Err(io::Error::other("replacement doesn't match the kept copy"));
+        }
+        sync_dir(dir)?;
+        fs::rename(long_path(&temp), long_path(dupe))
+    })();
+    if let Err(e) = swapped {
+        fs::remove_file(long_path(&temp)).ok();
+        return Err(e);
+    }
+    sync_dir(dir)
+}
+
+// Replaces `dupe` with a link to `keep`
+fn link_duplicate(mode: LinkMode, keep: &Path, dupe: &Path) -> io::Result<()> {
+    match mode {
+        LinkMode::Hard => {
+            if !same_device(keep, dupe)? {
+                return Err(io::Error::other(
+                    "kept copy is on another filesystem, hardlinks can't cross devices",
+                ));
+            }
+            replace_with(keep, dupe, |temp| fs::hard_link(keep, temp))
+        }
+        LinkMode::Sym => {
+            // Absolute target so the link stays valid wherever the duplicate lives
+            let target = fs::canonicalize(keep)?;
+            replace_with(keep, dupe, |temp| symlink(&target, temp))
+        }
+    }
+}
+
+// What happens to each confirmed duplicate
+pub enum Action {
+    Delete,
+    Link(LinkMode),
+    // Duplicate data shared copy-on-write with the kept copy; both stay separate files
+    Reflink,
+    // Moved to the trash or a quarantine directory, recorded for `rust-dedup restore`
+    MoveAside(Quarantine),
+}
+
+impl Action {
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Action::Delete => "Delete",
+            Action::Link(LinkMode::Hard) => "Hardlink",
+            Action::Link(LinkMode::Sym) => "Symlink",
+            Action::Reflink => "Reflink",
+            Action::MoveAside(q) => match q.bin() {
+                Bin::Trash => "Trash",
+                Bin::Dir(_) => "Quarantine",
+            },
+        }
+    }
+
+    // Whether handled duplicates are gone from their directories, rather than replaced
+    fn removes_files(&self) -> bool {
+        matches!(self, Action::Delete | Action::MoveAside(_))
+    }
+}
+
+fn handle_duplicate(action: &mut Action, keep: &Path, dupe: &Path) -> bool {
+    // Only reported: it would take rewriting the whole archive
+    if archive::is_member(dupe) {
+        println!(
+            "  {} {}: inside an archive",
+            "Skipped".dimmed(),
+            dupe.display()
+        );
+        return false;
+    }
+    // Checked again right before, as the kept copy may have gone since the scan or the manifest
+    if let Err(e) = fs::metadata(keep) {
+        eprintln!(
+            "  Error: kept copy {} is gone ({}), leaving {}",
+            keep.display(),
+            e,
+            dupe.display()
+        );
+        return false;
+    }
+    let original = fs::symlink_metadata(dupe);
+    let result = match action {
+        Action::Delete => fs::remove_file(long_path(dupe)),
+        Action::Link(mode) => link_duplicate(*mode, keep, dupe),
+        Action::Reflink => reflink(keep, dupe),
+        Action::MoveAside(quarantine) => quarantine.move_aside(keep, dupe),
+    };
+    let done = match action {
+        Action::Delete => "Deleted:".red(),
+        Action::Link(LinkMode::Hard) => "Hardlinked:".cyan(),
+        Action::Link(LinkMode::Sym) => "Symlinked:".cyan(),
+        Action::Reflink => "Reflinked:".cyan(),
+        Action::MoveAside(q) => match q.bin() {
+            Bin::Trash => "Trashed:".yellow(),
+            Bin::Dir(_) => "Quarantined:".yellow(),
+        },
+    };
+    match result {
+        Ok(()) => {
+            if let Ok(original) = &original {
+                // A hardlink is the kept copy itself, so there's nothing of the duplicate's
+                // left to keep; what replaces it otherwise is a file of its own
+                if matches!(action, Action::Link(LinkMode::Sym) | Action::Reflink) {
+                    restore_metadata(dupe, original);
+                }
+                if PRESERVE_METADATA.load(Ordering::Relaxed) {
+                    keep_older_mtime(keep, original);
+                }
+            }
+            println!("  {} {}", done, dupe.display());
+            true
+        }
+        Err(e) => {
+            let doing = match action {
+                Action::Delete => "deleting",
+                Action::Link(_) => "linking",
+                Action::Reflink => "cloning",
+                Action::MoveAside(_) => "moving",
+            };
+            eprintln!("  Error {} {}: {}", doing, dupe.display(), e);
+            false
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GroupReport<'a> {
+    hash: &'a str,
+    size: u64,
+    wasted_bytes: u64,
+    // The first path is the copy that would be kept
+    paths: &'a [PathBuf],
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    // Hashes are only comparable between reports made with the same one
+    algorithm: HashAlgo,
+    total_groups: usize,
+    total_wasted_bytes: u64,
+    groups: Vec<GroupReport<'a>>,
+    // Only with --perceptual; kept apart from the exact matches above
+    #[serde(skip_serializing_if = "Option::is_none")]
+    similar_images: Option<&'a [SimilarGroup]>,
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    group: usize,
+    hash: &'a str,
+    size: u64,
+    wasted_bytes: u64,
+    keep: bool,
+    path: &'a Path,
+}
+
+// Moves the copy `keep` picks to the front of each group, where the copy kept goes; the
+// others stay in scan order. Ties go to whichever was found first, and a copy inside an
+// archive is never picked, as it can't be kept on its own.
+pub fn choose_keep(duplicates: &mut HashMap<String, Vec<PathBuf>>, keep: KeepStrategy) {
+    for paths in duplicates.values_mut() {
+        choose_keep_in(paths, keep);
+    }
+}
+
+// The same for a single group
+pub fn choose_keep_in(paths: &mut Vec<PathBuf>, keep: KeepStrategy) {
+    let modified = |path: &PathBuf| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let on_disk = paths
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| !archive::is_member(path));
+    let chosen = match keep {
+        KeepStrategy::First => None,
+        KeepStrategy::Oldest => on_disk
+            .filter_map(|(i, path)| Some((i, modified(path)?)))
+            .min_by_key(|(_, time)| *time)
+            .map(|(i, _)| i),
+        KeepStrategy::Newest => on_disk
+            .filter_map(|(i, path)| Some((i, modified(path)?)))
+            .min_by_key(|(_, time)| std::cmp::Reverse(*time))
+            .map(|(i, _)| i),
+        KeepStrategy::ShortestPath => on_disk
+            .min_by_key(|(_, path)| path.as_os_str().len())
+            .map(|(i, _)| i),
+    };
+    if let Some(i) = chosen {
+        let kept = paths.remove(i);
+        paths.insert(0, kept);
+    }
+}
+
+// One group as a line of NDJSON, shaped like the groups of the JSON report
+pub fn write_group_line(out: &mut impl Write, hash: &str, paths: &[PathBuf]) -> io::Result<()> {
+    let size = archive::size(&paths[0]);
+    let group = GroupReport {
+        hash,
+        size,
+        wasted_bytes: size * (paths.len() as u64 - 1),
+        paths,
+    };
+    serde_json::to_writer(&mut *out, &group)?;
+    writeln!(out)
+}
+
+// Duplicate groups in the order they're reported in, whatever the format
+pub fn sorted_groups(
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+    order: SortOrder,
+) -> Vec<(&String, &Vec<PathBuf>)> {
+    let mut groups: Vec<(u64, &String, &Vec<PathBuf>)> = duplicates
+        .iter()
+        .map(|(hash, paths)| {
+            (
+                archive::size(&paths[0]) * (paths.len() as u64 - 1),
+                hash,
+                paths,
+            )
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        let first = match order {
+            SortOrder::Size => b.0.cmp(&a.0),
+            SortOrder::Count => b.2.len().cmp(&a.2.len()),
+            SortOrder::Path => std::cmp::Ordering::Equal,
+        };
+        // The hash only decides between groups keeping the same file, i.e. a file and its
+        // normalized text with --fuzzy-text
+        first
+            .then_with(|| a.2[0].cmp(&b.2[0]))
+            .then_with(|| a.1.cmp(b.1))
+    });
+    groups
+        .into_iter()
+        .map(|(_, hash, paths)| (hash, paths))
+        .collect()
+}
+
+fn group_reports(
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+    order: SortOrder,
+) -> Vec<GroupReport<'_>> {
+    sorted_groups(duplicates, order)
+        .into_iter()
+        .map(|(hash, paths)| {
+            let size = archive::size(&paths[0]);
+            GroupReport {
+                hash,
+                size,
+                wasted_bytes: size * (paths.len() as u64 - 1),
+                paths,
+            }
+        })
+        .collect()
+}
+
+// Machine-readable report on stdout, for piping into other tools or archiving
+pub fn write_report(
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+    format: OutputFormat,
+    order: SortOrder,
+    algorithm: HashAlgo,
+    similar: Option<&[SimilarGroup]>,
+) -> io::Result<()> {
+    let groups = group_reports(duplicates, order);
+    let stdout = io::stdout().lock();
+
+    match format {
+        OutputFormat::Json => {
+            let report = Report {
+                algorithm,
+                total_groups: groups.len(),
+                total_wasted_bytes: groups.iter().map(|g| g.wasted_bytes).sum(),
+                groups,
+                similar_images: similar,
+            };
+            let mut out = stdout;
+            serde_json::to_writer_pretty(&mut out, &report)?;
+            writeln!(out)
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(stdout);
+            for (i, group) in groups.iter().enumerate() {
+                for (j, path) in group.paths.iter().enumerate() {
+                    writer.serialize(CsvRow {
+                        group: i + 1,
+                        hash: group.hash,
+                        size: group.size,
+                        wasted_bytes: group.wasted_bytes,
+                        keep: j == 0,
+                        path,
+                    })?;
+                }
+            }
+            writer.flush()
+        }
+        OutputFormat::Text => unreachable!("text reports are printed by report_and_handle"),
+        OutputFormat::Ndjson => unreachable!("NDJSON is streamed as groups are found"),
+    }
+}
+
+// Removes directories the action left empty, deepest first so a parent emptied by removing
+// its last subdirectory goes too. The roots themselves are never removed. In a dry run the
+// files are still there, so it works out which directories would end up empty instead.
+fn prune_empty_dirs(roots: &[PathBuf], removed: &[&PathBuf], dry_run: bool) {
+    let mut gone: HashSet<PathBuf> = removed.iter().map(|p| p.to_path_buf()).collect();
+    let mut candidates: Vec<PathBuf> = removed
+        .iter()
+        .flat_map(|p| p.ancestors().skip(1))
+        .filter(|dir| {
+            roots
+                .iter()
+                .any(|root| dir.starts_with(root) && *dir != root.as_path())
+        })
+        .map(Path::to_path_buf)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    candidates.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+    let mut pruned = 0;
+    for dir in candidates {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let empty = entries
+            .filter_map(Result::ok)
+            .all(|entry| gone.contains(&entry.path()));
+        if !empty {
+            continue;
+        }
+        if dry_run {
+            println!(
+                "  {} {}",
+                "Would remove empty directory:".dimmed(),
+                dir.display()
+            );
+        } else if let Err(e) = fs::remove_dir(&dir) {
+            eprintln!("  Error removing empty directory {}: {}", dir.display(), e);
+            continue;
+        } else {
+            println!("  {} {}", "Removed empty directory:".red(), dir.display());
+        }
+        gone.insert(dir);
+        pruned += 1;
+    }
+    if pruned > 0 && !dry_run {
+        println!(
+            "{} Removed {} empty director{}",
+            "=>".green().bold(),
+            pruned.to_string().cyan(),
+            if pruned == 1 { "y" } else { "ies" }
+        );
+    }
+}
+
+// Duplicates the action succeeded on, the space they took up, and the ones it failed on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Handled {
+    pub files: u64,
+    pub bytes: u64,
+    pub failed: u64,
+}
+
+// With `prune` set to the scan roots, directories emptied under them are removed afterwards.
+// Nothing is handled in a dry run.
 pub fn report_and_handle(
     duplicates: &HashMap<String, Vec<PathBuf>>,
+    order: SortOrder,
     dry_run: bool,
     force: bool,
-) {
+    action: &mut Action,
+    prune: Option<&[PathBuf]>,
+) -> Handled {
     if duplicates.is_empty() {
         println!("{}", "No duplicates found!".green().bold());
-        return;
+        return Handled::default();
     }
 
     let total_groups = duplicates.len();
@@ -21,7 +589,7 @@ pub fn report_and_handle(
     let wasted_bytes: u64 = duplicates
         .values()
         .map(|paths| {
-            let size = paths[0].metadata().map(|m| m.len()).unwrap_or(0);
+            let size = archive::size(&paths[0]);
             size * (paths.len() as u64 - 1)
         })
         .sum();
@@ -33,12 +601,30 @@ pub fn report_and_handle(
         total_dupes.to_string().cyan(),
         format_size(wasted_bytes).red().bold()
     );
+    // Said up front, as nothing will ask before it happens
+    if force && !dry_run {
+        let (count, bytes) = duplicates
+            .values()
+            .flat_map(|paths| paths[1..].iter().filter(|path| !archive::is_member(path)))
+            .fold((0u64, 0u64), |(count, bytes), path| {
+                (count + 1, bytes + archive::size(path))
+            });
+        println!(
+            "{} Going to {} {} file(s) ({}) without asking",
+            "=>".red().bold(),
+            action.verb().to_lowercase(),
+            count.to_string().bold(),
+            format_size(bytes).bold()
+        );
+    }
 
     let mut deleted_count = 0u64;
     let mut deleted_bytes = 0u64;
+    let mut failed = 0u64;
+    let mut removed: Vec<&PathBuf> = Vec::new();
 
-    for (i, (_hash, paths)) in duplicates.iter().enumerate() {
-        let size = paths[0].metadata().map(|m| m.len()).unwrap_or(0);
+    for (i, (_hash, paths)) in sorted_groups(duplicates, order).into_iter().enumerate() {
+        let size = archive::size(&paths[0]);
         println!(
             "\n{} Group {} — {} each, {} copies:",
             "##".blue().bold(),
@@ -56,60 +642,205 @@ pub fn report_and_handle(
             println!("  {} {}", label, path.display());
         }
 
+        let dupes = &paths[1..];
         if dry_run {
+            removed.extend(dupes);
             continue;
         }
 
-        let dupes = &paths[1..];
-
-        if force {
-            for dupe in dupes {
-                match fs::remove_file(dupe) {
-                    Ok(()) => {
-                        deleted_count += 1;
-                        deleted_bytes += size;
-                        println!("  {} {}", "Deleted:".red(), dupe.display());
-                    }
-                    Err(e) => {
-                        eprintln!("  Error deleting {}: {}", dupe.display(), e);
-                    }
-                }
-            }
-        } else {
+        let confirmed = force || {
             print!(
-                "  Delete {} duplicate(s)? [y/N] ",
+                "  {} {} duplicate(s)? [y/N] ",
+                action.verb(),
                 dupes.len().to_string().bold()
             );
             io::stdout().flush().ok();
 
             let mut input = String::new();
             io::stdin().read_line(&mut input).ok();
+            input.trim().eq_ignore_ascii_case("y")
+        };
 
-            if input.trim().eq_ignore_ascii_case("y") {
-                for dupe in dupes {
-                    match fs::remove_file(dupe) {
-                        Ok(()) => {
-                            deleted_count += 1;
-                            deleted_bytes += size;
-                            println!("  {} {}", "Deleted:".red(), dupe.display());
-                        }
-                        Err(e) => {
-                            eprintln!("  Error deleting {}: {}", dupe.display(), e);
-                        }
-                    }
-                }
-            } else {
-                println!("  {}", "Skipped.".dimmed());
-            }
+        if confirmed {
+            let (handled, group_failed) = act_on_group(action, &paths[0], dupes);
+            failed += group_failed;
+            deleted_count += handled.len() as u64;
+            deleted_bytes += handled.len() as u64 * size;
+            removed.extend(handled);
+        } else {
+            println!("  {}", "Skipped.".dimmed());
         }
     }
 
-    if !dry_run && deleted_count > 0 {
+    if !dry_run {
+        print_summary(action, deleted_count, deleted_bytes);
+    }
+    if let Some(roots) = prune
+        && action.removes_files()
+    {
+        prune_empty_dirs(roots, &removed, dry_run);
+    }
+    Handled {
+        files: deleted_count,
+        bytes: deleted_bytes,
+        failed,
+    }
+}
+
+// Applies the action to every duplicate of one group, returning the ones it succeeded on and
+// how many it failed on. Archive members are only skipped; a quarantine manifest that can't be
+// written counts as one more failure, as what was moved can't be restored from it.
+fn act_on_group<'a>(
+    action: &mut Action,
+    keep: &Path,
+    dupes: &'a [PathBuf],
+) -> (Vec<&'a PathBuf>, u64) {
+    let handled: Vec<&PathBuf> = dupes
+        .iter()
+        .filter(|dupe| handle_duplicate(action, keep, dupe))
+        .collect();
+    let attempted = dupes
+        .iter()
+        .filter(|dupe| !archive::is_member(dupe))
+        .count();
+    let mut failed = (attempted - handled.len()) as u64;
+    if let Action::MoveAside(quarantine) = action
+        && let Err(e) = quarantine.save()
+    {
+        eprintln!(
+            "  Error writing manifest {}: {}",
+            quarantine.manifest_path().display(),
+            e
+        );
+        failed += 1;
+    }
+    (handled, failed)
+}
+
+// Applies the action to the files chosen in the interactive picker
+pub fn apply_selection(
+    selection: &[Selection],
+    action: &mut Action,
+    prune: Option<&[PathBuf]>,
+) -> Handled {
+    let mut count = 0u64;
+    let mut bytes = 0u64;
+    let mut failed = 0u64;
+    let mut removed: Vec<&PathBuf> = Vec::new();
+    for group in selection {
+        if group.remove.is_empty() {
+            continue;
+        }
+        println!("\n{} Keeping {}", "##".blue().bold(), group.keep.display());
+        let (handled, group_failed) = act_on_group(action, &group.keep, &group.remove);
+        failed += group_failed;
+        count += handled.len() as u64;
+        bytes += handled.len() as u64 * group.size;
+        removed.extend(handled);
+    }
+    print_summary(action, count, bytes);
+    if let Some(roots) = prune
+        && action.removes_files()
+    {
+        prune_empty_dirs(roots, &removed, false);
+    }
+    Handled {
+        files: count,
+        bytes,
+        failed,
+    }
+}
+
+fn print_summary(action: &Action, deleted_count: u64, deleted_bytes: u64) {
+    if deleted_count == 0 {
+        return;
+    }
+    match action {
+        Action::MoveAside(quarantine) => {
+            println!(
+                "\n{} Moved {} file(s) ({}) aside, undo with `rust-dedup restore {}`",
+                "=>".green().bold(),
+                quarantine.moved().to_string().cyan(),
+                format_size(deleted_bytes).green().bold(),
+                quarantine.manifest_path().display()
+            );
+        }
+        _ => {
+            println!(
+                "\n{} {} {} file(s), freed {}",
+                "=>".green().bold(),
+                match action {
+                    Action::Link(_) => "Linked",
+                    Action::Reflink => "Reflinked",
+                    _ => "Cleaned up",
+                },
+                deleted_count.to_string().cyan(),
+                format_size(deleted_bytes).green().bold()
+            );
+        }
+    }
+}
+
+// Near-duplicate images from --perceptual. They can differ in quality or size, so which
+// copy to keep is left to the reader.
+pub fn print_similar(groups: &[SimilarGroup]) {
+    if groups.is_empty() {
+        println!("\n{}", "No similar images found.".green());
+        return;
+    }
+    println!(
+        "\n{} Found {} group(s) of similar images (not acted on):",
+        "=>".yellow().bold(),
+        groups.len().to_string().cyan()
+    );
+    for (i, group) in groups.iter().enumerate() {
         println!(
-            "\n{} Cleaned up {} file(s), freed {}",
-            "=>".green().bold(),
-            deleted_count.to_string().cyan(),
-            format_size(deleted_bytes).green().bold()
+            "\n{} Similar {} — {} images:",
+            "##".blue().bold(),
+            (i + 1).to_string().bold(),
+            group.images.len()
         );
+        for image in &group.images {
+            let size = image.path.metadata().map(|m| m.len()).unwrap_or(0);
+            println!(
+                "  {} {} ({})",
+                format!("[{:>2}]", image.distance).dimmed(),
+                image.path.display(),
+                format_size(size)
+            );
+        }
+    }
+}
+
+pub fn print_same_names(groups: &[NameGroup]) {
+    if groups.is_empty() {
+        println!("\n{}", "No files share a name.".green());
+        return;
+    }
+    println!(
+        "\n{} Found {} group(s) of files with the same name (not acted on):",
+        "=>".yellow().bold(),
+        groups.len().to_string().cyan()
+    );
+    for group in groups {
+        let each = group
+            .size
+            .map(|size| format!(", {} each", format_size(size).yellow()))
+            .unwrap_or_default();
+        println!(
+            "\n{} {} — {} files{}:",
+            "##".blue().bold(),
+            group.name.bold(),
+            group.paths.len(),
+            each
+        );
+        for path in &group.paths {
+            if group.size.is_some() {
+                println!("  {}", path.display());
+            } else {
+                let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+                println!("  {} ({})", path.display(), format_size(size));
+            }
+        }
     }
-}
\ No newline at end of file
+}
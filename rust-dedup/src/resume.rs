@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::cli::HashAlgo;
+use crate::manifest::absolute;
+use crate::progress;
+
+// Bumped whenever a field changes meaning; a state file from another version is ignored
+const SCHEMA_VERSION: u32 = 1;
+// How much hashing an interruption can lose at most
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Where an interrupted scan got to: every file hashed so far, and the candidates still
+// waiting. Paths are absolute, so a re-run from elsewhere still finds them.
+#[derive(Serialize, Deserialize)]
+struct State {
+    version: u32,
+    algorithm: HashAlgo,
+    fuzzy_text: bool,
+    hashed: HashMap<PathBuf, Hashed>,
+    pending: HashSet<PathBuf>,
+}
+
+// A hash is only reused while the file's size and modification time are what they were
+#[derive(Clone, Serialize, Deserialize)]
+struct Hashed {
+    size: u64,
+    modified: Duration,
+    hash: String,
+}
+
+// The state file of a `--resume` scan, saved every few seconds while hashing
+pub struct Resume {
+    path: PathBuf,
+    // Where the state file is, or will be, for keeping it out of the scan
+    absolute: PathBuf,
+    state: Mutex<State>,
+    last_saved: Mutex<Instant>,
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    PathBuf::from(temp)
+}
+
+fn modified(path: &Path) -> Option<(u64, Duration)> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((meta.len(), modified))
+}
+
+impl Resume {
+    // Picks up the state left at `path`, unless it's from a scan hashing differently
+    pub fn open(path: &Path, algo: HashAlgo, fuzzy_text: bool) -> Self {
+        let fresh = State {
+            version: SCHEMA_VERSION,
+            algorithm: algo,
+            fuzzy_text,
+            hashed: HashMap::new(),
+            pending: HashSet::new(),
+        };
+        let state = match fs::read(path) {
+            Ok(bytes) => match serde_json::from_slice::<State>(&bytes) {
+                Ok(state)
+                    if state.version == SCHEMA_VERSION
+                        && state.algorithm == algo
+                        && state.fuzzy_text == fuzzy_text =>
+                {
+                    progress::status(format_args!(
+                        "  Resuming from {}: {} file(s) already hashed, {} left",
+                        path.display(),
+                        state.hashed.len(),
+                        state.pending.len()
+                    ));
+                    state
+                }
+                Ok(_) => {
+                    eprintln!(
+                        "  Warning: {} is from a scan with other settings; starting over",
+                        path.display()
+                    );
+                    fresh
+                }
+                Err(e) => {
+                    eprintln!("  Warning: ignoring {}: {}", path.display(), e);
+                    fresh
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => fresh,
+            Err(e) => {
+                eprintln!(
+                    "  Warning: cannot read {}: {}; starting over",
+                    path.display(),
+                    e
+                );
+                fresh
+            }
+        };
+        // It may not exist yet, but its directory has to
+        let absolute = match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) if !dir.as_os_str().is_empty() => absolute(dir).join(name),
+            (_, Some(name)) => absolute(Path::new(".")).join(name),
+            _ => absolute(path),
+        };
+        Self {
+            path: path.to_path_buf(),
+            absolute,
+            state: Mutex::new(state),
+            last_saved: Mutex::new(Instant::now()),
+        }
+    }
+
+    // The state file or its temporary copy, which can be inside the scanned directories
+    pub fn is_own(&self, file: &Path) -> bool {
+        let file = absolute(file);
+        file == self.absolute || file == temp_path(&self.absolute)
+    }
+
+    // Notes what's about to be hashed, so the state file says how much is left
+    pub fn start(&self, candidates: &[&PathBuf]) {
+        {
+            let mut state = self.state.lock().unwrap();
+            let pending: HashSet<PathBuf> = candidates.iter().map(|file| absolute(file)).collect();
+            state.pending = pending
+                .into_iter()
+                .filter(|file| !state.hashed.contains_key(file))
+                .collect();
+        }
+        self.save();
+    }
+
+    // The hash from an earlier run, if the file hasn't changed since
+    pub fn cached(&self, file: &Path) -> Option<String> {
+        let (size, modified) = modified(file)?;
+        let state = self.state.lock().unwrap();
+        let hashed = state.hashed.get(&absolute(file))?;
+        (hashed.size == size && hashed.modified == modified).then(|| hashed.hash.clone())
+    }
+
+    pub fn record(&self, file: &Path, hash: &str) {
+        if let Some((size, modified)) = modified(file) {
+            let file = absolute(file);
+            let mut state = self.state.lock().unwrap();
+            state.pending.remove(&file);
+            state.hashed.insert(
+                file,
+                Hashed {
+                    size,
+                    modified,
+                    hash: hash.to_string(),
+                },
+            );
+        }
+        // Whichever thread finds the interval over saves; the others carry on hashing
+        let due = self.last_saved.try_lock().is_ok_and(|mut last| {
+            let due = last.elapsed() >= SAVE_INTERVAL;
+            if due {
+                *last = Instant::now();
+            }
+            due
+        });
+        if due {
+            self.save();
+        }
+    }
+
+    // Hashing goes on without it if the state can't be saved, it just can't be resumed
+    fn save(&self) {
+        if let Err(e) = self.write() {
+            eprintln!("  Warning: could not save {}: {}", self.path.display(), e);
+        }
+    }
+
+    // Written next to the state file first, so an interruption mid-write loses nothing
+    fn write(&self) -> io::Result<()> {
+        let json = serde_json::to_vec(&*self.state.lock().unwrap()).map_err(io::Error::other)?;
+        let temp = temp_path(&self.path);
+        fs::write(&temp, json)?;
+        fs::rename(&temp, &self.path)
+    }
+
+    // Hashing is over, so there's nothing left to resume
+    pub fn finish(self) {
+        if let Err(e) = fs::remove_file(&self.path)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            eprintln!("  Warning: could not remove {}: {}", self.path.display(), e);
+        }
+    }
+}
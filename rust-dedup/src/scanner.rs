@@ -1,19 +1,459 @@
-use std::path::PathBuf;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-pub fn scan_files(root: &str, min_size: u64) -> Vec<PathBuf> {
-    let mut files = Vec::new();
+use crate::archive;
+use crate::progress;
 
-    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.is_file() {
-            if let Ok(meta) = path.metadata() {
-                if meta.len() >= min_size {
-                    files.push(path.to_path_buf());
+// Narrows a scan down to some of the files under its roots. Every filter has to accept a
+// file for it to be considered.
+#[derive(Clone)]
+pub enum Filter {
+    // Lowercase, without the dot
+    Extensions(HashSet<String>),
+    // Canonical directories; files under any one of them pass
+    Under(Vec<PathBuf>),
+}
+
+impl Filter {
+    fn accepts(&self, canonical: &Path) -> bool {
+        match self {
+            Filter::Extensions(extensions) => canonical
+                .extension()
+                .is_some_and(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase())),
+            Filter::Under(dirs) => dirs.iter().any(|dir| canonical.starts_with(dir)),
+        }
+    }
+
+    // Whether anything below this directory could pass, so the rest isn't walked at all
+    fn may_contain(&self, canonical_dir: &Path) -> bool {
+        match self {
+            Filter::Extensions(_) => true,
+            Filter::Under(dirs) => dirs
+                .iter()
+                .any(|dir| canonical_dir.starts_with(dir) || dir.starts_with(canonical_dir)),
+        }
+    }
+}
+
+// Read in every directory walked, in gitignore syntax, to leave out build output and the like
+const DEDUPIGNORE: &str = ".dedupignore";
+
+// The ignore files of each directory, read once when first needed, and the excludes
+// given for the whole scan
+pub struct IgnoreRules {
+    respect_gitignore: bool,
+    dirs: HashMap<PathBuf, Option<Gitignore>>,
+    excludes: Option<Gitignore>,
+}
+
+impl IgnoreRules {
+    pub fn new(options: &ScanOptions) -> Self {
+        Self {
+            respect_gitignore: options.respect_gitignore,
+            dirs: HashMap::new(),
+            excludes: Self::excludes(&options.excludes),
+        }
+    }
+
+    // Not tied to any directory, so a pattern with a slash in it is matched against the
+    // path as walked, e.g. `./photos/tmp` for `photos/tmp` when scanning `.`
+    fn excludes(patterns: &[String]) -> Option<Gitignore> {
+        if patterns.is_empty() {
+            return None;
+        }
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                eprintln!("  Warning: bad exclude pattern {}: {}", pattern, e);
+            }
+        }
+        builder
+            .build()
+            .inspect_err(|e| eprintln!("  Warning: {}", e))
+            .ok()
+    }
+
+    // .dedupignore is added last, so its rules win over .gitignore's in the same directory
+    fn load(&self, dir: &Path) -> Option<Gitignore> {
+        let mut files = Vec::new();
+        if self.respect_gitignore {
+            files.push(dir.join(".gitignore"));
+        }
+        files.push(dir.join(DEDUPIGNORE));
+        let files: Vec<PathBuf> = files.into_iter().filter(|f| f.is_file()).collect();
+        if files.is_empty() {
+            return None;
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        for file in &files {
+            if let Some(e) = builder.add(file) {
+                eprintln!("  Warning: {}", e);
+            }
+        }
+        builder
+            .build()
+            .inspect_err(|e| eprintln!("  Warning: {}", e))
+            .ok()
+    }
+
+    // Whether the rules of the directories from `root` down to the path's parent leave it
+    // out. As in git, a rule nearer the path wins, so a `!pattern` can take a file back,
+    // and the excludes only count when no ignore file has a say.
+    fn ignores(&mut self, root: &Path, path: &Path, is_dir: bool) -> bool {
+        for dir in path.ancestors().skip(1) {
+            if !self.dirs.contains_key(dir) {
+                let rules = self.load(dir);
+                self.dirs.insert(dir.to_path_buf(), rules);
+            }
+            if let Some(rules) = &self.dirs[dir] {
+                let matched = rules.matched(path, is_dir);
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+            if dir == root {
+                break;
+            }
+        }
+        self.excludes
+            .as_ref()
+            .is_some_and(|excludes| excludes.matched(path, is_dir).is_ignore())
+    }
+
+    // For a path found outside a walk, whose directories weren't checked on the way down
+    pub fn ignores_path(&mut self, root: &Path, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+        let mut current = root.to_path_buf();
+        let depth = relative.components().count();
+        for (i, component) in relative.components().enumerate() {
+            current.push(component);
+            if self.ignores(root, &current, i + 1 < depth || current.is_dir()) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Clone)]
+pub struct ScanOptions {
+    pub min_size: u64,
+    pub max_size: Option<u64>,
+    // Levels below each root; files directly in a root are at depth 1
+    pub max_depth: Option<usize>,
+    /// Skip files not owned by the invoking user
+    pub owned_only: bool,
+    /// Skip files the invoking user can't write to
+    pub writable_only: bool,
+    /// Descend into symlinked directories and take symlinked files as the files they point to
+    pub follow_symlinks: bool,
+    /// Don't descend into directories on other filesystems than their root
+    pub one_filesystem: bool,
+    pub filters: Vec<Filter>,
+    /// Also collect archives to look inside, whatever the filters say about the archive itself
+    pub scan_archives: bool,
+    /// Apply .gitignore files along with .dedupignore ones
+    pub respect_gitignore: bool,
+    /// Patterns in gitignore syntax left out everywhere, like git's core.excludesFile
+    pub excludes: Vec<String>,
+    /// Windows only: also scan files and directories with the hidden attribute
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub include_hidden: bool,
+    /// Windows only: also scan files and directories with the system attribute
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub include_system: bool,
+}
+
+pub struct ScanResult {
+    pub files: Vec<PathBuf>,
+    pub excluded_not_owned: usize,
+    pub excluded_not_writable: usize,
+    // Roots and entries that couldn't be read, so the file list may be incomplete
+    pub errors: usize,
+    // With scan_archives; these are in `files` too if they pass the filters
+    pub archives: Vec<PathBuf>,
+}
+
+#[cfg(unix)]
+fn is_owned(meta: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    // SAFETY: geteuid has no preconditions and cannot fail
+    meta.uid() == unsafe { libc::geteuid() }
+}
+
+#[cfg(not(unix))]
+fn is_owned(_meta: &Metadata) -> bool {
+    // No portable notion of a file owner here; treat everything as ours
+    true
+}
+
+#[cfg(unix)]
+fn is_writable(path: &Path, _meta: &Metadata) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    // Ask the kernel rather than reading mode bits, so groups and ACLs count too
+    match CString::new(path.as_os_str().as_bytes()) {
+        // SAFETY: the pointer is a valid NUL-terminated string for the duration of the call
+        Ok(c_path) => unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 },
+        Err(_) => false,
+    }
+}
+
+// Hidden and system files are left out on Windows unless asked for, as Explorer does. So
+// are reparse points other than links (which count as symlinks already), unless links are
+// followed: cloud placeholders, say, would be downloaded just to be hashed.
+#[cfg(windows)]
+fn skipped_by_attributes(meta: &Metadata, options: &ScanOptions) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    let attributes = meta.file_attributes();
+    (!options.include_hidden && attributes & FILE_ATTRIBUTE_HIDDEN != 0)
+        || (!options.include_system && attributes & FILE_ATTRIBUTE_SYSTEM != 0)
+        || (!options.follow_symlinks && attributes & FILE_ATTRIBUTE_REPARSE_POINT != 0)
+}
+
+#[cfg(not(windows))]
+fn skipped_by_attributes(_meta: &Metadata, _options: &ScanOptions) -> bool {
+    // Dotfiles are ordinary files here
+    false
+}
+
+// Windows paths of MAX_PATH (260) characters or more only work with the \\?\ prefix,
+// which also turns off the parsing that would choke on them. std adds it to its own calls
+// since 1.58; this is for paths from elsewhere that are handed on as they are, so a file the
+// scan could reach can always be acted on too.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    const MAX_PATH: usize = 260;
+    let raw = path.as_os_str();
+    if raw.len() < MAX_PATH || raw.as_encoded_bytes().starts_with(br"\\?\") {
+        return Cow::Borrowed(path);
+    }
+    // Verbatim paths aren't normalized, so `..` and forward slashes have to go first
+    let Ok(absolute) = std::path::absolute(path) else {
+        return Cow::Borrowed(path);
+    };
+    let mut verbatim = std::ffi::OsString::new();
+    match absolute
+        .as_os_str()
+        .to_str()
+        .and_then(|p| p.strip_prefix(r"\\"))
+    {
+        Some(unc) => {
+            verbatim.push(r"\\?\UNC\");
+            verbatim.push(unc);
+        }
+        None => {
+            verbatim.push(r"\\?\");
+            verbatim.push(absolute.as_os_str());
+        }
+    }
+    Cow::Owned(PathBuf::from(verbatim))
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+// Identifies a file or directory however it was reached
+#[cfg(unix)]
+pub fn file_id(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn file_id(_meta: &Metadata) -> Option<(u64, u64)> {
+    // Without inode numbers, walkdir's own loop detection is all there is
+    None
+}
+
+#[cfg(not(unix))]
+fn is_writable(_path: &Path, meta: &Metadata) -> bool {
+    !meta.permissions().readonly()
+}
+
+// The size limits and filters for a file inside an archive, given as the archive's
+// canonical path followed by archive::SEPARATOR and the path inside it
+pub fn wanted_member(path: &Path, size: u64, options: &ScanOptions) -> bool {
+    let too_big = options.max_size.is_some_and(|max| size > max);
+    size >= options.min_size && !too_big && options.filters.iter().all(|f| f.accepts(path))
+}
+
+// The per-file checks of a scan, for files that turn up after it, e.g. in watch mode
+pub fn wanted(path: &Path, meta: &Metadata, options: &ScanOptions) -> bool {
+    let too_big = options.max_size.is_some_and(|max| meta.len() > max);
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    meta.is_file()
+        && options.filters.iter().all(|f| f.accepts(&canonical))
+        && meta.len() >= options.min_size
+        && !too_big
+        && !skipped_by_attributes(meta, options)
+        && (!options.owned_only || is_owned(meta))
+        && (!options.writable_only || is_writable(path, meta))
+}
+
+// Walks every root in order. Overlapping roots (e.g. /photos and /photos/2024) would list
+// the same file twice and make it its own duplicate, so each file is only taken once.
+pub fn scan_files(roots: &[String], options: &ScanOptions) -> ScanResult {
+    let mut result = ScanResult {
+        files: Vec::new(),
+        excluded_not_owned: 0,
+        excluded_not_writable: 0,
+        errors: 0,
+        archives: Vec::new(),
+    };
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut seen_archives: HashSet<PathBuf> = HashSet::new();
+    let mut ignore_rules = IgnoreRules::new(options);
+    // With symlinks followed, paths no longer identify files: a link can lead back up the
+    // tree or to somewhere already scanned, so directories and files are tracked by inode
+    let mut seen_dirs: HashSet<(u64, u64)> = HashSet::new();
+    let mut seen_files: HashSet<(u64, u64)> = HashSet::new();
+    let spinner = progress::scan_spinner();
+
+    for root in roots {
+        spinner.set_message(root.clone());
+        // Unless symlinks are followed, joining onto the canonical root gives each file's
+        // canonical path without resolving it again; a followed link leads somewhere else,
+        // so then every path is resolved
+        let canonical_root = match fs::canonicalize(root) {
+            Ok(path) => path,
+            Err(e) => {
+                spinner.suspend(|| eprintln!("  Warning: cannot scan {}: {}", root, e));
+                result.errors += 1;
+                continue;
+            }
+        };
+
+        let mut walker = WalkDir::new(root)
+            .follow_links(options.follow_symlinks)
+            .same_file_system(options.one_filesystem);
+        if let Some(depth) = options.max_depth {
+            walker = walker.max_depth(depth + 1);
+        }
+        let root_path = Path::new(root);
+        let canonical_of = |path: &Path| {
+            if options.follow_symlinks
+                && let Ok(resolved) = fs::canonicalize(path)
+            {
+                return resolved;
+            }
+            canonical_root.join(path.strip_prefix(root).unwrap_or(path))
+        };
+        let entries = walker.into_iter().filter_entry(|entry| {
+            // Ignored directories aren't descended into at all
+            if entry.depth() > 0
+                && ignore_rules.ignores(root_path, entry.path(), entry.file_type().is_dir())
+            {
+                return false;
+            }
+            if entry.depth() > 0
+                && entry
+                    .metadata()
+                    .is_ok_and(|meta| skipped_by_attributes(&meta, options))
+            {
+                return false;
+            }
+            if !entry.file_type().is_dir() {
+                return true;
+            }
+            if !options.follow_symlinks {
+                let canonical = canonical_of(entry.path());
+                return options.filters.iter().all(|f| f.may_contain(&canonical));
+            }
+            // Any directory could hold a link into the ones asked for, so with links followed
+            // nothing is pruned up front and each file is checked where it resolves to
+            let Some(id) = entry.metadata().ok().as_ref().and_then(file_id) else {
+                return true;
+            };
+            let first_visit = seen_dirs.insert(id);
+            if !first_visit && entry.path_is_symlink() {
+                spinner.suspend(|| {
+                    eprintln!(
+                        "  Skipping {}: already scanned through another path",
+                        entry.path().display()
+                    )
+                });
+            }
+            first_visit
+        });
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // walkdir catches links back up to an ancestor before they get here
+                Err(e) if e.loop_ancestor().is_some() => {
+                    if let Some(path) = e.path() {
+                        spinner
+                            .suspend(|| eprintln!("  Skipping {}: symlink loop", path.display()));
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    spinner.suspend(|| eprintln!("  Warning: {}", e));
+                    result.errors += 1;
+                    continue;
                 }
+            };
+            // Unless asked to follow them, symlinks are skipped: one pointing at another
+            // scanned file would look like its duplicate, and removing or relinking either
+            // could lose the data
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let canonical = canonical_of(path);
+            if options.scan_archives
+                && archive::is_archive(path)
+                && seen_archives.insert(canonical.clone())
+            {
+                result.archives.push(path.to_path_buf());
+            }
+            if !options.filters.iter().all(|f| f.accepts(&canonical)) {
+                continue;
+            }
+            let Ok(meta) = path.metadata() else {
+                continue;
+            };
+            let too_big = options.max_size.is_some_and(|max| meta.len() > max);
+            if meta.len() < options.min_size || too_big {
+                continue;
+            }
+            if !seen.insert(canonical) {
+                continue;
+            }
+            // A followed link and its target are the same file, never duplicates of each other
+            if options.follow_symlinks
+                && let Some(id) = file_id(&meta)
+                && !seen_files.insert(id)
+            {
+                continue;
+            }
+            if options.owned_only && !is_owned(&meta) {
+                result.excluded_not_owned += 1;
+                continue;
+            }
+            if options.writable_only && !is_writable(path, &meta) {
+                result.excluded_not_writable += 1;
+                continue;
             }
+            result.files.push(path.to_path_buf());
+            spinner.inc(1);
         }
     }
+    spinner.finish_and_clear();
 
-    files
-}
\ No newline at end of file
+    result
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::cli::{ScriptKind, SortOrder};
+use crate::format::format_size;
+use crate::manifest::absolute;
+use crate::reporter::sorted_groups;
+
+// A POSIX shell word: everything literal inside single quotes, which can't contain one
+fn sh_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', r"'\''"))
+}
+
+// PowerShell takes the typographic single quotes as quotes too, so those are doubled as well
+fn powershell_quote(path: &str) -> String {
+    let mut quoted = String::from("'");
+    for c in path.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+impl ScriptKind {
+    fn quote(self, path: &str) -> String {
+        match self {
+            ScriptKind::Sh => sh_quote(path),
+            ScriptKind::Powershell => powershell_quote(path),
+        }
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            ScriptKind::Sh => "#!/bin/sh\nset -eu\n",
+            ScriptKind::Powershell => "$ErrorActionPreference = 'Stop'\n",
+        }
+    }
+}
+
+// Writes the commands that would delete every duplicate, for someone to review and run
+// through their own process. Each group's removals are guarded on the kept copy still
+// being there when the script runs, however long after the scan that is.
+pub fn write_script(
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+    kind: ScriptKind,
+    order: SortOrder,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    // Copies inside archives can't be deleted on their own, so only files on disk are listed.
+    // Same order as the reports, so a script can be checked against one.
+    let groups: Vec<(&String, Vec<&PathBuf>)> = sorted_groups(duplicates, order)
+        .into_iter()
+        .map(|(hash, paths)| {
+            (
+                hash,
+                paths.iter().filter(|p| !archive::is_member(p)).collect(),
+            )
+        })
+        .filter(|(_, paths): &(&String, Vec<&PathBuf>)| paths.len() > 1)
+        .collect();
+    let removals: usize = groups.iter().map(|(_, paths)| paths.len() - 1).sum();
+    let wasted: u64 = groups
+        .iter()
+        .map(|(_, paths)| archive::size(paths[0]) * (paths.len() as u64 - 1))
+        .sum();
+
+    write!(out, "{}", kind.header())?;
+    writeln!(
+        out,
+        "# Generated by rust-dedup: {} duplicate group(s), {} file(s) to delete, {} to free.",
+        groups.len(),
+        removals,
+        format_size(wasted)
+    )?;
+    writeln!(
+        out,
+        "# Review before running; nothing has been deleted yet."
+    )?;
+
+    for (i, (hash, paths)) in groups.iter().enumerate() {
+        let keep = absolute(paths[0]);
+        writeln!(out, "\n# Group {} ({})", i + 1, hash)?;
+        // Paths that aren't valid UTF-8 can't be written out faithfully, and a mangled one
+        // could name a different file. They're only mentioned, escaped, so a newline in one
+        // can't end the comment.
+        let Some(keep_str) = keep.to_str() else {
+            writeln!(
+                out,
+                "# Skipped: the kept copy's path isn't valid UTF-8: {:?}",
+                keep
+            )?;
+            continue;
+        };
+        let dupes: Vec<PathBuf> = paths[1..].iter().map(|p| absolute(p)).collect();
+        match kind {
+            ScriptKind::Sh => writeln!(out, "if [ -e {} ]; then", kind.quote(keep_str))?,
+            ScriptKind::Powershell => writeln!(
+                out,
+                "if (Test-Path -LiteralPath {}) {{",
+                kind.quote(keep_str)
+            )?,
+        }
+        for dupe in &dupes {
+            write_removal(out, kind, dupe)?;
+        }
+        match kind {
+            ScriptKind::Sh => writeln!(out, "fi")?,
+            ScriptKind::Powershell => writeln!(out, "}}")?,
+        }
+    }
+    Ok(())
+}
+
+fn write_removal(out: &mut impl Write, kind: ScriptKind, dupe: &Path) -> io::Result<()> {
+    let Some(dupe_str) = dupe.to_str() else {
+        return writeln!(out, "    # Skipped, path isn't valid UTF-8: {:?}", dupe);
+    };
+    match kind {
+        ScriptKind::Sh => writeln!(out, "    rm -- {}", kind.quote(dupe_str)),
+        ScriptKind::Powershell => {
+            writeln!(out, "    Remove-Item -LiteralPath {}", kind.quote(dupe_str))
+        }
+    }
+}
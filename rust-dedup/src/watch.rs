@@ -0,0 +1,261 @@
+use colored::Colorize;
+use indicatif::ProgressBar;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+use crate::cli::HashAlgo;
+use crate::format::format_size;
+use crate::hasher::hash_file;
+use crate::progress;
+use crate::scanner::{IgnoreRules, ScanOptions, file_id, wanted};
+
+// A download or copy in progress fires an event per write; a file is only looked at once
+// it has been left alone this long
+const SETTLE: Duration = Duration::from_secs(2);
+const POLL: Duration = Duration::from_millis(500);
+
+// Everything known about the watched trees. Hashes are only worked out once two files share
+// a size, as in a full scan, and kept until the file changes.
+struct Index<'a> {
+    roots: Vec<PathBuf>,
+    options: &'a ScanOptions,
+    ignore_rules: IgnoreRules,
+    algo: HashAlgo,
+    by_size: HashMap<u64, Vec<PathBuf>>,
+    sizes: HashMap<PathBuf, u64>,
+    hashes: HashMap<PathBuf, String>,
+}
+
+impl Index<'_> {
+    fn hash(&mut self, path: &Path, size: u64) -> Option<String> {
+        if let Some(hash) = self.hashes.get(path) {
+            return Some(hash.clone());
+        }
+        match hash_file(path, size, self.algo, &ProgressBar::hidden()) {
+            Ok(hash) => {
+                self.hashes.insert(path.to_path_buf(), hash.clone());
+                Some(hash)
+            }
+            Err(e) => {
+                eprintln!("  Warning: could not hash {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    // Drops the path, and everything under it if it was a directory
+    fn forget(&mut self, path: &Path) {
+        let gone: Vec<PathBuf> = self
+            .sizes
+            .keys()
+            .filter(|p| p.starts_with(path))
+            .cloned()
+            .collect();
+        for file in gone {
+            if let Some(size) = self.sizes.remove(&file)
+                && let Some(paths) = self.by_size.get_mut(&size)
+            {
+                paths.retain(|p| *p != file);
+            }
+            self.hashes.remove(&file);
+        }
+    }
+
+    // Files further down than --max-depth allows are left out, as in the first scan
+    fn within_depth(&self, path: &Path) -> bool {
+        let Some(max) = self.options.max_depth else {
+            return true;
+        };
+        self.roots
+            .iter()
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .any(|relative| relative.components().count() <= max + 1)
+    }
+
+    fn ignored(&mut self, path: &Path) -> bool {
+        let Some(root) = self.roots.iter().find(|root| path.starts_with(root)) else {
+            return false;
+        };
+        self.ignore_rules.ignores_path(&root.clone(), path)
+    }
+
+    // Takes in a new or changed file, returning the earlier copies it duplicates
+    fn add(&mut self, path: &Path) -> Vec<PathBuf> {
+        let meta = if self.options.follow_symlinks {
+            fs::metadata(path)
+        } else {
+            fs::symlink_metadata(path)
+        };
+        let Ok(meta) = meta else {
+            return Vec::new();
+        };
+        if !wanted(path, &meta, self.options) || !self.within_depth(path) || self.ignored(path) {
+            return Vec::new();
+        }
+        let size = meta.len();
+        let peers = self.by_size.entry(size).or_default().clone();
+        self.by_size
+            .get_mut(&size)
+            .unwrap()
+            .push(path.to_path_buf());
+        self.sizes.insert(path.to_path_buf(), size);
+        if peers.is_empty() {
+            return Vec::new();
+        }
+
+        let Some(hash) = self.hash(path, size) else {
+            return Vec::new();
+        };
+        let id = file_id(&meta);
+        peers
+            .into_iter()
+            // Another name for the same inode frees nothing, as in hasher.rs
+            .filter(|peer| id.is_none() || fs::metadata(peer).ok().as_ref().and_then(file_id) != id)
+            .filter(|peer| self.hash(peer, size).as_ref() == Some(&hash))
+            .collect()
+    }
+}
+
+fn report(path: &Path, copies: &[PathBuf]) {
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    println!(
+        "\n{} New duplicate, {}: {}",
+        "=>".yellow().bold(),
+        format_size(size),
+        path.display().to_string().bold()
+    );
+    for copy in copies {
+        println!("  {} {}", "[same]".dimmed(), copy.display());
+    }
+}
+
+// Keeps running after the first scan, reporting every file that shows up as a copy of one
+// already there. Nothing is deleted or linked; run again without --watch for that.
+pub fn watch(
+    roots: &[String],
+    options: &ScanOptions,
+    algo: HashAlgo,
+    min_copies: usize,
+    files: &[PathBuf],
+    duplicates: &HashMap<String, Vec<PathBuf>>,
+) -> Result<(), String> {
+    // Events come with canonical paths, so the roots and what the first scan found under
+    // them are made to match
+    let roots_as_given: Vec<(PathBuf, PathBuf)> = roots
+        .iter()
+        .map(|root| {
+            let given = PathBuf::from(root);
+            let canonical = fs::canonicalize(&given).unwrap_or_else(|_| given.clone());
+            (given, canonical)
+        })
+        .collect();
+    let canonical = |path: &Path| -> PathBuf {
+        roots_as_given
+            .iter()
+            .find_map(|(given, canonical)| {
+                path.strip_prefix(given)
+                    .ok()
+                    .map(|relative| canonical.join(relative))
+            })
+            .unwrap_or_else(|| path.to_path_buf())
+    };
+    let mut index = Index {
+        roots: roots_as_given
+            .iter()
+            .map(|(_, canonical)| canonical.clone())
+            .collect(),
+        options,
+        ignore_rules: IgnoreRules::new(options),
+        algo,
+        by_size: HashMap::new(),
+        sizes: HashMap::new(),
+        // The first scan already hashed these
+        hashes: duplicates
+            .iter()
+            .flat_map(|(hash, paths)| paths.iter().map(|p| (canonical(p), hash.clone())))
+            .collect(),
+    };
+    for file in files {
+        if let Ok(meta) = file.metadata() {
+            let file = canonical(file);
+            index
+                .by_size
+                .entry(meta.len())
+                .or_default()
+                .push(file.clone());
+            index.sizes.insert(file, meta.len());
+        }
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("cannot watch for changes: {}", e))?;
+    for root in &index.roots {
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("cannot watch {}: {}", root.display(), e))?;
+    }
+    progress::status(format_args!(
+        "\n{} Watching {} for new duplicates (Ctrl+C to stop)...",
+        "=>".blue().bold(),
+        roots.join(", ").bold()
+    ));
+
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    loop {
+        match rx.recv_timeout(POLL) {
+            Ok(Ok(event)) => {
+                // Reading a file or changing its permissions leaves the contents as they were
+                if !matches!(
+                    event.kind,
+                    EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_))
+                ) {
+                    for path in event.paths {
+                        pending.insert(path, Instant::now());
+                    }
+                }
+            }
+            Ok(Err(e)) => eprintln!("  Warning: {}", e),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err("stopped receiving changes".to_string());
+            }
+        }
+
+        let mut settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, last)| last.elapsed() >= SETTLE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        settled.sort();
+        for path in settled {
+            pending.remove(&path);
+            // Whatever happened to it (written, renamed away, deleted), the path is looked at
+            // afresh: dropped, then taken in again if there's still a file there
+            index.forget(&path);
+            if path.is_dir() {
+                // A directory moved in whole only reports itself, not its files. Files that
+                // have events of their own are still being written and get their turn later.
+                for entry in WalkDir::new(&path).into_iter().filter_map(Result::ok) {
+                    if entry.file_type().is_file() && !pending.contains_key(entry.path()) {
+                        let copies = index.add(entry.path());
+                        if !copies.is_empty() && copies.len() + 1 >= min_copies {
+                            report(entry.path(), &copies);
+                        }
+                    }
+                }
+                continue;
+            }
+            let copies = index.add(&path);
+            if !copies.is_empty() && copies.len() + 1 >= min_copies {
+                report(&path, &copies);
+            }
+        }
+    }
+}
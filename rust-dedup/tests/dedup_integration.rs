@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rust_dedup::cli::{HashAlgo, SortOrder};
+use rust_dedup::hasher::find_duplicates;
+use rust_dedup::manifest;
+use rust_dedup::progress;
+use rust_dedup::quarantine::{self, Bin, Quarantine};
+use rust_dedup::reporter::{Action, Handled, report_and_handle};
+use rust_dedup::scanner::{ScanOptions, scan_files};
+use tempfile::TempDir;
+
+fn options() -> ScanOptions {
+    ScanOptions {
+        min_size: 1,
+        max_size: None,
+        max_depth: None,
+        owned_only: false,
+        writable_only: false,
+        follow_symlinks: false,
+        one_filesystem: false,
+        filters: Vec::new(),
+        scan_archives: false,
+        respect_gitignore: false,
+        excludes: Vec::new(),
+        include_hidden: false,
+        include_system: false,
+    }
+}
+
+// Writes each file under a fresh temporary directory, creating directories as needed
+fn tree(files: &[(&str, &[u8])]) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for (path, contents) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn duplicates_in(
+    dir: &Path,
+    options: &ScanOptions,
+    algo: HashAlgo,
+) -> HashMap<String, Vec<PathBuf>> {
+    progress::set_quiet(true);
+    let scan = scan_files(&[dir.display().to_string()], options);
+    assert_eq!(scan.errors, 0);
+    let result = find_duplicates(&scan.files, &[], 2, algo, false, None);
+    assert_eq!(result.errors, 0);
+    result.duplicates
+}
+
+// Each group's paths relative to the tree, sorted, with the groups sorted too
+fn relative_groups(dir: &Path, duplicates: &HashMap<String, Vec<PathBuf>>) -> Vec<Vec<String>> {
+    let root = fs::canonicalize(dir).unwrap();
+    let mut groups: Vec<Vec<String>> = duplicates
+        .values()
+        .map(|paths| {
+            let mut group: Vec<String> = paths
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(&root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect();
+            group.sort();
+            group
+        })
+        .collect();
+    groups.sort();
+    groups
+}
+
+fn remaining_files(dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            entry
+                .path()
+                .strip_prefix(dir)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn sample_tree() -> TempDir {
+    tree(&[
+        ("a.txt", b"hello world"),
+        ("nested/a-copy.txt", b"hello world"),
+        ("nested/deeper/a-again.txt", b"hello world"),
+        // Same size as the ones above, different contents
+        ("decoy.txt", b"hello there"),
+        ("photo.jpg", &[7; 4096]),
+        ("backup/photo.jpg", &[7; 4096]),
+        ("unique.bin", &[1; 100]),
+    ])
+}
+
+#[test]
+fn finds_groups_of_identical_files() {
+    let dir = sample_tree();
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    assert_eq!(
+        relative_groups(dir.path(), &duplicates),
+        vec![
+            vec!["a.txt", "nested/a-copy.txt", "nested/deeper/a-again.txt"],
+            vec!["backup/photo.jpg", "photo.jpg"],
+        ]
+    );
+}
+
+#[test]
+fn every_algorithm_finds_the_same_groups() {
+    let dir = sample_tree();
+    let expected = relative_groups(
+        dir.path(),
+        &duplicates_in(dir.path(), &options(), HashAlgo::Blake3),
+    );
+    for algo in [HashAlgo::Xxh3, HashAlgo::Sha256] {
+        let duplicates = duplicates_in(dir.path(), &options(), algo);
+        assert_eq!(
+            relative_groups(dir.path(), &duplicates),
+            expected,
+            "{}",
+            algo.name()
+        );
+    }
+}
+
+#[test]
+fn scan_options_narrow_what_is_compared() {
+    let dir = sample_tree();
+
+    let big_only = ScanOptions {
+        min_size: 1000,
+        ..options()
+    };
+    let duplicates = duplicates_in(dir.path(), &big_only, HashAlgo::Blake3);
+    assert_eq!(
+        relative_groups(dir.path(), &duplicates),
+        vec![vec!["backup/photo.jpg", "photo.jpg"]]
+    );
+
+    let shallow = ScanOptions {
+        max_depth: Some(1),
+        ..options()
+    };
+    let duplicates = duplicates_in(dir.path(), &shallow, HashAlgo::Blake3);
+    assert_eq!(
+        relative_groups(dir.path(), &duplicates),
+        vec![
+            vec!["a.txt", "nested/a-copy.txt"],
+            vec!["backup/photo.jpg", "photo.jpg"]
+        ]
+    );
+
+    let excluded = ScanOptions {
+        excludes: vec!["backup/".to_string()],
+        ..options()
+    };
+    let duplicates = duplicates_in(dir.path(), &excluded, HashAlgo::Blake3);
+    assert_eq!(
+        relative_groups(dir.path(), &duplicates),
+        vec![vec![
+            "a.txt",
+            "nested/a-copy.txt",
+            "nested/deeper/a-again.txt"
+        ]]
+    );
+}
+
+#[test]
+fn dedupignore_files_are_respected() {
+    let dir = sample_tree();
+    fs::write(dir.path().join("nested/.dedupignore"), "deeper/\n").unwrap();
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    assert_eq!(
+        relative_groups(dir.path(), &duplicates),
+        vec![
+            vec!["a.txt", "nested/a-copy.txt"],
+            vec!["backup/photo.jpg", "photo.jpg"]
+        ]
+    );
+}
+
+#[test]
+fn no_duplicates_means_nothing_handled() {
+    let dir = tree(&[("one", b"first"), ("two", b"second"), ("three", b"third")]);
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    assert!(duplicates.is_empty());
+    let handled = report_and_handle(
+        &duplicates,
+        SortOrder::Size,
+        false,
+        true,
+        &mut Action::Delete,
+        None,
+    );
+    assert_eq!(handled, Handled::default());
+}
+
+#[test]
+fn dry_run_deletes_nothing() {
+    let dir = sample_tree();
+    let before = remaining_files(dir.path());
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    let handled = report_and_handle(
+        &duplicates,
+        SortOrder::Size,
+        true,
+        true,
+        &mut Action::Delete,
+        None,
+    );
+    assert_eq!(handled, Handled::default());
+    assert_eq!(remaining_files(dir.path()), before);
+}
+
+#[test]
+fn deleting_keeps_one_copy_and_frees_the_rest() {
+    let dir = sample_tree();
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    let kept: Vec<PathBuf> = duplicates.values().map(|paths| paths[0].clone()).collect();
+
+    let handled = report_and_handle(
+        &duplicates,
+        SortOrder::Size,
+        false,
+        true,
+        &mut Action::Delete,
+        None,
+    );
+    assert_eq!(
+        handled,
+        Handled {
+            files: 3,
+            bytes: 2 * 11 + 4096,
+            failed: 0
+        }
+    );
+
+    for path in &kept {
+        assert!(path.exists(), "{} was kept", path.display());
+    }
+    let remaining = remaining_files(dir.path());
+    assert_eq!(remaining.len(), 4);
+    assert!(remaining.contains(&"decoy.txt".to_string()));
+    assert!(remaining.contains(&"unique.bin".to_string()));
+    // Scanning again finds nothing left to do
+    assert!(duplicates_in(dir.path(), &options(), HashAlgo::Blake3).is_empty());
+}
+
+#[test]
+fn duplicates_gone_before_deleting_count_as_failed() {
+    let dir = tree(&[("a", b"same"), ("b", b"same")]);
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    for paths in duplicates.values() {
+        fs::remove_file(&paths[1]).unwrap();
+    }
+
+    let handled = report_and_handle(
+        &duplicates,
+        SortOrder::Size,
+        false,
+        true,
+        &mut Action::Delete,
+        None,
+    );
+    assert_eq!(
+        handled,
+        Handled {
+            files: 0,
+            bytes: 0,
+            failed: 1
+        }
+    );
+}
+
+#[test]
+fn pruning_removes_directories_emptied_by_deletion() {
+    let dir = tree(&[("keep/file", b"same"), ("gone/inner/file", b"same")]);
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    let roots = [fs::canonicalize(dir.path()).unwrap()];
+    let root = &roots[0];
+    // The copy kept is the first one scanned; make it the one under keep/
+    let duplicates: HashMap<String, Vec<PathBuf>> = duplicates
+        .into_iter()
+        .map(|(hash, mut paths)| {
+            paths.sort_by_key(|path| !path.starts_with(root.join("keep")));
+            (hash, paths)
+        })
+        .collect();
+
+    let handled = report_and_handle(
+        &duplicates,
+        SortOrder::Size,
+        false,
+        true,
+        &mut Action::Delete,
+        Some(&roots),
+    );
+    assert_eq!(
+        handled,
+        Handled {
+            files: 1,
+            bytes: 4,
+            failed: 0
+        }
+    );
+    assert!(dir.path().join("keep/file").exists());
+    assert!(!dir.path().join("gone").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn hardlinking_leaves_every_path_in_place() {
+    use rust_dedup::cli::LinkMode;
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = sample_tree();
+    let before = remaining_files(dir.path());
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+
+    let handled = report_and_handle(
+        &duplicates,
+        SortOrder::Size,
+        false,
+        true,
+        &mut Action::Link(LinkMode::Hard),
+        None,
+    );
+    assert_eq!(
+        handled,
+        Handled {
+            files: 3,
+            bytes: 2 * 11 + 4096,
+            failed: 0
+        }
+    );
+
+    assert_eq!(remaining_files(dir.path()), before);
+    for paths in duplicates.values() {
+        let inode = fs::metadata(&paths[0]).unwrap().ino();
+        for path in paths {
+            assert_eq!(
+                fs::metadata(path).unwrap().ino(),
+                inode,
+                "{} is linked",
+                path.display()
+            );
+        }
+    }
+    assert_eq!(
+        fs::read(dir.path().join("nested/deeper/a-again.txt")).unwrap(),
+        b"hello world"
+    );
+}
+
+#[test]
+fn quarantined_duplicates_can_be_restored() {
+    let dir = sample_tree();
+    let aside = tempfile::tempdir().unwrap();
+    let before = remaining_files(dir.path());
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+
+    let mut action = Action::MoveAside(Quarantine::new(Bin::Dir(aside.path().to_path_buf()), None));
+    let handled = report_and_handle(&duplicates, SortOrder::Size, false, true, &mut action, None);
+    assert_eq!(
+        handled,
+        Handled {
+            files: 3,
+            bytes: 2 * 11 + 4096,
+            failed: 0
+        }
+    );
+    assert_eq!(remaining_files(dir.path()).len(), 4);
+
+    let Action::MoveAside(quarantine) = &action else {
+        unreachable!()
+    };
+    assert_eq!(quarantine.moved(), 3);
+    quarantine::restore(quarantine.manifest_path()).unwrap();
+    assert_eq!(remaining_files(dir.path()), before);
+}
+
+#[cfg(unix)]
+#[test]
+fn manifest_never_removes_the_kept_file_under_another_name() {
+    let dir = tree(&[("d/b.txt", b"same"), ("e/c.txt", b"same")]);
+    std::os::unix::fs::symlink(dir.path().join("d"), dir.path().join("alias")).unwrap();
+    let duplicates = duplicates_in(dir.path(), &options(), HashAlgo::Blake3);
+    let manifest_path = dir.path().join("manifest.json");
+    manifest::write(
+        &manifest_path,
+        &duplicates,
+        SortOrder::Size,
+        HashAlgo::Blake3,
+    )
+    .unwrap();
+
+    // Edited by hand to remove the kept copy through the symlinked directory
+    let mut manifest: serde_json::Value =
+        serde_json::from_slice(&fs::read(&manifest_path).unwrap()).unwrap();
+    let group = &mut manifest["groups"][0];
+    group["keep"] = serde_json::json!(fs::canonicalize(dir.path().join("d/b.txt")).unwrap());
+    group["remove"] = serde_json::json!([dir.path().join("alias/b.txt")]);
+    fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+
+    manifest::apply(&manifest_path, &mut Action::Delete, false).unwrap();
+    assert!(dir.path().join("d/b.txt").exists());
+    assert!(dir.path().join("e/c.txt").exists());
+}
@@ -0,0 +1,120 @@
+// Storage backend benchmarks: `cargo bench --bench storage`. Compare the numbers with
+// GET /admin/capacity on a loaded server when choosing STORAGE_BACKEND.
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rust_playground::User;
+use rust_playground::storage::{EventSourcedStore, InMemoryStore, UserStore};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const PREFILLED_USERS: usize = 10_000;
+const LISTED_USERS: usize = 1_000;
+const THREAD_COUNTS: [usize; 3] = [1, 4, 8];
+
+type Backend = (&'static str, fn() -> Arc<dyn UserStore>);
+
+const BACKENDS: [Backend; 2] = [
+    ("in-memory", || Arc::new(InMemoryStore::new())),
+    ("event-sourced", || Arc::new(EventSourcedStore::new())),
+];
+
+// User fields are private to the crate, so benchmark users come in through serde like
+// request bodies do
+fn user(n: usize, id: Uuid) -> User {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "name": format!("User {}", n),
+        "email": format!("user{}@example.com", n),
+        "age": 18 + (n % 60),
+        "latitude": (n % 180) as f64 - 90.0,
+        "longitude": (n % 360) as f64 - 180.0,
+    }))
+    .expect("benchmark user deserializes")
+}
+
+fn prefilled(make: fn() -> Arc<dyn UserStore>, count: usize) -> (Arc<dyn UserStore>, Vec<Uuid>) {
+    let store = make();
+    let mut ids = Vec::with_capacity(count);
+    for n in 0..count {
+        let id = Uuid::new_v4();
+        store.insert(user(n, id)).expect("insert succeeds");
+        ids.push(id);
+    }
+    (store, ids)
+}
+
+// Splits `iters` operations across `threads` threads and times the whole batch, so the
+// figure includes lock contention
+fn concurrent(threads: usize, iters: u64, op: impl Fn(u64) + Sync) -> Duration {
+    let per_thread = iters.div_ceil(threads as u64);
+    let started = Instant::now();
+    std::thread::scope(|scope| {
+        for t in 0..threads as u64 {
+            let op = &op;
+            scope.spawn(move || {
+                for i in 0..per_thread {
+                    op(t * per_thread + i);
+                }
+            });
+        }
+    });
+    started.elapsed()
+}
+
+fn create(c: &mut Criterion) {
+    let mut group = c.benchmark_group("create");
+    for (name, make) in BACKENDS {
+        for threads in THREAD_COUNTS {
+            group.bench_function(BenchmarkId::new(name, threads), |b| {
+                b.iter_custom(|iters| {
+                    let store = make();
+                    concurrent(threads, iters, |i| {
+                        store
+                            .insert(user(i as usize, Uuid::new_v4()))
+                            .expect("insert succeeds")
+                    })
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get");
+    for (name, make) in BACKENDS {
+        let (store, ids) = prefilled(make, PREFILLED_USERS);
+        for threads in THREAD_COUNTS {
+            group.bench_function(BenchmarkId::new(name, threads), |b| {
+                b.iter_custom(|iters| {
+                    concurrent(threads, iters, |i| {
+                        let id = ids[i as usize % ids.len()];
+                        black_box(store.get(id).expect("get succeeds"));
+                    })
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn list(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list");
+    for (name, make) in BACKENDS {
+        let (store, _) = prefilled(make, LISTED_USERS);
+        for threads in THREAD_COUNTS {
+            group.bench_function(BenchmarkId::new(name, threads), |b| {
+                b.iter_custom(|iters| {
+                    concurrent(threads, iters, |_| {
+                        black_box(store.list().expect("list succeeds"));
+                    })
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, create, get, list);
+criterion_main!(benches);
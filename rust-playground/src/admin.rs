@@ -0,0 +1,191 @@
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::cluster::Cluster;
+use crate::events::{ChangeKind, EventBus};
+use crate::problem::Problem;
+use crate::routes::{self, Auth, RouteTable};
+use crate::storage::CapacityReport;
+use crate::{ApiResponse, AppState, Database, seed_sample_users};
+use crate::{config, duplicates, metrics, replay};
+
+const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+#[derive(Debug, Clone)]
+pub struct AdminConfig {
+    token: Option<String>,
+    allow_destructive: bool,
+}
+
+impl AdminConfig {
+    pub fn from_env() -> Self {
+        Self {
+            token: std::env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty()),
+            allow_destructive: std::env::var("ALLOW_DESTRUCTIVE_ADMIN")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        }
+    }
+}
+
+// Rejects requests that don't carry the configured admin token
+async fn require_admin(
+    State(config): State<Arc<AdminConfig>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = config.token.as_deref() else {
+        warn!("Admin endpoint called but ADMIN_TOKEN is not configured");
+        return Problem::forbidden("Admin endpoints are not configured").into_response();
+    };
+
+    let provided = req
+        .headers()
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok());
+
+    if provided != Some(expected) {
+        warn!("Rejected admin request to {}", req.uri().path());
+        return Problem::unauthorized("Missing or invalid admin token").into_response();
+    }
+
+    next.run(req).await
+}
+
+fn ensure_destructive_allowed(config: &AdminConfig) -> Result<(), Problem> {
+    if config.allow_destructive {
+        Ok(())
+    } else {
+        Err(Problem::forbidden(
+            "Destructive admin endpoints are disabled (set ALLOW_DESTRUCTIVE_ADMIN=true)",
+        ))
+    }
+}
+
+// Clearing storage isn't replicated, and peers seed nothing, so a cluster would diverge
+fn ensure_not_clustered(cluster: &Cluster) -> Result<(), Problem> {
+    if cluster.enabled() {
+        Err(
+            Problem::new(StatusCode::CONFLICT, "cluster-enabled", "Cluster Enabled").with_detail(
+                "Clearing the database is not replicated, so it is refused while PEERS is set",
+            ),
+        )
+    } else {
+        Ok(())
+    }
+}
+
+async fn reset_database(
+    State(config): State<Arc<AdminConfig>>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+) -> Result<Json<ApiResponse<String>>, Problem> {
+    ensure_destructive_allowed(&config)?;
+    ensure_not_clustered(&cluster)?;
+
+    db.clear()?;
+    seed_sample_users(db.as_ref())?;
+    events.publish(ChangeKind::Reset, None, None);
+
+    let seeded = db.len()?;
+    info!("Database reset to seed data ({} users)", seeded);
+    Ok(Json(ApiResponse::success(format!(
+        "Database reset, {} seed user(s) loaded",
+        seeded
+    ))))
+}
+
+async fn delete_all_users(
+    State(config): State<Arc<AdminConfig>>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+) -> Result<Json<ApiResponse<String>>, Problem> {
+    ensure_destructive_allowed(&config)?;
+    ensure_not_clustered(&cluster)?;
+
+    let removed = db.clear()?;
+    events.publish(ChangeKind::Reset, None, None);
+
+    info!("Deleted all users ({} removed)", removed);
+    Ok(Json(ApiResponse::success(format!(
+        "Deleted {} user(s)",
+        removed
+    ))))
+}
+
+// Backend size and lock contention, to help choose a storage backend
+async fn capacity(
+    State(db): State<Database>,
+) -> Result<Json<ApiResponse<CapacityReport>>, Problem> {
+    Ok(Json(ApiResponse::success(db.capacity()?)))
+}
+
+pub fn routes(state: &AppState) -> RouteTable<AppState> {
+    RouteTable::new()
+        .route(
+            Method::POST,
+            "/admin/reset",
+            reset_database,
+            "Reset database to seed data",
+        )
+        .route(
+            Method::DELETE,
+            "/admin/users",
+            delete_all_users,
+            "Delete all users",
+        )
+        .route(
+            Method::GET,
+            "/admin/config",
+            config::get_config,
+            "Currently applied configuration",
+        )
+        .route(
+            Method::GET,
+            "/admin/stats",
+            metrics::admin_stats,
+            "Per-route latency and error rates",
+        )
+        .route(
+            Method::GET,
+            "/admin/capacity",
+            capacity,
+            "Store size, memory estimate and lock contention",
+        )
+        .route(
+            Method::GET,
+            "/admin/duplicates",
+            duplicates::list_duplicates,
+            "Likely duplicate users (same email, or same name and age)",
+        )
+        .route(
+            Method::POST,
+            "/admin/users/merge",
+            duplicates::merge_users,
+            "Merge one user into another",
+        )
+        .route(
+            Method::POST,
+            "/admin/replay/:id",
+            replay::replay,
+            "Re-execute a recorded request",
+        )
+        .route(
+            Method::GET,
+            "/admin/routes",
+            routes::list_routes,
+            "This route table",
+        )
+        .guard(
+            Auth::AdminToken,
+            middleware::from_fn_with_state(state.admin.clone(), require_admin),
+        )
+}
@@ -0,0 +1,173 @@
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, State},
+    http::request::Parts,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::problem::{AppPath, Problem};
+use crate::quota::Caller;
+use crate::{ApiResponse, Database, User, user_not_found};
+
+// How many field changes are kept per user; the oldest are dropped first
+const RETAINED_PER_USER: usize = 256;
+// Recorded as the actor when the request carried no API key
+const ANONYMOUS: &str = "anonymous";
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FieldChange {
+    field: String,
+    // null when the field was unset, e.g. a location being added
+    old: Value,
+    new: Value,
+    changed_at: DateTime<Utc>,
+    actor: String,
+}
+
+// Field-level change history per user, kept in memory whatever the storage backend.
+// Entries outlive the user so deletions can still be looked into.
+pub struct ChangeHistory {
+    users: Mutex<HashMap<Uuid, VecDeque<FieldChange>>>,
+}
+
+impl ChangeHistory {
+    pub fn new() -> Self {
+        Self {
+            users: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Records every field that differs between the two versions of a user
+    pub fn record(&self, actor: &str, before: &User, after: &User) {
+        let (Ok(Value::Object(old)), Ok(Value::Object(mut new))) =
+            (serde_json::to_value(before), serde_json::to_value(after))
+        else {
+            return;
+        };
+        let mut pairs: Vec<(String, Value, Value)> = old
+            .into_iter()
+            .map(|(field, old)| {
+                let new = new.remove(&field).unwrap_or(Value::Null);
+                (field, old, new)
+            })
+            .collect();
+        // Optional fields are skipped when unset, so some only appear afterwards
+        pairs.extend(
+            new.into_iter()
+                .map(|(field, new)| (field, Value::Null, new)),
+        );
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let changed_at = Utc::now();
+        let changes: Vec<FieldChange> = pairs
+            .into_iter()
+            .filter(|(field, old, new)| field != "id" && old != new)
+            .map(|(field, old, new)| FieldChange {
+                field,
+                old,
+                new,
+                changed_at,
+                actor: actor.to_string(),
+            })
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+
+        let mut users = self.users.lock().unwrap();
+        let log = users.entry(before.id).or_default();
+        for change in changes {
+            if log.len() == RETAINED_PER_USER {
+                log.pop_front();
+            }
+            log.push_back(change);
+        }
+    }
+
+    // Moves a merged-away user's changes over to the user it was merged into
+    pub fn absorb(&self, from: Uuid, into: Uuid) {
+        let mut users = self.users.lock().unwrap();
+        let Some(moved) = users.remove(&from) else {
+            return;
+        };
+        let log = users.entry(into).or_default();
+        log.extend(moved);
+        log.make_contiguous().sort_by_key(|c| c.changed_at);
+        while log.len() > RETAINED_PER_USER {
+            log.pop_front();
+        }
+    }
+
+    pub fn changes(&self, id: Uuid) -> Option<Vec<FieldChange>> {
+        let users = self.users.lock().unwrap();
+        users.get(&id).map(|log| log.iter().cloned().collect())
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/changes",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Field changes made to the user, oldest first", body = ApiResponse<Vec<FieldChange>>),
+        (status = 404, description = "User not found and no changes recorded"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+pub async fn get_user_changes(
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+    State(history): State<Arc<ChangeHistory>>,
+) -> Result<Json<ApiResponse<Vec<FieldChange>>>, Problem> {
+    info!("Getting field changes for user: {}", id);
+
+    match history.changes(id) {
+        Some(changes) => Ok(Json(ApiResponse::success(changes))),
+        // Known but never changed
+        None if db.get(id)?.is_some() => Ok(Json(ApiResponse::success(Vec::new()))),
+        None => Err(user_not_found(id)),
+    }
+}
+
+// The change history together with who is making the current request's changes:
+// the client behind its API key, if any
+pub struct Auditor {
+    history: Arc<ChangeHistory>,
+    actor: String,
+}
+
+impl Auditor {
+    pub fn record(&self, before: &User, after: &User) {
+        self.history.record(&self.actor, before, after);
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Auditor
+where
+    Arc<ChangeHistory>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let actor = parts
+            .extensions
+            .get::<Caller>()
+            .map_or(ANONYMOUS, |Caller(name)| name.as_str())
+            .to_string();
+        Ok(Auditor {
+            history: Arc::from_ref(state),
+            actor,
+        })
+    }
+}
@@ -0,0 +1,287 @@
+use axum::{
+    extract::State,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::deadline;
+use crate::geo::NearbyUser;
+use crate::problem::Problem;
+use crate::storage::{
+    CapacityReport, Merged, StorageError, StoreResult, StoredEvent, UserPatch, UserStore,
+};
+use crate::{ApiResponse, Database, User};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreakerState {
+    Closed,
+    // Calls fail fast without reaching the backend
+    Open,
+    // Cooldown is over; a single probe call decides whether to close or open again
+    HalfOpen,
+}
+
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    // A half-open probe is out, so other calls keep failing fast until it's back
+    probing: bool,
+    trips: u64,
+    last_error: Option<String>,
+}
+
+// Stops calling a failing storage backend for a cooldown once it has failed
+// BREAKER_FAILURE_THRESHOLD times in a row
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BreakerStatus {
+    state: BreakerState,
+    consecutive_failures: u32,
+    failure_threshold: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    trips: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<String>,
+}
+
+impl CircuitBreaker {
+    pub fn from_env() -> Self {
+        let failure_threshold = std::env::var("BREAKER_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|t| *t > 0)
+            .unwrap_or(5);
+        let cooldown_secs = std::env::var("BREAKER_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+        Self {
+            failure_threshold,
+            cooldown: Duration::from_secs(cooldown_secs),
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                open_until: None,
+                probing: false,
+                trips: 0,
+                last_error: None,
+            }),
+        }
+    }
+
+    // Whether a call may go through, and if so whether it's the half-open probe; moves an
+    // expired open breaker to half-open
+    fn admit(&self) -> StoreResult<bool> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == BreakerState::Open
+            && let Some(until) = inner.open_until
+        {
+            let now = Instant::now();
+            if now < until {
+                return Err(StorageError::CircuitOpen {
+                    retry_after: until - now,
+                });
+            }
+            info!("Storage circuit half-open, trying the backend again");
+            inner.state = BreakerState::HalfOpen;
+        }
+        if inner.state != BreakerState::HalfOpen {
+            return Ok(false);
+        }
+        if inner.probing {
+            return Err(StorageError::CircuitOpen {
+                retry_after: Duration::from_secs(1),
+            });
+        }
+        inner.probing = true;
+        Ok(true)
+    }
+
+    fn record<T>(&self, result: &StoreResult<T>) {
+        let mut inner = self.inner.lock().unwrap();
+        match result {
+            Ok(_) => {
+                if inner.state != BreakerState::Closed {
+                    info!("Storage backend recovered, circuit closed");
+                }
+                inner.state = BreakerState::Closed;
+                inner.consecutive_failures = 0;
+                inner.open_until = None;
+            }
+            Err(StorageError::CircuitOpen { .. } | StorageError::DeadlineExceeded) => {}
+            Err(StorageError::Unavailable(reason)) => {
+                inner.consecutive_failures += 1;
+                inner.last_error = Some(reason.clone());
+                let trip = inner.state == BreakerState::HalfOpen
+                    || inner.consecutive_failures >= self.failure_threshold;
+                if trip && inner.state != BreakerState::Open {
+                    warn!(
+                        "Storage failing ({}), opening circuit for {:?}",
+                        reason, self.cooldown
+                    );
+                    inner.state = BreakerState::Open;
+                    inner.open_until = Some(Instant::now() + self.cooldown);
+                    inner.trips += 1;
+                }
+            }
+        }
+    }
+
+    fn call<T>(&self, op: impl FnOnce() -> StoreResult<T>) -> StoreResult<T> {
+        let _probe = self.admit()?.then_some(Probe(self));
+        let result = op();
+        self.record(&result);
+        result
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().unwrap();
+        let retry_after_secs = match (inner.state, inner.open_until) {
+            (BreakerState::Open, Some(until)) => Some(
+                until
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+                    .ceil()
+                    .max(1.0) as u64,
+            ),
+            _ => None,
+        };
+        BreakerStatus {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            failure_threshold: self.failure_threshold,
+            retry_after_secs,
+            trips: inner.trips,
+            last_error: inner.last_error.clone(),
+        }
+    }
+}
+
+// The half-open probe that's out; dropping it lets the next one through, even if the
+// call panicked before its result was recorded
+struct Probe<'a>(&'a CircuitBreaker);
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.0.inner.lock() {
+            inner.probing = false;
+        }
+    }
+}
+
+// Wraps the configured backend so every call goes through the breaker
+pub struct GuardedStore {
+    inner: Box<dyn UserStore>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl GuardedStore {
+    pub fn new(inner: Box<dyn UserStore>, breaker: Arc<CircuitBreaker>) -> Self {
+        Self { inner, breaker }
+    }
+
+    // Out-of-time requests don't reach the backend, and don't count against it either
+    fn call<T>(
+        &self,
+        operation: &'static str,
+        op: impl FnOnce() -> StoreResult<T>,
+    ) -> StoreResult<T> {
+        deadline::check_storage(operation).map_err(|_| StorageError::DeadlineExceeded)?;
+        self.breaker.call(op)
+    }
+}
+
+impl UserStore for GuardedStore {
+    fn list(&self) -> StoreResult<Vec<User>> {
+        self.call("list", || self.inner.list())
+    }
+
+    fn get(&self, id: Uuid) -> StoreResult<Option<User>> {
+        self.call("get", || self.inner.get(id))
+    }
+
+    fn insert(&self, user: User) -> StoreResult<()> {
+        self.call("insert", || self.inner.insert(user))
+    }
+
+    fn update(&self, id: Uuid, patch: UserPatch) -> StoreResult<Option<User>> {
+        self.call("update", || self.inner.update(id, patch))
+    }
+
+    fn update_if(
+        &self,
+        id: Uuid,
+        decide: &dyn Fn(&User) -> Option<UserPatch>,
+    ) -> StoreResult<Option<(User, Option<User>)>> {
+        self.call("update_if", || self.inner.update_if(id, decide))
+    }
+
+    fn delete(&self, id: Uuid) -> StoreResult<Option<User>> {
+        self.call("delete", || self.inner.delete(id))
+    }
+
+    fn merge(
+        &self,
+        keep: Uuid,
+        merge: Uuid,
+        patch_for: &dyn Fn(&User, &User) -> UserPatch,
+    ) -> StoreResult<Result<Merged, Uuid>> {
+        self.call("merge", || self.inner.merge(keep, merge, patch_for))
+    }
+
+    fn clear(&self) -> StoreResult<usize> {
+        self.call("clear", || self.inner.clear())
+    }
+
+    fn len(&self) -> StoreResult<usize> {
+        self.call("len", || self.inner.len())
+    }
+
+    fn capacity(&self) -> StoreResult<CapacityReport> {
+        self.call("capacity", || self.inner.capacity())
+    }
+
+    fn history(&self, id: Uuid) -> StoreResult<Option<Vec<StoredEvent>>> {
+        self.call("history", || self.inner.history(id))
+    }
+
+    fn nearby(&self, lat: f64, lon: f64, radius_km: f64) -> StoreResult<Vec<NearbyUser>> {
+        self.call("nearby", || self.inner.nearby(lat, lon, radius_km))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    ready: bool,
+    storage: BreakerStatus,
+}
+
+// Readiness for load balancers: a 503 problem, with the breaker's status alongside, while
+// storage is unreachable or the breaker is open, whereas /health only says the process is up
+pub async fn readiness(
+    State(db): State<Database>,
+    State(breaker): State<Arc<CircuitBreaker>>,
+) -> Response {
+    if let Err(e) = db.len() {
+        return Problem::from(e)
+            .with_extension("storage", breaker.status())
+            .into_response();
+    }
+    let body = Readiness {
+        ready: true,
+        storage: breaker.status(),
+    };
+    Json(ApiResponse::success(body)).into_response()
+}
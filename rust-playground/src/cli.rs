@@ -0,0 +1,125 @@
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(name = "rust-playground", about = "User management API playground")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the API server (the default when no subcommand is given)
+    Serve,
+    /// Call a running server from the command line
+    Client(ClientArgs),
+    /// Regenerate the typed client from the OpenAPI document
+    Codegen(CodegenArgs),
+    /// Exit 0 if the server is ready and 1 otherwise, e.g. for a container HEALTHCHECK
+    Healthcheck(HealthcheckArgs),
+}
+
+#[derive(ClapArgs)]
+pub struct HealthcheckArgs {
+    /// Readiness URL to probe (defaults to /health/ready on localhost at $PORT, or 3000)
+    #[arg(long, env = "HEALTHCHECK_URL")]
+    pub url: Option<String>,
+
+    /// Give up after this many seconds
+    #[arg(long, default_value = "5")]
+    pub timeout_secs: u64,
+}
+
+#[derive(ClapArgs)]
+pub struct CodegenArgs {
+    /// File to write (defaults to src/client/generated.rs in this crate)
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+
+    /// Fail instead of writing if the file is out of date
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(ClapArgs)]
+pub struct ClientArgs {
+    /// Base URL of the server
+    #[arg(long, env = "PLAYGROUND_URL", default_value = "http://localhost:3000")]
+    pub url: String,
+
+    #[command(subcommand)]
+    pub command: ClientCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ClientCommand {
+    /// Check that the server is up
+    Health,
+    /// Manage users
+    #[command(subcommand)]
+    Users(UsersCommand),
+}
+
+#[derive(Subcommand)]
+pub enum UsersCommand {
+    /// List all users
+    List,
+    /// Show one user
+    Get { id: Uuid },
+    /// Create a user
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        age: u32,
+        /// Create the user as invited rather than active
+        #[arg(long)]
+        invited: bool,
+        #[command(flatten)]
+        location: LocationArgs,
+    },
+    /// Change some fields of a user
+    Update {
+        id: Uuid,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        email: Option<String>,
+        #[arg(long)]
+        age: Option<u32>,
+        #[command(flatten)]
+        location: LocationArgs,
+    },
+    /// Delete a user
+    Delete { id: Uuid },
+    /// Activate an invited or suspended user
+    Activate { id: Uuid },
+    /// Suspend an active user
+    Suspend { id: Uuid },
+    /// Deactivate a user for good
+    Deactivate { id: Uuid },
+    /// Find users near a point
+    Nearby {
+        #[arg(long, allow_hyphen_values = true)]
+        lat: f64,
+        #[arg(long, allow_hyphen_values = true)]
+        lon: f64,
+        #[arg(long)]
+        radius_km: f64,
+    },
+}
+
+#[derive(ClapArgs)]
+pub struct LocationArgs {
+    /// Latitude in degrees (requires --lon)
+    #[arg(long, requires = "lon", allow_hyphen_values = true)]
+    pub lat: Option<f64>,
+
+    /// Longitude in degrees (requires --lat)
+    #[arg(long, requires = "lat", allow_hyphen_values = true)]
+    pub lon: Option<f64>,
+}
@@ -0,0 +1,189 @@
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::audit::FieldChange;
+use crate::cli::{ClientArgs, ClientCommand, HealthcheckArgs, LocationArgs, UsersCommand};
+use crate::events::ChangesResponse;
+use crate::geo::NearbyUser;
+use crate::lifecycle::UserStatus;
+use crate::stats::UserStats;
+use crate::storage::StoredEvent;
+use crate::{ApiResponse, CreateUserRequest, UpdateUserRequest, User};
+
+// One method per documented operation, generated from the OpenAPI document
+#[rustfmt::skip]
+mod generated;
+
+// Thin typed wrapper over the HTTP API, using the same DTOs as the handlers
+struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl ApiClient {
+    fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Request to {} failed: {}", self.base_url, e))?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+
+        if !status.is_success() {
+            return Err(describe_problem(status, &body));
+        }
+        let parsed: ApiResponse<T> = serde_json::from_slice(&body)
+            .map_err(|e| format!("Unexpected response from server: {}", e))?;
+        parsed
+            .data
+            .ok_or_else(|| format!("Server returned no data: {}", parsed.message))
+    }
+}
+
+// Renders an RFC 7807 error body as one readable line (plus any schema violations)
+fn describe_problem(status: StatusCode, body: &[u8]) -> String {
+    let Ok(problem) = serde_json::from_slice::<Value>(body) else {
+        return format!("{}: {}", status, String::from_utf8_lossy(body));
+    };
+    let title = problem["title"].as_str().unwrap_or("Request failed");
+    let mut message = match problem["detail"].as_str() {
+        Some(detail) => format!("{} {}: {}", status.as_u16(), title, detail),
+        None => format!("{} {}", status.as_u16(), title),
+    };
+    for violation in problem["violations"].as_array().into_iter().flatten() {
+        message.push_str(&format!(
+            "\n  {}: {}",
+            violation["pointer"]
+                .as_str()
+                .or(violation["field"].as_str())
+                .unwrap_or(""),
+            violation["message"].as_str().unwrap_or("")
+        ));
+    }
+    message
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to format output: {}", e),
+    }
+}
+
+// Probes the readiness endpoint; any error or non-2xx answer is a failure
+pub async fn healthcheck(args: HealthcheckArgs) -> Result<(), String> {
+    let url = args.url.unwrap_or_else(|| {
+        let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
+        format!("http://127.0.0.1:{}/health/ready", port)
+    });
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(args.timeout_secs))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = http
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Unhealthy: {} failed: {}", url, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Unhealthy: {} answered {}", url, status));
+    }
+    println!("Healthy: {} answered {}", url, status);
+    Ok(())
+}
+
+pub async fn run(args: ClientArgs) -> Result<(), String> {
+    let api = ApiClient::new(&args.url);
+
+    match args.command {
+        ClientCommand::Health => {
+            let message = api.health_check().await?;
+            println!("{}", message);
+        }
+        ClientCommand::Users(command) => users(&api, command).await?,
+    }
+    Ok(())
+}
+
+async fn users(api: &ApiClient, command: UsersCommand) -> Result<(), String> {
+    match command {
+        UsersCommand::List => {
+            let users = api.get_all_users().await?;
+            print_json(&users);
+        }
+        UsersCommand::Get { id } => {
+            let user = api.get_user_by_id(id).await?;
+            print_json(&user);
+        }
+        UsersCommand::Create {
+            name,
+            email,
+            age,
+            invited,
+            location: LocationArgs { lat, lon },
+        } => {
+            let body = CreateUserRequest {
+                name,
+                email,
+                age,
+                status: invited.then_some(UserStatus::Invited),
+                latitude: lat,
+                longitude: lon,
+            };
+            let user = api.create_user(&body).await?;
+            print_json(&user);
+        }
+        UsersCommand::Update {
+            id,
+            name,
+            email,
+            age,
+            location: LocationArgs { lat, lon },
+        } => {
+            let body = UpdateUserRequest {
+                name,
+                email,
+                age,
+                latitude: lat,
+                longitude: lon,
+            };
+            let user = api.update_user(id, &body).await?;
+            print_json(&user);
+        }
+        UsersCommand::Delete { id } => {
+            let message = api.delete_user(id).await?;
+            println!("{}", message);
+        }
+        UsersCommand::Activate { id } => print_json(&api.activate_user(id).await?),
+        UsersCommand::Suspend { id } => print_json(&api.suspend_user(id).await?),
+        UsersCommand::Deactivate { id } => print_json(&api.deactivate_user(id).await?),
+        UsersCommand::Nearby {
+            lat,
+            lon,
+            radius_km,
+        } => {
+            let nearby = api.nearby_users(lat, lon, radius_km).await?;
+            print_json(&nearby);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// @generated by `rust-playground codegen` from the OpenAPI document. Do not edit by hand:
+// change the documented handlers and regenerate, or `codegen --check` will fail.
+#![allow(dead_code)]
+
+use super::*;
+
+impl ApiClient {
+    // GET /health
+    pub async fn health_check(&self) -> Result<String, String> {
+        self.send(self.http.get(self.url("/health"))).await
+    }
+
+    // GET /users
+    pub async fn get_all_users(&self) -> Result<Vec<User>, String> {
+        self.send(self.http.get(self.url("/users"))).await
+    }
+
+    // POST /users
+    pub async fn create_user(&self, body: &CreateUserRequest) -> Result<User, String> {
+        self.send(self.http.post(self.url("/users")).json(body)).await
+    }
+
+    // GET /users/changes
+    pub async fn long_poll_changes(&self, since: Option<u64>, wait: Option<&str>) -> Result<ChangesResponse, String> {
+        let mut query: Vec<(&str, String)> = Vec::new();
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+        if let Some(wait) = wait {
+            query.push(("wait", wait.to_string()));
+        }
+        self.send(self.http.get(self.url("/users/changes")).query(&query)).await
+    }
+
+    // GET /users/nearby
+    pub async fn nearby_users(&self, lat: f64, lon: f64, radius_km: f64) -> Result<Vec<NearbyUser>, String> {
+        let mut query: Vec<(&str, String)> = Vec::new();
+        query.push(("lat", lat.to_string()));
+        query.push(("lon", lon.to_string()));
+        query.push(("radius_km", radius_km.to_string()));
+        self.send(self.http.get(self.url("/users/nearby")).query(&query)).await
+    }
+
+    // GET /users/stats
+    pub async fn user_stats(&self) -> Result<UserStats, String> {
+        self.send(self.http.get(self.url("/users/stats"))).await
+    }
+
+    // DELETE /users/{id}
+    pub async fn delete_user(&self, id: Uuid) -> Result<String, String> {
+        self.send(self.http.delete(self.url(&format!("/users/{}", id)))).await
+    }
+
+    // GET /users/{id}
+    pub async fn get_user_by_id(&self, id: Uuid) -> Result<User, String> {
+        self.send(self.http.get(self.url(&format!("/users/{}", id)))).await
+    }
+
+    // PUT /users/{id}
+    pub async fn update_user(&self, id: Uuid, body: &UpdateUserRequest) -> Result<User, String> {
+        self.send(self.http.put(self.url(&format!("/users/{}", id))).json(body)).await
+    }
+
+    // POST /users/{id}/activate
+    pub async fn activate_user(&self, id: Uuid) -> Result<User, String> {
+        self.send(self.http.post(self.url(&format!("/users/{}/activate", id)))).await
+    }
+
+    // GET /users/{id}/changes
+    pub async fn get_user_changes(&self, id: Uuid) -> Result<Vec<FieldChange>, String> {
+        self.send(self.http.get(self.url(&format!("/users/{}/changes", id)))).await
+    }
+
+    // POST /users/{id}/deactivate
+    pub async fn deactivate_user(&self, id: Uuid) -> Result<User, String> {
+        self.send(self.http.post(self.url(&format!("/users/{}/deactivate", id)))).await
+    }
+
+    // GET /users/{id}/history
+    pub async fn get_user_history(&self, id: Uuid) -> Result<Vec<StoredEvent>, String> {
+        self.send(self.http.get(self.url(&format!("/users/{}/history", id)))).await
+    }
+
+    // POST /users/{id}/suspend
+    pub async fn suspend_user(&self, id: Uuid) -> Result<User, String> {
+        self.send(self.http.post(self.url(&format!("/users/{}/suspend", id)))).await
+    }
+}
@@ -0,0 +1,466 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::events::{ChangeKind, EventBus};
+use crate::problem::{AppJson, Problem};
+use crate::routes::{Auth, RouteTable};
+use crate::storage::{StoreResult, UserPatch};
+use crate::validation::ValidatorRegistry;
+use crate::{ApiResponse, AppState, Database, User};
+
+const CLUSTER_TOKEN_HEADER: &str = "x-cluster-token";
+const PEER_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Last-write-wins version: wall-clock millis, with the node id breaking ties
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct Version {
+    millis: u64,
+    node: String,
+}
+
+impl Version {
+    // Records written before this node knew about replication (e.g. seed data)
+    fn zero() -> Self {
+        Self {
+            millis: 0,
+            node: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Mutation {
+    Upsert { user: User, version: Version },
+    Delete { id: Uuid, version: Version },
+}
+
+impl Mutation {
+    fn id(&self) -> Uuid {
+        match self {
+            Mutation::Upsert { user, .. } => user.id,
+            Mutation::Delete { id, .. } => *id,
+        }
+    }
+
+    fn version(&self) -> &Version {
+        match self {
+            Mutation::Upsert { version, .. } | Mutation::Delete { version, .. } => version,
+        }
+    }
+}
+
+struct RecordVersion {
+    version: Version,
+    deleted: bool,
+}
+
+#[derive(Default)]
+struct ClusterCounters {
+    sent: AtomicU64,
+    send_failures: AtomicU64,
+    applied: AtomicU64,
+    ignored_stale: AtomicU64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClusterStatus {
+    node_id: String,
+    peers: Vec<String>,
+    tracked_records: usize,
+    tombstones: usize,
+    replication_sent: u64,
+    replication_send_failures: u64,
+    replication_applied: u64,
+    replication_ignored_stale: u64,
+}
+
+// Full-mesh, multi-primary replication: every local write is pushed to all peers, and
+// receivers keep whichever version is newest (deletes leave tombstones so they win too)
+pub struct Cluster {
+    node_id: String,
+    peers: Vec<String>,
+    token: Option<String>,
+    client: reqwest::Client,
+    // Highest version millis issued or observed, so local versions never go backwards
+    clock: Mutex<u64>,
+    // Latest version per record; deletes are kept as tombstones so they win over stale upserts
+    records: Mutex<HashMap<Uuid, RecordVersion>>,
+    counters: ClusterCounters,
+}
+
+impl Cluster {
+    // Peers can write any record through /internal/replicate, so replication refuses to run
+    // without a CLUSTER_TOKEN to authenticate them
+    pub fn from_env() -> Result<Self, String> {
+        let peers: Vec<String> = std::env::var("PEERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|p| p.trim().trim_end_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        let token = std::env::var("CLUSTER_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty());
+        if !peers.is_empty() && token.is_none() {
+            return Err("PEERS is set but CLUSTER_TOKEN isn't".to_string());
+        }
+
+        Ok(Self {
+            node_id: std::env::var("NODE_ID").unwrap_or_else(|_| Uuid::new_v4().to_string()),
+            peers,
+            token,
+            client: reqwest::Client::builder()
+                .timeout(PEER_TIMEOUT)
+                .build()
+                .expect("HTTP client builds"),
+            clock: Mutex::new(0),
+            records: Mutex::new(HashMap::new()),
+            counters: ClusterCounters::default(),
+        })
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.peers.is_empty()
+    }
+
+    fn next_version(&self) -> Version {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut clock = self.clock.lock().unwrap();
+        *clock = now.max(*clock + 1);
+        Version {
+            millis: *clock,
+            node: self.node_id.clone(),
+        }
+    }
+
+    fn observe(&self, version: &Version) {
+        let mut clock = self.clock.lock().unwrap();
+        *clock = (*clock).max(version.millis);
+    }
+
+    fn current_version(&self, id: Uuid) -> Version {
+        self.records
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map_or_else(Version::zero, |r| r.version.clone())
+    }
+
+    // Runs `write` and then stores the version, if it is newer than anything seen for the
+    // record. A failed write leaves the version unrecorded, so a retry isn't dropped as stale;
+    // the lock is held across it so an older mutation can't land in between.
+    fn accept(
+        &self,
+        mutation: &Mutation,
+        write: impl FnOnce() -> StoreResult<()>,
+    ) -> StoreResult<bool> {
+        let version = mutation.version();
+        self.observe(version);
+
+        let mut records = self.records.lock().unwrap();
+        if records
+            .get(&mutation.id())
+            .is_some_and(|r| *version <= r.version)
+        {
+            return Ok(false);
+        }
+        write()?;
+        records.insert(
+            mutation.id(),
+            RecordVersion {
+                version: version.clone(),
+                deleted: matches!(mutation, Mutation::Delete { .. }),
+            },
+        );
+        Ok(true)
+    }
+
+    pub fn replicate_upsert(self: &Arc<Self>, user: &User) {
+        if !self.enabled() {
+            return;
+        }
+        let mutation = Mutation::Upsert {
+            user: user.clone(),
+            version: self.next_version(),
+        };
+        // Already written by the caller
+        let _ = self.accept(&mutation, || Ok(()));
+        self.broadcast(mutation);
+    }
+
+    pub fn replicate_delete(self: &Arc<Self>, id: Uuid) {
+        if !self.enabled() {
+            return;
+        }
+        let mutation = Mutation::Delete {
+            id,
+            version: self.next_version(),
+        };
+        let _ = self.accept(&mutation, || Ok(()));
+        self.broadcast(mutation);
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => builder.header(CLUSTER_TOKEN_HEADER, token),
+            None => builder,
+        }
+    }
+
+    fn broadcast(self: &Arc<Self>, mutation: Mutation) {
+        for peer in self.peers.clone() {
+            let cluster = Arc::clone(self);
+            let mutation = mutation.clone();
+            tokio::spawn(async move {
+                let url = format!("{}/internal/replicate", peer);
+                let result = cluster
+                    .request(cluster.client.post(&url))
+                    .json(&mutation)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status());
+                match result {
+                    Ok(_) => {
+                        cluster.counters.sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        cluster
+                            .counters
+                            .send_failures
+                            .fetch_add(1, Ordering::Relaxed);
+                        warn!("Replication to {} failed: {}", peer, e);
+                    }
+                }
+            });
+        }
+    }
+
+    // Forgets deletes older than the cutoff. A peer that was offline for longer than that
+    // could reintroduce the deleted record, so the retention should exceed expected outages.
+    pub fn purge_tombstones(&self, older_than: SystemTime) -> usize {
+        let cutoff = older_than
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut records = self.records.lock().unwrap();
+        let before = records.len();
+        records.retain(|_, r| !(r.deleted && r.version.millis < cutoff));
+        before - records.len()
+    }
+
+    fn status(&self) -> ClusterStatus {
+        let records = self.records.lock().unwrap();
+        ClusterStatus {
+            node_id: self.node_id.clone(),
+            peers: self.peers.clone(),
+            tracked_records: records.len(),
+            tombstones: records.values().filter(|r| r.deleted).count(),
+            replication_sent: self.counters.sent.load(Ordering::Relaxed),
+            replication_send_failures: self.counters.send_failures.load(Ordering::Relaxed),
+            replication_applied: self.counters.applied.load(Ordering::Relaxed),
+            replication_ignored_stale: self.counters.ignored_stale.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// A peer's user has to pass the same rules as one created or updated through the API
+fn check_remote(validators: &ValidatorRegistry, mutation: &Mutation) -> Result<(), Problem> {
+    match mutation {
+        Mutation::Upsert { user, .. } => validators.check(user),
+        Mutation::Delete { .. } => Ok(()),
+    }
+}
+
+// Applies a mutation from a peer to local storage if it wins last-write-wins
+fn apply_remote(
+    cluster: &Cluster,
+    db: &Database,
+    events: &EventBus,
+    mutation: Mutation,
+) -> StoreResult<()> {
+    let applied = cluster.accept(&mutation, || write_remote(db, events, &mutation))?;
+    let counter = if applied {
+        &cluster.counters.applied
+    } else {
+        &cluster.counters.ignored_stale
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+fn write_remote(db: &Database, events: &EventBus, mutation: &Mutation) -> StoreResult<()> {
+    match mutation.clone() {
+        Mutation::Upsert { user, .. } => {
+            let patch = UserPatch {
+                name: Some(user.name.clone()),
+                email: Some(user.email.clone()),
+                age: Some(user.age),
+                status: Some(user.status),
+                latitude: user.latitude,
+                longitude: user.longitude,
+            };
+            if db.update(user.id, patch)?.is_some() {
+                events.publish(ChangeKind::Updated, Some(user.id), Some(user));
+            } else {
+                db.insert(user.clone())?;
+                events.publish(ChangeKind::Created, Some(user.id), Some(user));
+            }
+        }
+        Mutation::Delete { id, .. } => {
+            if db.delete(id)?.is_some() {
+                events.publish(ChangeKind::Deleted, Some(id), None);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Everything this node knows, including tombstones, so a joining peer can catch up
+fn snapshot(cluster: &Cluster, db: &Database) -> StoreResult<Vec<Mutation>> {
+    let mut mutations: Vec<Mutation> = db
+        .list()?
+        .into_iter()
+        .map(|user| {
+            let version = cluster.current_version(user.id);
+            Mutation::Upsert { user, version }
+        })
+        .collect();
+    let records = cluster.records.lock().unwrap();
+    mutations.extend(
+        records
+            .iter()
+            .filter(|(_, r)| r.deleted)
+            .map(|(id, r)| Mutation::Delete {
+                id: *id,
+                version: r.version.clone(),
+            }),
+    );
+    Ok(mutations)
+}
+
+// Pulls a snapshot from each reachable peer at startup
+pub async fn bootstrap(
+    cluster: Arc<Cluster>,
+    db: Database,
+    events: Arc<EventBus>,
+    validators: Arc<ValidatorRegistry>,
+) {
+    for peer in &cluster.peers {
+        let url = format!("{}/internal/snapshot", peer);
+        let result = cluster.request(cluster.client.get(&url)).send().await;
+        let mutations = match result {
+            Ok(response) => match response.json::<Vec<Mutation>>().await {
+                Ok(mutations) => mutations,
+                Err(e) => {
+                    warn!("Invalid snapshot from {}: {}", peer, e);
+                    continue;
+                }
+            },
+            Err(e) => {
+                info!("Peer {} not reachable during bootstrap: {}", peer, e);
+                continue;
+            }
+        };
+        info!("Merging {} record(s) from {}", mutations.len(), peer);
+        for mutation in mutations {
+            if check_remote(&validators, &mutation).is_err() {
+                warn!(
+                    "Skipping record {} from {}: it breaks a validation rule",
+                    mutation.id(),
+                    peer
+                );
+                continue;
+            }
+            if let Err(e) = apply_remote(&cluster, &db, &events, mutation) {
+                warn!("Stopped merging the snapshot from {}: {}", peer, e);
+                break;
+            }
+        }
+    }
+}
+
+async fn require_cluster_token(
+    State(cluster): State<Arc<Cluster>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let provided = req
+        .headers()
+        .get(CLUSTER_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok());
+    if cluster.token.is_none() || provided != cluster.token.as_deref() {
+        warn!("Rejected cluster request to {}", req.uri().path());
+        return Problem::unauthorized("Missing or invalid cluster token").into_response();
+    }
+    next.run(req).await
+}
+
+async fn receive_mutation(
+    State(cluster): State<Arc<Cluster>>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(validators): State<Arc<ValidatorRegistry>>,
+    AppJson(mutation): AppJson<Mutation>,
+) -> Result<Json<ApiResponse<String>>, Problem> {
+    check_remote(&validators, &mutation)?;
+    apply_remote(&cluster, &db, &events, mutation)?;
+    Ok(Json(ApiResponse::success("Applied".to_string())))
+}
+
+async fn send_snapshot(
+    State(cluster): State<Arc<Cluster>>,
+    State(db): State<Database>,
+) -> Result<Json<Vec<Mutation>>, Problem> {
+    Ok(Json(snapshot(&cluster, &db)?))
+}
+
+async fn cluster_status(State(cluster): State<Arc<Cluster>>) -> Json<ApiResponse<ClusterStatus>> {
+    Json(ApiResponse::success(cluster.status()))
+}
+
+pub fn routes(state: &AppState) -> RouteTable<AppState> {
+    let status = RouteTable::new().route(
+        Method::GET,
+        "/cluster/status",
+        cluster_status,
+        "Replication status",
+    );
+    // Without peers nothing replicates, and nothing may write through the internal endpoints
+    if !state.cluster.enabled() {
+        return status;
+    }
+    RouteTable::new()
+        .route(
+            Method::POST,
+            "/internal/replicate",
+            receive_mutation,
+            "Apply a mutation from a peer",
+        )
+        .route(
+            Method::GET,
+            "/internal/snapshot",
+            send_snapshot,
+            "Full state for a joining peer",
+        )
+        .guard(
+            Auth::ClusterToken,
+            middleware::from_fn_with_state(state.cluster.clone(), require_cluster_token),
+        )
+        .merge(status)
+}
@@ -0,0 +1,158 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{HeaderMap, HeaderValue, StatusCode, header, request::Parts},
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::convert::Infallible;
+
+use crate::problem::{AppJson, Problem};
+
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+// Wire formats the CRUD endpoints speak; JSON stays the default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    MsgPack,
+}
+
+fn is_msgpack(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or("").trim();
+    essence.eq_ignore_ascii_case(MSGPACK_CONTENT_TYPE)
+        || essence.eq_ignore_ascii_case("application/x-msgpack")
+}
+
+pub fn is_msgpack_body(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(is_msgpack)
+}
+
+impl Format {
+    // Picks msgpack only when the client explicitly prefers it, honouring q-values
+    fn from_accept(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()) else {
+            return Format::Json;
+        };
+        // Highest q-value wins; ties go to whichever range the client listed first
+        let mut best: Option<(Format, f32)> = None;
+        for range in accept.split(',') {
+            let mut params = range.split(';');
+            let media_type = params.next().unwrap_or("").trim();
+            let quality = params
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let format = if is_msgpack(media_type) {
+                Format::MsgPack
+            } else if media_type.eq_ignore_ascii_case("application/json")
+                || media_type == "application/*"
+                || media_type == "*/*"
+            {
+                Format::Json
+            } else {
+                continue;
+            };
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((format, quality));
+            }
+        }
+        best.map_or(Format::Json, |(format, _)| format)
+    }
+}
+
+// The response format the client asked for via Accept
+pub struct Accept(pub Format);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Accept {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Accept(Format::from_accept(&parts.headers)))
+    }
+}
+
+// Request body decoded from JSON or MessagePack depending on Content-Type
+pub struct AppBody<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for AppBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !is_msgpack_body(req.headers()) {
+            let AppJson(value) = AppJson::<T>::from_request(req, state).await?;
+            return Ok(AppBody(value));
+        }
+
+        let bytes = Bytes::from_request(req, state).await.map_err(|e| {
+            Problem::new(e.status(), "malformed-body", "Malformed Request Body")
+                .with_detail(e.body_text())
+        })?;
+        let mut deserializer = rmp_serde::Deserializer::new(&bytes[..]).with_human_readable();
+        T::deserialize(&mut deserializer).map(AppBody).map_err(|e| {
+            Problem::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid-body",
+                "Invalid Request Body",
+            )
+            .with_detail(format!("Failed to decode the MessagePack body: {}", e))
+        })
+    }
+}
+
+// Decodes a MessagePack body into a JSON value, e.g. for schema validation
+pub fn decode_msgpack_value(bytes: &[u8]) -> Option<serde_json::Value> {
+    let mut deserializer = rmp_serde::Deserializer::new(bytes).with_human_readable();
+    serde::Deserialize::deserialize(&mut deserializer).ok()
+}
+
+// Maps keyed by field name and ids as strings, so the payload has the same shape as the JSON
+fn encode_msgpack<T: Serialize>(value: &T) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    let mut body = Vec::new();
+    let mut serializer = rmp_serde::Serializer::new(&mut body)
+        .with_struct_map()
+        .with_human_readable();
+    value.serialize(&mut serializer)?;
+    Ok(body)
+}
+
+// Response body encoded in the negotiated format
+pub struct Negotiated<T>(pub Format, pub T);
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        match self.0 {
+            Format::Json => ([(header::VARY, "accept")], Json(self.1)).into_response(),
+            Format::MsgPack => match encode_msgpack(&self.1) {
+                Ok(body) => (
+                    [
+                        (
+                            header::CONTENT_TYPE,
+                            HeaderValue::from_static(MSGPACK_CONTENT_TYPE),
+                        ),
+                        (header::VARY, HeaderValue::from_static("accept")),
+                    ],
+                    body,
+                )
+                    .into_response(),
+                Err(e) => Problem::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "encoding-failed",
+                    "Response Encoding Failed",
+                )
+                .with_detail(e.to_string())
+                .into_response(),
+            },
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::PathBuf;
+use utoipa::OpenApi;
+
+use crate::cli::CodegenArgs;
+use crate::openapi::ApiDoc;
+
+// Where the client module lives in this crate; regenerated in place by default
+pub const DEFAULT_OUTPUT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/client/generated.rs");
+
+const HEADER: &str = "\
+// @generated by `rust-playground codegen` from the OpenAPI document. Do not edit by hand:
+// change the documented handlers and regenerate, or `codegen --check` will fail.
+#![allow(dead_code)]
+
+use super::*;
+
+impl ApiClient {
+";
+
+struct Param {
+    name: String,
+    rust_type: String,
+    in_query: bool,
+    // Optional query parameters are only sent when given
+    optional: bool,
+}
+
+// Rust type for a parameter schema; component references name the server's own DTOs
+fn rust_type(schema: &Value) -> String {
+    if let Some(reference) = schema["$ref"].as_str() {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    let (kind, nullable) = match &schema["type"] {
+        Value::String(kind) => (kind.as_str(), false),
+        Value::Array(kinds) => (
+            kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|k| *k != "null")
+                .unwrap_or("null"),
+            kinds.iter().any(|k| k == "null"),
+        ),
+        _ => ("", false),
+    };
+    let unsigned = schema["minimum"].as_f64().is_some_and(|m| m >= 0.0);
+    let base = match (kind, schema["format"].as_str()) {
+        ("string", Some("uuid")) => "Uuid".to_string(),
+        ("string", _) => "String".to_string(),
+        ("integer", Some("int64")) if unsigned => "u64".to_string(),
+        ("integer", Some("int64")) => "i64".to_string(),
+        ("integer", _) if unsigned => "u32".to_string(),
+        ("integer", _) => "i32".to_string(),
+        ("number", _) => "f64".to_string(),
+        ("boolean", _) => "bool".to_string(),
+        ("array", _) => format!("Vec<{}>", rust_type(&schema["items"])),
+        _ => "serde_json::Value".to_string(),
+    };
+    if nullable {
+        format!("Option<{}>", base)
+    } else {
+        base
+    }
+}
+
+// utoipa names generic instantiations after their arguments, e.g. ApiResponse_Vec_User
+fn generic_type(name: &str) -> String {
+    match name.strip_prefix("Vec_") {
+        Some(inner) => format!("Vec<{}>", generic_type(inner)),
+        None => name.to_string(),
+    }
+}
+
+fn json_schema_ref(content: &Value) -> Option<&str> {
+    content["application/json"]["schema"]["$ref"]
+        .as_str()
+        .and_then(|r| r.rsplit('/').next())
+}
+
+fn parameters(operation: &Value) -> Vec<Param> {
+    operation["parameters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|p| {
+            let in_query = p["in"] == "query";
+            let required = p["required"].as_bool().unwrap_or(false);
+            let mut rust_type = rust_type(&p["schema"]);
+            // Strings are borrowed; everything else here is Copy
+            if rust_type == "String" {
+                rust_type = "&str".to_string();
+            }
+            let optional = in_query && !required;
+            if optional && !rust_type.starts_with("Option<") {
+                rust_type = format!("Option<{}>", rust_type);
+            }
+            Param {
+                name: p["name"].as_str().unwrap_or_default().to_string(),
+                rust_type,
+                in_query,
+                optional,
+            }
+        })
+        .collect()
+}
+
+// One client method per documented operation. Returns why an operation was skipped instead,
+// e.g. a response that isn't wrapped in ApiResponse.
+fn operation(out: &mut String, method: &str, path: &str, operation: &Value) -> Result<(), String> {
+    let name = operation["operationId"].as_str().ok_or("no operationId")?;
+    let data = json_schema_ref(&operation["responses"]["200"]["content"])
+        .and_then(|r| r.strip_prefix("ApiResponse_"))
+        .map(generic_type)
+        .ok_or("200 response is not an ApiResponse")?;
+    let body = json_schema_ref(&operation["requestBody"]["content"]);
+    let params = parameters(operation);
+
+    let mut args: Vec<String> = params
+        .iter()
+        .map(|p| format!("{}: {}", p.name, p.rust_type))
+        .collect();
+    if let Some(body) = body {
+        args.push(format!("body: &{}", body));
+    }
+    let path_params: Vec<&Param> = params.iter().filter(|p| !p.in_query).collect();
+    let query_params: Vec<&Param> = params.iter().filter(|p| p.in_query).collect();
+
+    let url = if path_params.is_empty() {
+        format!("self.url({:?})", path)
+    } else {
+        let mut template = path.to_string();
+        for p in &path_params {
+            template = template.replace(&format!("{{{}}}", p.name), "{}");
+        }
+        let values: Vec<&str> = path_params.iter().map(|p| p.name.as_str()).collect();
+        format!("self.url(&format!({:?}, {}))", template, values.join(", "))
+    };
+
+    writeln!(out, "    // {} {}", method.to_uppercase(), path).unwrap();
+    writeln!(
+        out,
+        "    pub async fn {}(&self{}) -> Result<{}, String> {{",
+        name,
+        args.iter().map(|a| format!(", {}", a)).collect::<String>(),
+        data
+    )
+    .unwrap();
+    let mut request = format!("self.http.{}({})", method, url);
+    if !query_params.is_empty() {
+        writeln!(
+            out,
+            "        let mut query: Vec<(&str, String)> = Vec::new();"
+        )
+        .unwrap();
+        for p in &query_params {
+            if p.optional {
+                writeln!(
+                    out,
+                    "        if let Some({0}) = {0} {{\n            query.push(({0:?}, {0}.to_string()));\n        }}",
+                    p.name
+                )
+                .unwrap();
+            } else {
+                writeln!(out, "        query.push(({0:?}, {0}.to_string()));", p.name).unwrap();
+            }
+        }
+        request.push_str(".query(&query)");
+    }
+    if body.is_some() {
+        request.push_str(".json(body)");
+    }
+    writeln!(out, "        self.send({}).await", request).unwrap();
+    writeln!(out, "    }}").unwrap();
+    Ok(())
+}
+
+pub fn generate(spec: &Value) -> String {
+    let mut out = HEADER.to_string();
+    let mut first = true;
+    for (path, item) in spec["paths"].as_object().into_iter().flatten() {
+        for (method, op) in item.as_object().into_iter().flatten() {
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+            if let Err(reason) = operation(&mut out, method, path, op) {
+                writeln!(
+                    out,
+                    "    // Skipped {} {}: {}",
+                    method.to_uppercase(),
+                    path,
+                    reason
+                )
+                .unwrap();
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+// Writes the client module, or with --check only reports whether it's out of date
+pub fn run(args: CodegenArgs) -> Result<(), String> {
+    let spec = serde_json::to_value(ApiDoc::openapi())
+        .map_err(|e| format!("Failed to serialize the OpenAPI document: {}", e))?;
+    let generated = generate(&spec);
+    let path = args.out.unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT));
+
+    if args.check {
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        if current != generated {
+            return Err(format!(
+                "{} is out of date; run `rust-playground codegen` to regenerate it",
+                path.display()
+            ));
+        }
+        println!("{} is up to date", path.display());
+        return Ok(());
+    }
+
+    std::fs::write(&path, generated)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
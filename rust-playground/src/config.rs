@@ -0,0 +1,260 @@
+use axum::{
+    extract::{Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::{Json, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tower::{Layer, ServiceExt};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{error, info};
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+use crate::ApiResponse;
+use crate::load_shed::{LoadShedConfig, LoadShedder};
+use crate::validation::{ValidationConfig, ValidatorRegistry};
+
+// How often the config file's modification time is checked
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub type LogHandle = reload::Handle<EnvFilter, Registry>;
+
+// Settings that can change while the server is running. Anything left out of the config
+// file keeps its environment/default value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    // tracing filter directive, e.g. "info" or "rust_playground=debug,tower_http=warn"
+    pub log_level: String,
+    pub load_shed: LoadShedConfig,
+    pub cors: CorsConfig,
+    pub validation: ValidationConfig,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            log_level: std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            load_shed: LoadShedConfig::default(),
+            cors: CorsConfig::default(),
+            validation: ValidationConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    // "*" allows any origin
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".to_string()],
+        }
+    }
+}
+
+impl CorsConfig {
+    fn layer(&self) -> Result<CorsLayer, String> {
+        if self.allowed_origins.iter().any(|o| o == "*") {
+            return Ok(CorsLayer::permissive());
+        }
+        let origins = self
+            .allowed_origins
+            .iter()
+            .map(|o| HeaderValue::from_str(o).map_err(|_| format!("Invalid CORS origin: {:?}", o)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers(Any)
+            .expose_headers(Any))
+    }
+}
+
+// What /admin/config reports: the live settings plus where they came from
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedConfig {
+    source: Option<PathBuf>,
+    loaded_at: DateTime<Utc>,
+    reloads: u64,
+    // Set when the most recent reload was rejected; the previous settings stay active
+    last_error: Option<String>,
+    config: RuntimeConfig,
+}
+
+struct Live {
+    applied: AppliedConfig,
+    cors: CorsLayer,
+    modified: Option<SystemTime>,
+}
+
+// Owns the reloadable settings and pushes changes into the components that use them
+pub struct ConfigManager {
+    path: Option<PathBuf>,
+    log_handle: LogHandle,
+    load_shed: Arc<LoadShedder>,
+    validators: Arc<ValidatorRegistry>,
+    live: RwLock<Live>,
+}
+
+impl ConfigManager {
+    // Reads CONFIG_FILE (if set) and applies it; fails if the initial file is unusable
+    pub fn from_env(
+        log_handle: LogHandle,
+        load_shed: Arc<LoadShedder>,
+        validators: Arc<ValidatorRegistry>,
+    ) -> Result<Self, String> {
+        let path = std::env::var_os("CONFIG_FILE").map(PathBuf::from);
+        let (config, modified) = match &path {
+            Some(path) => (read(path)?, modified_time(path)),
+            None => (RuntimeConfig::default(), None),
+        };
+        let cors = apply(&config, &log_handle, &load_shed, &validators)?;
+
+        Ok(Self {
+            live: RwLock::new(Live {
+                applied: AppliedConfig {
+                    source: path.clone(),
+                    loaded_at: Utc::now(),
+                    reloads: 0,
+                    last_error: None,
+                    config,
+                },
+                cors,
+                modified,
+            }),
+            path,
+            log_handle,
+            load_shed,
+            validators,
+        })
+    }
+
+    pub fn applied(&self) -> AppliedConfig {
+        self.live.read().unwrap().applied.clone()
+    }
+
+    fn cors(&self) -> CorsLayer {
+        self.live.read().unwrap().cors.clone()
+    }
+
+    // Re-reads the config file; on any error the running settings are left untouched
+    pub fn reload(&self) {
+        let Some(path) = &self.path else {
+            info!("No CONFIG_FILE set, nothing to reload");
+            return;
+        };
+        let modified = modified_time(path);
+        let result = read(path).and_then(|config| {
+            let cors = apply(&config, &self.log_handle, &self.load_shed, &self.validators)?;
+            Ok((config, cors))
+        });
+
+        let mut live = self.live.write().unwrap();
+        live.modified = modified;
+        match result {
+            Ok((config, cors)) => {
+                info!("Applied configuration from {}", path.display());
+                live.cors = cors;
+                live.applied.config = config;
+                live.applied.loaded_at = Utc::now();
+                live.applied.reloads += 1;
+                live.applied.last_error = None;
+            }
+            Err(e) => {
+                error!("Keeping previous configuration: {}", e);
+                live.applied.last_error = Some(e);
+            }
+        }
+    }
+
+    // Reloads on SIGHUP and whenever the config file's modification time changes
+    pub fn watch(self: Arc<Self>) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+                .expect("SIGHUP handler installs");
+            let mut interval = tokio::time::interval(WATCH_INTERVAL);
+            loop {
+                #[cfg(unix)]
+                tokio::select! {
+                    _ = hangup.recv() => {
+                        info!("SIGHUP received, reloading configuration");
+                        self.reload();
+                        continue;
+                    }
+                    _ = interval.tick() => {}
+                }
+                #[cfg(not(unix))]
+                interval.tick().await;
+
+                let modified = modified_time(&path);
+                if modified != self.live.read().unwrap().modified {
+                    info!("{} changed, reloading configuration", path.display());
+                    self.reload();
+                }
+            }
+        });
+    }
+}
+
+fn read(path: &Path) -> Result<RuntimeConfig, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    toml::from_str(&raw).map_err(|e| format!("Invalid config in {}: {}", path.display(), e))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Validates everything before touching anything, so a bad file never half-applies
+fn apply(
+    config: &RuntimeConfig,
+    log_handle: &LogHandle,
+    load_shed: &LoadShedder,
+    validators: &ValidatorRegistry,
+) -> Result<CorsLayer, String> {
+    let filter = EnvFilter::try_new(&config.log_level)
+        .map_err(|e| format!("Invalid log_level {:?}: {}", config.log_level, e))?;
+    let cors = config.cors.layer()?;
+    config.validation.check()?;
+
+    log_handle
+        .reload(filter)
+        .map_err(|e| format!("Cannot update log level: {}", e))?;
+    load_shed.reconfigure(config.load_shed.clone());
+    validators.reconfigure(&config.validation);
+    Ok(cors)
+}
+
+// CORS with the origins from the current config; the layer is rebuilt per request so a
+// reload takes effect immediately
+pub async fn dynamic_cors(
+    State(config): State<Arc<ConfigManager>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let cors = config.cors();
+    match cors.layer(next).oneshot(req).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+pub async fn get_config(
+    State(config): State<Arc<ConfigManager>>,
+) -> Json<ApiResponse<AppliedConfig>> {
+    Json(ApiResponse::success(config.applied()))
+}
@@ -0,0 +1,169 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::problem::Problem;
+
+// Relative budget in milliseconds, e.g. "250"
+pub const TIMEOUT_HEADER: &str = "x-request-timeout";
+// Absolute deadline as Unix epoch milliseconds or an RFC 3339 timestamp
+pub const DEADLINE_HEADER: &str = "x-request-deadline";
+
+tokio::task_local! {
+    static CURRENT: Arc<RequestDeadline>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StorageCall {
+    operation: &'static str,
+    at_ms: u64,
+}
+
+// The time budget the caller gave one request, plus what got done within it
+pub struct RequestDeadline {
+    route: String,
+    started: Instant,
+    expires: Instant,
+    storage_calls: Mutex<Vec<StorageCall>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Diagnostics {
+    route: String,
+    budget_ms: u64,
+    elapsed_ms: u64,
+    // Where the request was when the budget ran out
+    stage: &'static str,
+    // Storage calls made within the budget, in order
+    storage_calls: Vec<StorageCall>,
+}
+
+impl RequestDeadline {
+    fn remaining(&self) -> Duration {
+        self.expires.saturating_duration_since(Instant::now())
+    }
+
+    fn problem(&self, stage: &'static str) -> Problem {
+        let elapsed = self.started.elapsed();
+        let diagnostics = Diagnostics {
+            route: self.route.clone(),
+            budget_ms: (self.expires - self.started).as_millis() as u64,
+            elapsed_ms: elapsed.as_millis() as u64,
+            stage,
+            storage_calls: self.storage_calls.lock().unwrap().clone(),
+        };
+        Problem::new(
+            StatusCode::GATEWAY_TIMEOUT,
+            "deadline-exceeded",
+            "Deadline Exceeded",
+        )
+        .with_detail(format!(
+            "The request's deadline passed after {}ms",
+            diagnostics.elapsed_ms
+        ))
+        .with_extension("diagnostics", diagnostics)
+    }
+}
+
+// Called by the storage layer before each call; Err once the current request is out of time.
+// Outside a request with a deadline there's nothing to enforce.
+pub fn check_storage(operation: &'static str) -> Result<(), ()> {
+    CURRENT
+        .try_with(|deadline| {
+            if deadline.remaining().is_zero() {
+                return Err(());
+            }
+            deadline.storage_calls.lock().unwrap().push(StorageCall {
+                operation,
+                at_ms: deadline.started.elapsed().as_millis() as u64,
+            });
+            Ok(())
+        })
+        .unwrap_or(Ok(()))
+}
+
+// The 504 for the current request, if it has a deadline
+pub fn exceeded_in_storage() -> Option<Problem> {
+    CURRENT
+        .try_with(|deadline| deadline.problem("storage"))
+        .ok()
+}
+
+fn parse_budget(headers: &HeaderMap) -> Result<Option<Duration>, Problem> {
+    let mut budget: Option<Duration> = None;
+
+    if let Some(value) = headers.get(TIMEOUT_HEADER) {
+        let millis: u64 = value
+            .to_str()
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .ok_or_else(|| {
+                Problem::bad_request(format!(
+                    "{} must be a number of milliseconds",
+                    TIMEOUT_HEADER
+                ))
+            })?;
+        budget = Some(Duration::from_millis(millis));
+    }
+
+    if let Some(value) = headers.get(DEADLINE_HEADER) {
+        let raw = value.to_str().unwrap_or("").trim();
+        let deadline = match raw.parse::<i64>() {
+            Ok(millis) => DateTime::<Utc>::from_timestamp_millis(millis),
+            Err(_) => DateTime::parse_from_rfc3339(raw)
+                .ok()
+                .map(|d| d.with_timezone(&Utc)),
+        }
+        .ok_or_else(|| {
+            Problem::bad_request(format!(
+                "{} must be Unix epoch milliseconds or an RFC 3339 timestamp",
+                DEADLINE_HEADER
+            ))
+        })?;
+        let until = (deadline - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+        // Both headers given: the tighter one wins
+        budget = Some(budget.map_or(until, |b| b.min(until)));
+    }
+
+    Ok(budget)
+}
+
+// Runs the rest of the stack under the caller's deadline and answers 504 with what got done
+// when it passes. Requests without either header run unbounded as before.
+pub async fn enforce_deadline(req: Request, next: Next) -> Response {
+    let budget = match parse_budget(req.headers()) {
+        Ok(Some(budget)) => budget,
+        Ok(None) => return next.run(req).await,
+        Err(problem) => return problem.into_response(),
+    };
+    let route = match req.extensions().get::<MatchedPath>() {
+        Some(path) => format!("{} {}", req.method(), path.as_str()),
+        None => format!("{} {}", req.method(), req.uri().path()),
+    };
+    let started = Instant::now();
+    let deadline = Arc::new(RequestDeadline {
+        route,
+        started,
+        expires: started + budget,
+        storage_calls: Mutex::new(Vec::new()),
+    });
+
+    if budget.is_zero() {
+        return deadline.problem("admission").into_response();
+    }
+    match tokio::time::timeout(budget, CURRENT.scope(deadline.clone(), next.run(req))).await {
+        Ok(response) => response,
+        Err(_) => {
+            warn!("{} exceeded its {:?} deadline", deadline.route, budget);
+            deadline.problem("handler").into_response()
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use axum::{extract::State, response::Json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::ChangeHistory;
+use crate::cluster::Cluster;
+use crate::events::{ChangeKind, EventBus};
+use crate::problem::{AppJson, Problem};
+use crate::storage::{Merged, UserPatch};
+use crate::{ApiResponse, Database, User, user_not_found};
+
+// Recorded as the actor of merges; the admin token doesn't say who holds it
+const MERGE_ACTOR: &str = "admin";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    SameEmail,
+    SameNameAndAge,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    reason: DuplicateReason,
+    // The normalized value the users share
+    key: String,
+    users: Vec<User>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicatesReport {
+    total_groups: usize,
+    groups: Vec<DuplicateGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeUsersRequest {
+    // The record that survives, under its own ID
+    keep: Uuid,
+    // The record folded into it and then deleted
+    merge: Uuid,
+}
+
+// Case and surrounding whitespace don't make a different address, and neither does a
+// "+tag" suffix on the local part
+fn normalize_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let local = local.split('+').next().unwrap_or(local);
+            format!("{}@{}", local, domain)
+        }
+        None => email,
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn find_duplicates(users: Vec<User>) -> Vec<DuplicateGroup> {
+    let mut buckets: HashMap<(DuplicateReason, String), Vec<User>> = HashMap::new();
+    for user in users {
+        let by_name = format!("{} ({})", normalize_name(&user.name), user.age);
+        buckets
+            .entry((DuplicateReason::SameEmail, normalize_email(&user.email)))
+            .or_default()
+            .push(user.clone());
+        buckets
+            .entry((DuplicateReason::SameNameAndAge, by_name))
+            .or_default()
+            .push(user);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = buckets
+        .into_iter()
+        .filter(|(_, users)| users.len() > 1)
+        .map(|((reason, key), mut users)| {
+            users.sort_by_key(|u| u.id);
+            DuplicateGroup { reason, key, users }
+        })
+        .collect();
+    // Email matches first, as they're the stronger signal
+    groups.sort_by(|a, b| {
+        (a.reason != DuplicateReason::SameEmail)
+            .cmp(&(b.reason != DuplicateReason::SameEmail))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    groups
+}
+
+// Likely duplicate users, for someone to review before merging
+pub async fn list_duplicates(
+    State(db): State<Database>,
+) -> Result<Json<ApiResponse<DuplicatesReport>>, Problem> {
+    let groups = find_duplicates(db.list()?);
+    Ok(Json(ApiResponse::success(DuplicatesReport {
+        total_groups: groups.len(),
+        groups,
+    })))
+}
+
+// Folds one user into another: the kept record gains whatever it was missing, the other
+// is deleted, and its change history moves over to the kept ID
+pub async fn merge_users(
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+    State(history): State<Arc<ChangeHistory>>,
+    AppJson(request): AppJson<MergeUsersRequest>,
+) -> Result<Json<ApiResponse<User>>, Problem> {
+    if request.keep == request.merge {
+        return Err(Problem::bad_request("Cannot merge a user into itself"));
+    }
+    // A location is only taken over whole, never one coordinate from each record
+    let patch_for = |keep: &User, merged: &User| {
+        let mut patch = UserPatch::default();
+        if keep.latitude.is_none() && merged.latitude.is_some() {
+            patch.latitude = merged.latitude;
+            patch.longitude = merged.longitude;
+        }
+        patch
+    };
+    // One store call, so the merge happens whole or not at all
+    let Merged {
+        before: keep,
+        after: user,
+        removed: merged,
+    } = db
+        .merge(request.keep, request.merge, &patch_for)?
+        .map_err(user_not_found)?;
+
+    history.absorb(merged.id, keep.id);
+    history.record(MERGE_ACTOR, &keep, &user);
+    events.publish(ChangeKind::Deleted, Some(merged.id), None);
+    events.publish(ChangeKind::Updated, Some(user.id), Some(user.clone()));
+    cluster.replicate_delete(merged.id);
+    cluster.replicate_upsert(&user);
+    info!("Merged user {} into {}", merged.id, user.id);
+    Ok(Json(ApiResponse::success(user)))
+}
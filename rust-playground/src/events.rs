@@ -0,0 +1,180 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::problem::{AppQuery, Problem};
+use crate::{ApiResponse, User};
+
+// How many past changes are retained for clients catching up
+const RETAINED_EVENTS: usize = 1024;
+const DEFAULT_WAIT: Duration = Duration::from_secs(30);
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+    // Bulk admin operation; clients should re-fetch the full list
+    Reset,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangeEvent {
+    version: u64,
+    kind: ChangeKind,
+    user_id: Option<Uuid>,
+    user: Option<User>,
+}
+
+struct ChangeLog {
+    version: u64,
+    events: VecDeque<ChangeEvent>,
+}
+
+// Versioned record of user changes that subscribers can wait on
+pub struct EventBus {
+    log: Mutex<ChangeLog>,
+    version_tx: watch::Sender<u64>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (version_tx, _) = watch::channel(0);
+        Self {
+            log: Mutex::new(ChangeLog {
+                version: 0,
+                events: VecDeque::with_capacity(RETAINED_EVENTS),
+            }),
+            version_tx,
+        }
+    }
+
+    pub fn publish(&self, kind: ChangeKind, user_id: Option<Uuid>, user: Option<User>) {
+        let version = {
+            let mut log = self.log.lock().unwrap();
+            log.version += 1;
+            let version = log.version;
+            if log.events.len() == RETAINED_EVENTS {
+                log.events.pop_front();
+            }
+            log.events.push_back(ChangeEvent {
+                version,
+                kind,
+                user_id,
+                user,
+            });
+            version
+        };
+        self.version_tx.send_replace(version);
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.version_tx.subscribe()
+    }
+
+    // Changes newer than `since`, or Err if some of them have already been evicted
+    pub fn changes_since(&self, since: u64) -> Result<(u64, Vec<ChangeEvent>), u64> {
+        let log = self.log.lock().unwrap();
+        let oldest = log.events.front().map_or(log.version + 1, |e| e.version);
+        if since.saturating_add(1) < oldest && since < log.version {
+            return Err(oldest);
+        }
+        let changes = log
+            .events
+            .iter()
+            .filter(|e| e.version > since)
+            .cloned()
+            .collect();
+        Ok((log.version, changes))
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangesQuery {
+    /// Last version the client has seen; omit to start from the beginning
+    #[serde(default)]
+    since: u64,
+    /// How long to hold the request open, e.g. `30s` or `500ms` (max 60s)
+    wait: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChangesResponse {
+    version: u64,
+    changes: Vec<ChangeEvent>,
+}
+
+fn parse_wait(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    let duration = if let Some(ms) = raw.strip_suffix("ms") {
+        Duration::from_millis(ms.parse().ok()?)
+    } else if let Some(m) = raw.strip_suffix('m') {
+        Duration::from_secs(m.parse::<u64>().ok()?.checked_mul(60)?)
+    } else {
+        Duration::from_secs(raw.strip_suffix('s').unwrap_or(raw).parse().ok()?)
+    };
+    Some(duration.min(MAX_WAIT))
+}
+
+fn evicted(oldest: u64) -> Problem {
+    Problem::new(StatusCode::GONE, "changes-evicted", "Changes No Longer Available")
+        .with_detail(format!(
+            "Changes before version {} are no longer retained; re-fetch /users and resume from the latest version",
+            oldest
+        ))
+        .with_extension("oldest_version", oldest)
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/changes",
+    params(ChangesQuery),
+    responses(
+        (status = 200, description = "Changes since the given version (empty if the wait timed out)", body = ApiResponse<ChangesResponse>),
+        (status = 400, description = "Invalid wait duration"),
+        (status = 410, description = "Requested version is older than the retained history")
+    )
+)]
+pub async fn long_poll_changes(
+    State(bus): State<Arc<EventBus>>,
+    AppQuery(query): AppQuery<ChangesQuery>,
+) -> Result<Json<ApiResponse<ChangesResponse>>, Problem> {
+    let wait = match query.wait.as_deref() {
+        Some(raw) => parse_wait(raw)
+            .ok_or_else(|| Problem::bad_request(format!("Invalid wait duration: {:?}", raw)))?,
+        None => DEFAULT_WAIT,
+    };
+
+    // Subscribe before checking so a change landing in between still wakes us
+    let mut version_rx = bus.subscribe();
+    let (version, changes) = bus.changes_since(query.since).map_err(evicted)?;
+    if !changes.is_empty() || wait.is_zero() {
+        return Ok(Json(ApiResponse::success(ChangesResponse {
+            version,
+            changes,
+        })));
+    }
+
+    info!(
+        "Long-polling for changes after version {} (up to {:?})",
+        query.since, wait
+    );
+    let since = query.since;
+    // A timeout simply means nothing changed; the client polls again with the same version
+    let _ = tokio::time::timeout(wait, version_rx.wait_for(|v| *v > since)).await;
+
+    let (version, changes) = bus.changes_since(since).map_err(evicted)?;
+    Ok(Json(ApiResponse::success(ChangesResponse {
+        version,
+        changes,
+    })))
+}
@@ -0,0 +1,131 @@
+use axum::{extract::State, response::Json};
+use rstar::AABB;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::problem::{AppQuery, Problem};
+use crate::{ApiResponse, Database, User};
+
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+// Half the circumference: every point on Earth is within this distance
+const MAX_RADIUS_KM: f64 = std::f64::consts::PI * EARTH_RADIUS_KM;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NearbyUser {
+    user: User,
+    distance_km: f64,
+}
+
+// Great-circle distance between two (lat, lon) points in degrees
+pub fn haversine_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+// Users with a location inside the radius, nearest first
+pub fn within_radius(users: Vec<User>, lat: f64, lon: f64, radius_km: f64) -> Vec<NearbyUser> {
+    let mut nearby: Vec<NearbyUser> = users
+        .into_iter()
+        .filter_map(|user| {
+            let distance_km = haversine_km((lat, lon), (user.latitude?, user.longitude?));
+            (distance_km <= radius_km).then_some(NearbyUser { user, distance_km })
+        })
+        .collect();
+    nearby.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    nearby
+}
+
+// [lon, lat] boxes covering the search circle, split in two when it crosses the antimeridian.
+// They over-cover, so results still need the exact distance check.
+pub fn bounding_boxes(lat: f64, lon: f64, radius_km: f64) -> Vec<AABB<[f64; 2]>> {
+    let d_lat = (radius_km / EARTH_RADIUS_KM).to_degrees();
+    let (min_lat, max_lat) = (lat - d_lat, lat + d_lat);
+    // Near a pole the circle spans every longitude
+    if min_lat <= -90.0 || max_lat >= 90.0 {
+        return vec![AABB::from_corners(
+            [-180.0, min_lat.max(-90.0)],
+            [180.0, max_lat.min(90.0)],
+        )];
+    }
+
+    let d_lon = (radius_km / (EARTH_RADIUS_KM * lat.to_radians().cos()))
+        .min(std::f64::consts::PI)
+        .to_degrees();
+    let (min_lon, max_lon) = (lon - d_lon, lon + d_lon);
+    if max_lon - min_lon >= 360.0 {
+        vec![AABB::from_corners([-180.0, min_lat], [180.0, max_lat])]
+    } else if min_lon < -180.0 {
+        vec![
+            AABB::from_corners([min_lon + 360.0, min_lat], [180.0, max_lat]),
+            AABB::from_corners([-180.0, min_lat], [max_lon, max_lat]),
+        ]
+    } else if max_lon > 180.0 {
+        vec![
+            AABB::from_corners([min_lon, min_lat], [180.0, max_lat]),
+            AABB::from_corners([-180.0, min_lat], [max_lon - 360.0, max_lat]),
+        ]
+    } else {
+        vec![AABB::from_corners([min_lon, min_lat], [max_lon, max_lat])]
+    }
+}
+
+// Latitude and longitude must be given together and lie on the globe
+pub fn check_location(latitude: Option<f64>, longitude: Option<f64>) -> Result<(), String> {
+    match (latitude, longitude) {
+        (Some(lat), Some(lon)) => {
+            if !(-90.0..=90.0).contains(&lat) {
+                return Err(format!("Latitude {} is outside -90..=90", lat));
+            }
+            if !(-180.0..=180.0).contains(&lon) {
+                return Err(format!("Longitude {} is outside -180..=180", lon));
+            }
+            Ok(())
+        }
+        (None, None) => Ok(()),
+        _ => Err("Latitude and longitude must be provided together".to_string()),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NearbyQuery {
+    /// Latitude of the search centre, in degrees
+    lat: f64,
+    /// Longitude of the search centre, in degrees
+    lon: f64,
+    /// Search radius in kilometres
+    radius_km: f64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/nearby",
+    params(NearbyQuery),
+    responses(
+        (status = 200, description = "Users within the radius, nearest first", body = ApiResponse<Vec<NearbyUser>>),
+        (status = 400, description = "Invalid coordinates or radius"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+pub async fn nearby_users(
+    State(db): State<Database>,
+    AppQuery(query): AppQuery<NearbyQuery>,
+) -> Result<Json<ApiResponse<Vec<NearbyUser>>>, Problem> {
+    check_location(Some(query.lat), Some(query.lon)).map_err(Problem::bad_request)?;
+    if query.radius_km.is_nan() || query.radius_km <= 0.0 {
+        return Err(Problem::bad_request("radius_km must be positive"));
+    }
+
+    info!(
+        "Finding users within {}km of ({}, {})",
+        query.radius_km, query.lat, query.lon
+    );
+    let radius_km = query.radius_km.min(MAX_RADIUS_KM);
+    Ok(Json(ApiResponse::success(
+        db.nearby(query.lat, query.lon, radius_km)?,
+    )))
+}
@@ -0,0 +1,656 @@
+use axum::{
+    extract::{FromRef, State},
+    http::{Method, StatusCode},
+    middleware,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, reload, util::SubscriberInitExt};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+mod admin;
+mod audit;
+mod breaker;
+mod cli;
+mod client;
+mod cluster;
+mod codec;
+mod codegen;
+mod config;
+mod deadline;
+mod duplicates;
+mod events;
+mod geo;
+mod lifecycle;
+mod load_shed;
+mod methods;
+mod metrics;
+mod openapi;
+mod problem;
+mod quota;
+mod replay;
+mod retention;
+mod routes;
+mod stats;
+pub mod storage;
+mod validation;
+
+use admin::AdminConfig;
+use audit::{Auditor, ChangeHistory};
+use breaker::{CircuitBreaker, GuardedStore};
+use clap::Parser;
+use cli::{Args, Command};
+use cluster::Cluster;
+use codec::{Accept, AppBody, Negotiated};
+use config::ConfigManager;
+use events::{ChangeKind, EventBus};
+use lifecycle::UserStatus;
+use load_shed::{LoadShedConfig, LoadShedMetrics, LoadShedder};
+use metrics::RequestStats;
+use openapi::SchemaValidator;
+use problem::{AppPath, Problem};
+use quota::{QuotaConfig, Quotas, UsageStore};
+use replay::Recorder;
+use retention::Retention;
+use routes::RouteTable;
+use storage::{StoreResult, StoredEvent, UserPatch, UserStore};
+use validation::{ValidationConfig, ValidatorRegistry};
+
+// Data models
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct User {
+    id: Uuid,
+    name: String,
+    email: String,
+    age: u32,
+    // Changed only through the transition endpoints; records from before it existed are active
+    #[serde(default)]
+    status: UserStatus,
+    // Optional location in degrees; either both are set or neither
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    latitude: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    longitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct CreateUserRequest {
+    name: String,
+    email: String,
+    age: u32,
+    // Only invited or active (the default); anything else has to go through a transition
+    status: Option<UserStatus>,
+    #[schema(minimum = -90.0, maximum = 90.0)]
+    latitude: Option<f64>,
+    #[schema(minimum = -180.0, maximum = 180.0)]
+    longitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct UpdateUserRequest {
+    name: Option<String>,
+    email: Option<String>,
+    age: Option<u32>,
+    #[schema(minimum = -90.0, maximum = 90.0)]
+    latitude: Option<f64>,
+    #[schema(minimum = -180.0, maximum = 180.0)]
+    longitude: Option<f64>,
+}
+
+impl From<UpdateUserRequest> for UserPatch {
+    fn from(req: UpdateUserRequest) -> Self {
+        UserPatch {
+            name: req.name,
+            email: req.email,
+            age: req.age,
+            status: None,
+            latitude: req.latitude,
+            longitude: req.longitude,
+        }
+    }
+}
+
+// Storage backend shared by all handlers
+type Database = Arc<dyn UserStore>;
+
+// Shared application state; handlers extract the parts they need via FromRef
+#[derive(Clone, FromRef)]
+struct AppState {
+    db: Database,
+    load_shed: Arc<LoadShedder>,
+    admin: Arc<AdminConfig>,
+    schema: Arc<SchemaValidator>,
+    events: Arc<EventBus>,
+    cluster: Arc<Cluster>,
+    config: Arc<ConfigManager>,
+    request_stats: Arc<RequestStats>,
+    quotas: Arc<Quotas>,
+    retention: Arc<Retention>,
+    recorder: Arc<Recorder>,
+    validators: Arc<ValidatorRegistry>,
+    breaker: Arc<CircuitBreaker>,
+    change_history: Arc<ChangeHistory>,
+}
+
+// API Response types
+#[derive(Serialize, Deserialize, ToSchema)]
+struct ApiResponse<T> {
+    success: bool,
+    data: Option<T>,
+    message: String,
+}
+
+impl<T> ApiResponse<T> {
+    fn success(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            message: "Success".to_string(),
+        }
+    }
+}
+
+fn user_not_found(id: Uuid) -> Problem {
+    Problem::not_found(format!("User {} does not exist", id))
+        .with_instance(format!("/users/{}", id))
+}
+
+// API Handlers
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses((status = 200, description = "Service is up", body = ApiResponse<String>))
+)]
+async fn health_check() -> Json<ApiResponse<String>> {
+    info!("Health check endpoint called");
+    Json(ApiResponse::success("API is running!".to_string()))
+}
+
+async fn load_shed_metrics(
+    State(shedder): State<Arc<LoadShedder>>,
+) -> Json<ApiResponse<LoadShedMetrics>> {
+    Json(ApiResponse::success(shedder.metrics()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users",
+    responses(
+        (status = 200, description = "All users", body = ApiResponse<Vec<User>>),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+async fn get_all_users(
+    Accept(format): Accept,
+    State(db): State<Database>,
+) -> Result<Negotiated<ApiResponse<Vec<User>>>, Problem> {
+    info!("Getting all users");
+    Ok(Negotiated(format, ApiResponse::success(db.list()?)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The user", body = ApiResponse<User>),
+        (status = 404, description = "User not found"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+async fn get_user_by_id(
+    Accept(format): Accept,
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    info!("Getting user by ID: {}", id);
+
+    match db.get(id)? {
+        Some(user) => Ok(Negotiated(format, ApiResponse::success(user))),
+        None => {
+            warn!("User not found: {}", id);
+            Err(user_not_found(id))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 200, description = "User created", body = ApiResponse<User>),
+        (status = 422, description = "Body does not match the schema or breaks a validation rule"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+async fn create_user(
+    Accept(format): Accept,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+    State(validators): State<Arc<ValidatorRegistry>>,
+    AppBody(payload): AppBody<CreateUserRequest>,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    info!("Creating new user: {}", payload.name);
+    let status = payload.status.unwrap_or_default();
+    if !matches!(status, UserStatus::Invited | UserStatus::Active) {
+        return Err(Problem::bad_request("New users must be invited or active"));
+    }
+
+    let new_user = User {
+        id: Uuid::new_v4(),
+        name: payload.name,
+        email: payload.email,
+        age: payload.age,
+        status,
+        latitude: payload.latitude,
+        longitude: payload.longitude,
+    };
+    validators.check(&new_user)?;
+
+    db.insert(new_user.clone())?;
+    events.publish(
+        ChangeKind::Created,
+        Some(new_user.id),
+        Some(new_user.clone()),
+    );
+    cluster.replicate_upsert(&new_user);
+
+    info!("User created with ID: {}", new_user.id);
+    Ok(Negotiated(format, ApiResponse::success(new_user)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/{id}",
+    params(("id" = Uuid, Path, description = "User ID")),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "User updated", body = ApiResponse<User>),
+        (status = 400, description = "Only one coordinate given"),
+        (status = 404, description = "User not found"),
+        (status = 422, description = "Body does not match the schema or breaks a validation rule"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+async fn update_user(
+    Accept(format): Accept,
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+    State(validators): State<Arc<ValidatorRegistry>>,
+    auditor: Auditor,
+    AppBody(payload): AppBody<UpdateUserRequest>,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    info!("Updating user: {}", id);
+    // Moving a user means sending both coordinates; the old pair is never mixed with a new one
+    if payload.latitude.is_some() != payload.longitude.is_some() {
+        return Err(Problem::bad_request(
+            "Latitude and longitude must be provided together",
+        ));
+    }
+    let patch = UserPatch::from(payload);
+
+    // Rules apply to the user as it would be after the update
+    let before = db.get(id)?;
+    if let Some(mut candidate) = before.clone() {
+        patch.apply_to(&mut candidate);
+        validators.check(&candidate)?;
+    }
+
+    match db.update(id, patch)? {
+        Some(user) => {
+            if let Some(before) = &before {
+                auditor.record(before, &user);
+            }
+            events.publish(ChangeKind::Updated, Some(id), Some(user.clone()));
+            cluster.replicate_upsert(&user);
+            info!("User updated: {}", id);
+            Ok(Negotiated(format, ApiResponse::success(user)))
+        }
+        None => {
+            warn!("User not found for update: {}", id);
+            Err(user_not_found(id))
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{id}",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User deleted", body = ApiResponse<String>),
+        (status = 404, description = "User not found"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+async fn delete_user(
+    Accept(format): Accept,
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+) -> Result<Negotiated<ApiResponse<String>>, Problem> {
+    info!("Deleting user: {}", id);
+
+    match db.delete(id)? {
+        Some(_) => {
+            events.publish(ChangeKind::Deleted, Some(id), None);
+            cluster.replicate_delete(id);
+            info!("User deleted: {}", id);
+            Ok(Negotiated(
+                format,
+                ApiResponse::success(format!("User {} deleted successfully", id)),
+            ))
+        }
+        None => {
+            warn!("User not found for deletion: {}", id);
+            Err(user_not_found(id))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/history",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Events recorded for the user, oldest first", body = ApiResponse<Vec<StoredEvent>>),
+        (status = 404, description = "No events recorded for the user"),
+        (status = 501, description = "The storage backend does not keep history"),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+async fn get_user_history(
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+) -> Result<Json<ApiResponse<Vec<StoredEvent>>>, Problem> {
+    info!("Getting history for user: {}", id);
+
+    let Some(history) = db.history(id)? else {
+        return Err(Problem::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "history-unsupported",
+            "History Not Supported",
+        )
+        .with_detail("User history requires STORAGE_BACKEND=event-sourced"));
+    };
+    if history.is_empty() {
+        return Err(user_not_found(id));
+    }
+    Ok(Json(ApiResponse::success(history)))
+}
+
+fn seed_sample_users(users: &dyn UserStore) -> StoreResult<()> {
+    let sample_user = User {
+        id: Uuid::new_v4(),
+        name: "John Doe".to_string(),
+        email: "john.doe@example.com".to_string(),
+        age: 30,
+        status: UserStatus::Active,
+        latitude: Some(40.7128),
+        longitude: Some(-74.0060),
+    };
+    users.insert(sample_user)?;
+
+    let sample_user2 = User {
+        id: Uuid::new_v4(),
+        name: "Jane Smith".to_string(),
+        email: "jane.smith@example.com".to_string(),
+        age: 25,
+        status: UserStatus::Active,
+        latitude: Some(51.5072),
+        longitude: Some(-0.1276),
+    };
+    users.insert(sample_user2)
+}
+
+// Runs whichever subcommand was given on the command line
+pub async fn run() {
+    let args = Args::parse();
+    match args.command {
+        None | Some(Command::Serve) => serve().await,
+        Some(Command::Client(client_args)) => {
+            if let Err(e) = client::run(client_args).await {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Healthcheck(healthcheck_args)) => {
+            if let Err(e) = client::healthcheck(healthcheck_args).await {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Codegen(codegen_args)) => {
+            if let Err(e) = codegen::run(codegen_args) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+async fn serve() {
+    // Initialize tracing; the filter sits behind a reload handle so the log level can change live
+    let (log_filter, log_handle) = reload::Layer::new(EnvFilter::new("info"));
+    tracing_subscriber::registry()
+        .with(log_filter)
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    // Create the storage backend (in-memory unless STORAGE_BACKEND says otherwise), behind a
+    // circuit breaker so an outage fails fast instead of piling up requests
+    let breaker = Arc::new(CircuitBreaker::from_env());
+    let db: Database = Arc::new(GuardedStore::new(storage::from_env(), breaker.clone()));
+
+    let cluster = Arc::new(Cluster::from_env().unwrap_or_else(|e| {
+        eprintln!("Failed to configure the cluster: {}", e);
+        std::process::exit(1);
+    }));
+
+    // Add some sample data; cluster members start empty and sync from their peers instead,
+    // otherwise every node would contribute its own copy of the seed users
+    if !cluster.enabled()
+        && let Err(e) = seed_sample_users(db.as_ref())
+    {
+        warn!("Failed to seed sample users: {}", e);
+    }
+
+    let load_shed = Arc::new(LoadShedder::new(LoadShedConfig::from_env()));
+    let quotas = Arc::new(Quotas::new(QuotaConfig::from_env(), UsageStore::from_env()));
+    let validators = Arc::new(ValidatorRegistry::new(&ValidationConfig::default()));
+    let config = Arc::new(
+        ConfigManager::from_env(log_handle, load_shed.clone(), validators.clone()).unwrap_or_else(
+            |e| {
+                eprintln!("Failed to load configuration: {}", e);
+                std::process::exit(1);
+            },
+        ),
+    );
+    config.clone().watch();
+
+    let state = AppState {
+        db,
+        load_shed: load_shed.clone(),
+        admin: Arc::new(AdminConfig::from_env()),
+        schema: Arc::new(SchemaValidator::from_env()),
+        events: Arc::new(EventBus::new()),
+        cluster: cluster.clone(),
+        config: config.clone(),
+        request_stats: Arc::new(RequestStats::from_env()),
+        quotas: quotas.clone(),
+        retention: Arc::new(Retention::from_env(cluster.clone(), quotas.clone())),
+        recorder: Arc::new(Recorder::from_env()),
+        validators,
+        breaker,
+        change_history: Arc::new(ChangeHistory::new()),
+    };
+    state.quotas.spawn_flusher();
+    state.retention.spawn();
+
+    if cluster.enabled() {
+        tokio::spawn(cluster::bootstrap(
+            cluster,
+            state.db.clone(),
+            state.events.clone(),
+            state.validators.clone(),
+        ));
+    }
+
+    let schema = state.schema.clone();
+    let request_stats = state.request_stats.clone();
+    let recorder = state.recorder.clone();
+
+    // Build our application with routes; the table doubles as GET /admin/routes
+    let (router, route_list) = RouteTable::new()
+        .route(Method::GET, "/health", health_check, "Health check")
+        .route(
+            Method::GET,
+            "/health/ready",
+            breaker::readiness,
+            "Readiness: storage reachable and circuit closed",
+        )
+        .route(
+            Method::GET,
+            "/metrics/load-shed",
+            load_shed_metrics,
+            "Load shedding metrics",
+        )
+        .route(
+            Method::GET,
+            "/metrics/retention",
+            retention::retention_metrics,
+            "Retention job runs and purged rows",
+        )
+        .route(
+            Method::GET,
+            "/openapi.json",
+            openapi::openapi_json,
+            "OpenAPI document",
+        )
+        .route(Method::GET, "/users", get_all_users, "Get all users")
+        .route(Method::POST, "/users", create_user, "Create a new user")
+        .route(
+            Method::GET,
+            "/users/stats",
+            stats::user_stats,
+            "Aggregate user statistics",
+        )
+        .route(
+            Method::GET,
+            "/users/changes",
+            events::long_poll_changes,
+            "Long-poll for changes since a version",
+        )
+        .route(
+            Method::GET,
+            "/users/nearby",
+            geo::nearby_users,
+            "Users within radius_km of lat/lon",
+        )
+        .route(Method::GET, "/users/:id", get_user_by_id, "Get user by ID")
+        .route(Method::PUT, "/users/:id", update_user, "Update user by ID")
+        .route(
+            Method::DELETE,
+            "/users/:id",
+            delete_user,
+            "Delete user by ID",
+        )
+        .route(
+            Method::GET,
+            "/users/:id/history",
+            get_user_history,
+            "Event history (event-sourced backend)",
+        )
+        .route(
+            Method::GET,
+            "/users/:id/changes",
+            audit::get_user_changes,
+            "Field-level change history",
+        )
+        .route(
+            Method::POST,
+            "/users/:id/activate",
+            lifecycle::activate_user,
+            "Activate an invited or suspended user",
+        )
+        .route(
+            Method::POST,
+            "/users/:id/suspend",
+            lifecycle::suspend_user,
+            "Suspend an active user",
+        )
+        .route(
+            Method::POST,
+            "/users/:id/deactivate",
+            lifecycle::deactivate_user,
+            "Deactivate a user for good",
+        )
+        .merge(admin::routes(&state))
+        .merge(cluster::routes(&state))
+        .merge(quota::routes())
+        .into_parts();
+    let routes = router
+        .method_not_allowed_fallback(problem::method_not_allowed_fallback)
+        .fallback(problem::not_found_fallback)
+        .with_state(state);
+
+    // The bare route table is kept around so OPTIONS/405 handling can ask it for Allow lists
+    let app = routes
+        .clone()
+        .layer(middleware::from_fn_with_state(
+            schema,
+            openapi::validate_schema,
+        ))
+        .layer(middleware::from_fn(deadline::enforce_deadline))
+        .layer(middleware::from_fn_with_state(quotas, quota::enforce_quota))
+        .layer(middleware::from_fn_with_state(
+            load_shed,
+            load_shed::load_shed,
+        ))
+        .layer(middleware::from_fn_with_state(
+            request_stats,
+            metrics::record_request,
+        ))
+        .layer(middleware::from_fn_with_state(
+            recorder.clone(),
+            replay::record_exchange,
+        ))
+        .layer(middleware::from_fn_with_state(config, config::dynamic_cors))
+        .layer(middleware::from_fn_with_state(
+            routes,
+            methods::allow_methods,
+        ));
+    // Replays go through the same stack as live traffic
+    recorder.set_target(app.clone());
+
+    let port: u16 = std::env::var("PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(3000);
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to bind to port {}: {}", port, e);
+            std::process::exit(1);
+        });
+
+    info!("🚀 Server starting on http://localhost:{}", port);
+    info!(
+        "📋 {} routes registered, see GET /admin/routes",
+        route_list.len()
+    );
+
+    axum::serve(listener, app).await.unwrap_or_else(|e| {
+        eprintln!("Server error: {}", e);
+        std::process::exit(1);
+    });
+}
@@ -0,0 +1,205 @@
+use axum::{extract::State, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::audit::Auditor;
+use crate::cluster::Cluster;
+use crate::codec::{Accept, Format, Negotiated};
+use crate::events::{ChangeKind, EventBus};
+use crate::problem::{AppPath, Problem};
+use crate::storage::UserPatch;
+use crate::{ApiResponse, Database, User, user_not_found};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    // Created on someone's behalf; becomes active once they accept
+    Invited,
+    #[default]
+    Active,
+    Suspended,
+    // Terminal: a deactivated user can't be brought back
+    Deactivated,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transition {
+    Activate,
+    Suspend,
+    Deactivate,
+}
+
+impl UserStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            UserStatus::Invited => "invited",
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+            UserStatus::Deactivated => "deactivated",
+        }
+    }
+}
+
+impl Transition {
+    fn as_str(self) -> &'static str {
+        match self {
+            Transition::Activate => "activate",
+            Transition::Suspend => "suspend",
+            Transition::Deactivate => "deactivate",
+        }
+    }
+
+    // The status this transition leads to, if it's allowed from `from`
+    fn apply(self, from: UserStatus) -> Option<UserStatus> {
+        use UserStatus::*;
+        match (self, from) {
+            (Transition::Activate, Invited | Suspended) => Some(Active),
+            (Transition::Suspend, Active) => Some(Suspended),
+            (Transition::Deactivate, Invited | Active | Suspended) => Some(Deactivated),
+            _ => None,
+        }
+    }
+}
+
+fn illegal_transition(id: Uuid, from: UserStatus, transition: Transition) -> Problem {
+    Problem::new(
+        StatusCode::CONFLICT,
+        "illegal-transition",
+        "Illegal Status Transition",
+    )
+    .with_detail(format!(
+        "Cannot {} a user who is {}",
+        transition.as_str(),
+        from.as_str()
+    ))
+    .with_instance(format!("/users/{}", id))
+    .with_extension("current_status", from)
+    .with_extension("transition", transition)
+}
+
+async fn transition(
+    format: Format,
+    id: Uuid,
+    db: Database,
+    events: Arc<EventBus>,
+    cluster: Arc<Cluster>,
+    auditor: Auditor,
+    transition: Transition,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    // Checked and written in one store call, so a concurrent transition can't slip in between
+    // and e.g. bring a deactivated user back
+    let decide = |user: &User| {
+        transition.apply(user.status).map(|to| UserPatch {
+            status: Some(to),
+            ..UserPatch::default()
+        })
+    };
+    let Some((before, after)) = db.update_if(id, &decide)? else {
+        warn!("User not found for {}: {}", transition.as_str(), id);
+        return Err(user_not_found(id));
+    };
+    let from = before.status;
+    let Some(user) = after else {
+        return Err(illegal_transition(id, from, transition));
+    };
+    let to = user.status;
+    auditor.record(&before, &user);
+    events.publish(ChangeKind::Updated, Some(id), Some(user.clone()));
+    cluster.replicate_upsert(&user);
+    info!("User {} is now {} (was {})", id, to.as_str(), from.as_str());
+    Ok(Negotiated(format, ApiResponse::success(user)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/activate",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User activated", body = ApiResponse<User>),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "User is neither invited nor suspended")
+    )
+)]
+pub async fn activate_user(
+    Accept(format): Accept,
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+    auditor: Auditor,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    transition(
+        format,
+        id,
+        db,
+        events,
+        cluster,
+        auditor,
+        Transition::Activate,
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/suspend",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User suspended", body = ApiResponse<User>),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "User is not active")
+    )
+)]
+pub async fn suspend_user(
+    Accept(format): Accept,
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+    auditor: Auditor,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    transition(
+        format,
+        id,
+        db,
+        events,
+        cluster,
+        auditor,
+        Transition::Suspend,
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/deactivate",
+    params(("id" = Uuid, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User deactivated", body = ApiResponse<User>),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "User is already deactivated")
+    )
+)]
+pub async fn deactivate_user(
+    Accept(format): Accept,
+    AppPath(id): AppPath<Uuid>,
+    State(db): State<Database>,
+    State(events): State<Arc<EventBus>>,
+    State(cluster): State<Arc<Cluster>>,
+    auditor: Auditor,
+) -> Result<Negotiated<ApiResponse<User>>, Problem> {
+    transition(
+        format,
+        id,
+        db,
+        events,
+        cluster,
+        auditor,
+        Transition::Deactivate,
+    )
+    .await
+}
@@ -0,0 +1,236 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::problem::Problem;
+
+// Weight given to the newest latency sample in the moving average
+const EWMA_ALPHA: f64 = 0.2;
+
+// Fields missing from the config file fall back to the environment, then the built-in defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoadShedConfig {
+    pub max_in_flight: usize,
+    pub max_latency_ms: u64,
+    pub cooldown_secs: u64,
+}
+
+impl LoadShedConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_in_flight: env_or("LOAD_SHED_MAX_IN_FLIGHT", 256),
+            max_latency_ms: env_or("LOAD_SHED_MAX_LATENCY_MS", 500),
+            cooldown_secs: env_or("LOAD_SHED_COOLDOWN_SECS", 1),
+        }
+    }
+
+    fn max_latency(&self) -> Duration {
+        Duration::from_millis(self.max_latency_ms)
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_secs)
+    }
+}
+
+impl Default for LoadShedConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+struct LatencyState {
+    ewma_ms: f64,
+    overloaded_until: Option<Instant>,
+}
+
+// Tracks in-flight requests and recent latency, deciding whether to admit new work
+pub struct LoadShedder {
+    // Swapped wholesale on config reload; counters and latency history carry over
+    config: RwLock<LoadShedConfig>,
+    in_flight: AtomicUsize,
+    accepted: AtomicU64,
+    shed_in_flight: AtomicU64,
+    shed_latency: AtomicU64,
+    latency: Mutex<LatencyState>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoadShedMetrics {
+    in_flight: usize,
+    max_in_flight: usize,
+    latency_ewma_ms: f64,
+    max_latency_ms: u128,
+    overloaded: bool,
+    accepted_total: u64,
+    shed_in_flight_total: u64,
+    shed_latency_total: u64,
+}
+
+enum ShedReason {
+    InFlight,
+    Latency,
+}
+
+impl LoadShedder {
+    pub fn new(config: LoadShedConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            in_flight: AtomicUsize::new(0),
+            accepted: AtomicU64::new(0),
+            shed_in_flight: AtomicU64::new(0),
+            shed_latency: AtomicU64::new(0),
+            latency: Mutex::new(LatencyState {
+                ewma_ms: 0.0,
+                overloaded_until: None,
+            }),
+        }
+    }
+
+    pub fn reconfigure(&self, config: LoadShedConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    fn config(&self) -> LoadShedConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn metrics(&self) -> LoadShedMetrics {
+        let config = self.config();
+        let latency = self.latency.lock().unwrap();
+        LoadShedMetrics {
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            max_in_flight: config.max_in_flight,
+            latency_ewma_ms: latency.ewma_ms,
+            max_latency_ms: config.max_latency().as_millis(),
+            overloaded: latency
+                .overloaded_until
+                .is_some_and(|until| Instant::now() < until),
+            accepted_total: self.accepted.load(Ordering::Relaxed),
+            shed_in_flight_total: self.shed_in_flight.load(Ordering::Relaxed),
+            shed_latency_total: self.shed_latency.load(Ordering::Relaxed),
+        }
+    }
+
+    fn try_admit(self: &Arc<Self>) -> Result<InFlightGuard, ShedReason> {
+        {
+            let latency = self.latency.lock().unwrap();
+            if latency
+                .overloaded_until
+                .is_some_and(|until| Instant::now() < until)
+            {
+                self.shed_latency.fetch_add(1, Ordering::Relaxed);
+                return Err(ShedReason::Latency);
+            }
+        }
+
+        let previous = self.in_flight.fetch_add(1, Ordering::AcqRel);
+        if previous >= self.config().max_in_flight {
+            self.in_flight.fetch_sub(1, Ordering::AcqRel);
+            self.shed_in_flight.fetch_add(1, Ordering::Relaxed);
+            return Err(ShedReason::InFlight);
+        }
+
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        Ok(InFlightGuard {
+            shedder: Arc::clone(self),
+        })
+    }
+
+    fn record_latency(&self, elapsed: Duration) {
+        let config = self.config();
+        let mut latency = self.latency.lock().unwrap();
+        let sample = elapsed.as_secs_f64() * 1000.0;
+        latency.ewma_ms = EWMA_ALPHA * sample + (1.0 - EWMA_ALPHA) * latency.ewma_ms;
+
+        if latency.ewma_ms > config.max_latency().as_secs_f64() * 1000.0 {
+            warn!(
+                "Latency {:.1}ms above threshold, shedding load for {:?}",
+                latency.ewma_ms,
+                config.cooldown()
+            );
+            latency.overloaded_until = Some(Instant::now() + config.cooldown());
+            // Start the next window from a clean slate so recovery is judged on fresh samples
+            latency.ewma_ms = 0.0;
+        }
+    }
+
+    fn retry_after_secs(&self) -> u64 {
+        self.config().cooldown_secs.max(1)
+    }
+}
+
+// Decrements the in-flight counter even if the request future is dropped early
+struct InFlightGuard {
+    shedder: Arc<LoadShedder>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.shedder.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+// Health and metrics must keep answering so operators can tell "busy" from "dead"
+pub fn is_exempt(path: &str) -> bool {
+    path == "/health" || path.starts_with("/health/") || path.starts_with("/metrics/")
+}
+
+pub async fn load_shed(
+    State(shedder): State<Arc<LoadShedder>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path().to_string();
+    if is_exempt(&path) {
+        return next.run(req).await;
+    }
+
+    let guard = match shedder.try_admit() {
+        Ok(guard) => guard,
+        Err(reason) => {
+            let message = match reason {
+                ShedReason::InFlight => "Server overloaded: too many in-flight requests",
+                ShedReason::Latency => "Server overloaded: latency above threshold",
+            };
+            warn!(
+                "Shedding {} {}: {}",
+                req.method(),
+                req.uri().path(),
+                message
+            );
+            let mut response = Problem::service_unavailable(message).into_response();
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(shedder.retry_after_secs()),
+            );
+            return response;
+        }
+    };
+
+    // Long-poll requests are slow by design and would skew the latency average
+    let long_poll = path == "/users/changes";
+    let started = Instant::now();
+    let response = next.run(req).await;
+    if !long_poll {
+        shedder.record_latency(started.elapsed());
+    }
+    drop(guard);
+    response
+}
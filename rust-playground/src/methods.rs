@@ -0,0 +1,66 @@
+use axum::{
+    Router,
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderValue, Method, StatusCode, Uri, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower::ServiceExt;
+
+use crate::problem::Problem;
+
+// A method no route registers, so the router answers with its 405 fallback and Allow header
+const PROBE_METHOD: &[u8] = b"PROBE";
+
+// Asks the bare router which methods `uri` supports; None if no route matches the path
+async fn allowed_methods(routes: Router, uri: &Uri) -> Option<String> {
+    let probe = Request::builder()
+        .method(Method::from_bytes(PROBE_METHOD).expect("valid method token"))
+        .uri(uri.clone())
+        .body(Body::empty())
+        .expect("valid probe request");
+
+    let response = match routes.oneshot(probe).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+
+    response
+        .headers()
+        .get(header::ALLOW)
+        .and_then(|v| v.to_str().ok())
+        .map(|allow| format!("{},OPTIONS", allow))
+}
+
+fn is_cors_preflight(req: &Request) -> bool {
+    req.headers()
+        .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+// Answers plain OPTIONS requests with the path's Allow list (CORS preflights pass through)
+// and makes sure 405 responses advertise OPTIONS alongside the routed methods.
+pub async fn allow_methods(State(routes): State<Router>, req: Request, next: Next) -> Response {
+    let uri = req.uri().clone();
+
+    if req.method() == Method::OPTIONS && !is_cors_preflight(&req) {
+        return match allowed_methods(routes, &uri).await {
+            Some(allow) => (
+                StatusCode::NO_CONTENT,
+                [(header::ALLOW, HeaderValue::from_str(&allow).unwrap())],
+            )
+                .into_response(),
+            None => Problem::not_found("No route matches the requested path").into_response(),
+        };
+    }
+
+    let mut response = next.run(req).await;
+    if response.status() == StatusCode::METHOD_NOT_ALLOWED
+        && let Some(allow) = allowed_methods(routes, &uri).await
+    {
+        response
+            .headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_str(&allow).unwrap());
+    }
+    response
+}
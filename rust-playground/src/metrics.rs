@@ -0,0 +1,244 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::{Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::ApiResponse;
+use crate::problem::AppQuery;
+
+// Latency histogram resolution: four buckets per doubling (~19% wide), covering 1µs..~70min
+const BUCKETS_PER_DOUBLING: f64 = 4.0;
+const HISTOGRAM_BUCKETS: usize = 128;
+// Requests that never matched a route are grouped together
+const UNMATCHED_ROUTE: &str = "<unmatched>";
+
+#[derive(Clone)]
+struct RouteWindow {
+    requests: u64,
+    client_errors: u64,
+    server_errors: u64,
+    total_us: u64,
+    max_us: u64,
+    histogram: [u32; HISTOGRAM_BUCKETS],
+}
+
+impl Default for RouteWindow {
+    fn default() -> Self {
+        Self {
+            requests: 0,
+            client_errors: 0,
+            server_errors: 0,
+            total_us: 0,
+            max_us: 0,
+            histogram: [0; HISTOGRAM_BUCKETS],
+        }
+    }
+}
+
+impl RouteWindow {
+    fn record(&mut self, status: u16, micros: u64) {
+        self.requests += 1;
+        match status {
+            400..=499 => self.client_errors += 1,
+            500..=599 => self.server_errors += 1,
+            _ => {}
+        }
+        self.total_us += micros;
+        self.max_us = self.max_us.max(micros);
+        self.histogram[bucket_index(micros)] += 1;
+    }
+
+    fn merge(&mut self, other: &RouteWindow) {
+        self.requests += other.requests;
+        self.client_errors += other.client_errors;
+        self.server_errors += other.server_errors;
+        self.total_us += other.total_us;
+        self.max_us = self.max_us.max(other.max_us);
+        for (mine, theirs) in self.histogram.iter_mut().zip(other.histogram.iter()) {
+            *mine += theirs;
+        }
+    }
+
+    // Upper bound of the bucket holding the given quantile, in milliseconds
+    fn percentile_ms(&self, quantile: f64) -> f64 {
+        let rank = (quantile * self.requests as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.histogram.iter().enumerate() {
+            seen += *count as u64;
+            if seen >= rank {
+                return (bucket_upper_us(index) as f64 / 1000.0).min(self.max_us as f64 / 1000.0);
+            }
+        }
+        self.max_us as f64 / 1000.0
+    }
+}
+
+fn bucket_index(micros: u64) -> usize {
+    let index = ((micros.max(1) as f64).log2() * BUCKETS_PER_DOUBLING) as usize;
+    index.min(HISTOGRAM_BUCKETS - 1)
+}
+
+fn bucket_upper_us(index: usize) -> u64 {
+    2f64.powf((index + 1) as f64 / BUCKETS_PER_DOUBLING).ceil() as u64
+}
+
+struct Minute {
+    minute: u64,
+    routes: HashMap<String, RouteWindow>,
+}
+
+// Per-route request statistics kept in one-minute slots for the last `window_minutes`
+pub struct RequestStats {
+    window_minutes: u64,
+    minutes: Mutex<VecDeque<Minute>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LatencySummary {
+    p50: f64,
+    p95: f64,
+    p99: f64,
+    mean: f64,
+    max: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteStats {
+    route: String,
+    requests: u64,
+    client_errors: u64,
+    server_errors: u64,
+    // Share of requests answered with a 5xx
+    error_rate: f64,
+    latency_ms: LatencySummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    window_minutes: u64,
+    routes: Vec<RouteStats>,
+}
+
+fn current_minute() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 60)
+        .unwrap_or(0)
+}
+
+impl RequestStats {
+    pub fn from_env() -> Self {
+        let window_minutes = std::env::var("STATS_WINDOW_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|m| *m > 0)
+            .unwrap_or(15);
+        Self {
+            window_minutes,
+            minutes: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn record(&self, route: &str, status: u16, micros: u64) {
+        let now = current_minute();
+        let mut minutes = self.minutes.lock().unwrap();
+        if minutes.back().is_none_or(|m| m.minute != now) {
+            minutes.push_back(Minute {
+                minute: now,
+                routes: HashMap::new(),
+            });
+        }
+        while minutes
+            .front()
+            .is_some_and(|m| m.minute.saturating_add(self.window_minutes) <= now)
+        {
+            minutes.pop_front();
+        }
+        let slot = minutes.back_mut().expect("current minute was just pushed");
+        if let Some(window) = slot.routes.get_mut(route) {
+            window.record(status, micros);
+        } else {
+            let mut window = RouteWindow::default();
+            window.record(status, micros);
+            slot.routes.insert(route.to_string(), window);
+        }
+    }
+
+    fn report(&self, minutes: u64) -> StatsReport {
+        let window_minutes = minutes.clamp(1, self.window_minutes);
+        let oldest = current_minute().saturating_sub(window_minutes - 1);
+
+        let mut merged: BTreeMap<String, RouteWindow> = BTreeMap::new();
+        for minute in self.minutes.lock().unwrap().iter() {
+            if minute.minute < oldest {
+                continue;
+            }
+            for (route, window) in &minute.routes {
+                merged.entry(route.clone()).or_default().merge(window);
+            }
+        }
+
+        let routes = merged
+            .into_iter()
+            .map(|(route, w)| RouteStats {
+                route,
+                requests: w.requests,
+                client_errors: w.client_errors,
+                server_errors: w.server_errors,
+                error_rate: w.server_errors as f64 / w.requests as f64,
+                latency_ms: LatencySummary {
+                    p50: w.percentile_ms(0.50),
+                    p95: w.percentile_ms(0.95),
+                    p99: w.percentile_ms(0.99),
+                    mean: w.total_us as f64 / w.requests as f64 / 1000.0,
+                    max: w.max_us as f64 / 1000.0,
+                },
+            })
+            .collect();
+
+        StatsReport {
+            window_minutes,
+            routes,
+        }
+    }
+}
+
+// Records latency and status per matched route ("GET /users/:id"), so ids don't explode
+// the number of series
+pub async fn record_request(
+    State(stats): State<Arc<RequestStats>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = match req.extensions().get::<MatchedPath>() {
+        Some(path) => format!("{} {}", req.method(), path.as_str()),
+        None => format!("{} {}", req.method(), UNMATCHED_ROUTE),
+    };
+    let started = Instant::now();
+    let response = next.run(req).await;
+    stats.record(
+        &route,
+        response.status().as_u16(),
+        started.elapsed().as_micros() as u64,
+    );
+    response
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    // Look-back in minutes; defaults to (and is capped at) the retained window
+    minutes: Option<u64>,
+}
+
+pub async fn admin_stats(
+    State(stats): State<Arc<RequestStats>>,
+    AppQuery(query): AppQuery<StatsQuery>,
+) -> Json<ApiResponse<StatsReport>> {
+    let minutes = query.minutes.unwrap_or(stats.window_minutes);
+    Json(ApiResponse::success(stats.report(minutes)))
+}
@@ -0,0 +1,247 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use jsonschema::Validator;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::error;
+use utoipa::OpenApi;
+
+use crate::codec;
+use crate::problem::Problem;
+
+// Same limit axum's Json extractor applies by default
+pub const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "rust-playground",
+        description = "In-memory user management API"
+    ),
+    paths(
+        crate::health_check,
+        crate::get_all_users,
+        crate::stats::user_stats,
+        crate::events::long_poll_changes,
+        crate::geo::nearby_users,
+        crate::get_user_by_id,
+        crate::create_user,
+        crate::update_user,
+        crate::delete_user,
+        crate::get_user_history,
+        crate::audit::get_user_changes,
+        crate::lifecycle::activate_user,
+        crate::lifecycle::suspend_user,
+        crate::lifecycle::deactivate_user,
+    )
+)]
+pub struct ApiDoc;
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[derive(Debug, Serialize)]
+struct SchemaViolation {
+    pointer: String,
+    message: String,
+}
+
+// (method, OpenAPI path template) of a documented operation
+type OperationKey = (Method, String);
+
+// Compiled JSON Schema validators for every documented request and response body
+pub struct SchemaValidator {
+    requests: HashMap<OperationKey, Validator>,
+    responses: HashMap<(OperationKey, u16), Validator>,
+    validate_responses: bool,
+}
+
+impl SchemaValidator {
+    pub fn from_env() -> Self {
+        // Response checks cost a re-serialization per request, so they default to debug builds
+        let validate_responses = std::env::var("VALIDATE_RESPONSES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(cfg!(debug_assertions));
+        let spec = serde_json::to_value(ApiDoc::openapi()).expect("OpenAPI document serializes");
+        Self::new(&spec, validate_responses)
+    }
+
+    fn new(spec: &Value, validate_responses: bool) -> Self {
+        let components = spec.get("components").cloned().unwrap_or(Value::Null);
+        let mut requests = HashMap::new();
+        let mut responses = HashMap::new();
+
+        let paths = spec["paths"].as_object().into_iter().flatten();
+        for (path, item) in paths {
+            for (method, operation) in item.as_object().into_iter().flatten() {
+                // Path items also hold non-operation members such as shared parameters
+                let Ok(method) = Method::from_bytes(method.to_uppercase().as_bytes()) else {
+                    continue;
+                };
+                let key = (method, path.clone());
+
+                if let Some(schema) = json_schema(&operation["requestBody"]) {
+                    requests.insert(key.clone(), compile(schema, &components));
+                }
+
+                for (status, response) in operation["responses"].as_object().into_iter().flatten() {
+                    if let (Ok(status), Some(schema)) = (status.parse(), json_schema(response)) {
+                        responses.insert((key.clone(), status), compile(schema, &components));
+                    }
+                }
+            }
+        }
+
+        Self {
+            requests,
+            responses,
+            validate_responses,
+        }
+    }
+}
+
+fn json_schema(body: &Value) -> Option<&Value> {
+    body.pointer("/content/application~1json/schema")
+}
+
+// Compiles an operation schema with the document's components alongside it so $refs resolve
+fn compile(schema: &Value, components: &Value) -> Validator {
+    let mut root = schema.clone();
+    if let Some(object) = root.as_object_mut() {
+        object.insert("components".to_string(), components.clone());
+    }
+    jsonschema::draft202012::new(&root).expect("generated OpenAPI schemas are valid JSON Schema")
+}
+
+fn violations(validator: &Validator, instance: &Value) -> Vec<SchemaViolation> {
+    validator
+        .iter_errors(instance)
+        .map(|e| SchemaViolation {
+            pointer: e.instance_path().to_string(),
+            message: e.to_string(),
+        })
+        .collect()
+}
+
+// Converts axum's "/users/:id" route syntax into OpenAPI's "/users/{id}"
+fn openapi_path(route: &str) -> String {
+    route
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => format!("{{{}}}", param),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+pub fn payload_too_large() -> Response {
+    Problem::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        "payload-too-large",
+        "Payload Too Large",
+    )
+    .with_detail(format!("Body exceeds {} bytes", MAX_BODY_BYTES))
+    .into_response()
+}
+
+// Validates JSON request bodies against the documented schema before the handler runs, and
+// (when enabled) checks that handler responses still match what the document promises.
+pub async fn validate_schema(
+    State(schema): State<Arc<SchemaValidator>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(route) = req.extensions().get::<MatchedPath>() else {
+        return next.run(req).await;
+    };
+    let key = (req.method().clone(), openapi_path(route.as_str()));
+
+    let req = match schema.requests.get(&key) {
+        Some(validator) if is_json(req.headers()) || codec::is_msgpack_body(req.headers()) => {
+            let msgpack = codec::is_msgpack_body(req.headers());
+            let (parts, body) = req.into_parts();
+            let Ok(bytes) = to_bytes(body, MAX_BODY_BYTES).await else {
+                return payload_too_large();
+            };
+
+            // Malformed bodies are left for the body extractor to report
+            let instance = if msgpack {
+                codec::decode_msgpack_value(&bytes)
+            } else {
+                serde_json::from_slice::<Value>(&bytes).ok()
+            };
+            if let Some(instance) = instance {
+                let found = violations(validator, &instance);
+                if !found.is_empty() {
+                    return Problem::new(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "schema-violation",
+                        "Request Body Violates Schema",
+                    )
+                    .with_detail(format!(
+                        "{} schema violation(s) in request body",
+                        found.len()
+                    ))
+                    .with_instance(parts.uri.path().to_string())
+                    .with_extension("violations", found)
+                    .into_response();
+                }
+            }
+            Request::from_parts(parts, Body::from(bytes))
+        }
+        _ => req,
+    };
+
+    let response = next.run(req).await;
+    if !schema.validate_responses || !is_json(response.headers()) {
+        return response;
+    }
+    let Some(validator) = schema
+        .responses
+        .get(&(key.clone(), response.status().as_u16()))
+    else {
+        return response;
+    };
+
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if let Ok(instance) = serde_json::from_slice::<Value>(&bytes) {
+        let found = violations(validator, &instance);
+        if !found.is_empty() {
+            error!(
+                "Response for {} {} drifted from the OpenAPI schema: {:?}",
+                key.0, key.1, found
+            );
+            return Problem::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "response-schema-violation",
+                "Response Violates Schema",
+            )
+            .with_detail(
+                "The handler produced a response that does not match its documented schema",
+            )
+            .with_extension("violations", found)
+            .into_response();
+        }
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
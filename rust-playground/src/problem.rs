@@ -0,0 +1,215 @@
+use axum::{
+    extract::{
+        FromRequest, FromRequestParts,
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+    },
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::deadline;
+use crate::storage::StorageError;
+
+// Problem type URIs are relative references under this prefix (RFC 7807 section 3.1)
+const PROBLEM_TYPE_PREFIX: &str = "/problems/";
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+// RFC 7807 problem details body, used for every error response the API produces
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type", serialize_with = "serialize_type")]
+    slug: &'static str,
+    title: &'static str,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
+    #[serde(flatten)]
+    extensions: Map<String, Value>,
+    // Sent as a Retry-After header rather than in the body
+    #[serde(skip)]
+    retry_after: Option<u32>,
+}
+
+fn serialize_type<S: Serializer>(slug: &&'static str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{}{}", PROBLEM_TYPE_PREFIX, slug))
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, slug: &'static str, title: &'static str) -> Self {
+        Self {
+            slug,
+            title,
+            status: status.as_u16(),
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+            retry_after: None,
+        }
+    }
+
+    pub fn bad_request(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad-request", "Bad Request").with_detail(detail)
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not-found", "Resource Not Found").with_detail(detail)
+    }
+
+    pub fn unauthorized(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", "Unauthorized").with_detail(detail)
+    }
+
+    pub fn forbidden(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", "Forbidden").with_detail(detail)
+    }
+
+    pub fn method_not_allowed() -> Self {
+        Self::new(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method-not-allowed",
+            "Method Not Allowed",
+        )
+        .with_detail("The requested method is not supported for this resource")
+    }
+
+    pub fn service_unavailable(detail: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "service-unavailable",
+            "Service Unavailable",
+        )
+        .with_detail(detail)
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    // Adds a problem-type specific member alongside the standard fields
+    pub fn with_extension(mut self, key: &str, value: impl Serialize) -> Self {
+        self.extensions.insert(
+            key.to_string(),
+            serde_json::to_value(value).unwrap_or(Value::Null),
+        );
+        self
+    }
+
+    pub fn with_retry_after(mut self, secs: u32) -> Self {
+        self.retry_after = Some(secs);
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let body = serde_json::to_vec(&self).unwrap_or_default();
+        let mut response = (
+            status,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(PROBLEM_CONTENT_TYPE),
+            )],
+            body,
+        )
+            .into_response();
+        if let Some(secs) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+impl From<StorageError> for Problem {
+    fn from(e: StorageError) -> Self {
+        if let StorageError::DeadlineExceeded = e {
+            return deadline::exceeded_in_storage().unwrap_or_else(|| {
+                Problem::new(
+                    StatusCode::GATEWAY_TIMEOUT,
+                    "deadline-exceeded",
+                    "Deadline Exceeded",
+                )
+            });
+        }
+        let problem = Problem::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "storage-unavailable",
+            "Storage Unavailable",
+        )
+        .with_detail(e.to_string());
+        match e {
+            StorageError::CircuitOpen { retry_after } => {
+                problem.with_retry_after(retry_after.as_secs_f64().ceil().max(1.0) as u32)
+            }
+            StorageError::Unavailable(_) | StorageError::DeadlineExceeded => problem,
+        }
+    }
+}
+
+impl From<JsonRejection> for Problem {
+    fn from(rejection: JsonRejection) -> Self {
+        let status = rejection.status();
+        let (slug, title) = match status {
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => {
+                ("unsupported-media-type", "Unsupported Media Type")
+            }
+            StatusCode::UNPROCESSABLE_ENTITY => ("invalid-body", "Invalid Request Body"),
+            _ => ("malformed-body", "Malformed Request Body"),
+        };
+        Problem::new(status, slug, title).with_detail(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for Problem {
+    fn from(rejection: PathRejection) -> Self {
+        Problem::new(rejection.status(), "invalid-path", "Invalid Path Parameter")
+            .with_detail(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for Problem {
+    fn from(rejection: QueryRejection) -> Self {
+        Problem::new(rejection.status(), "invalid-query", "Invalid Query String")
+            .with_detail(rejection.body_text())
+    }
+}
+
+// Json extractor whose rejections are reported as problem details
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(Problem))]
+pub struct AppJson<T>(pub T);
+
+// Path extractor whose rejections are reported as problem details
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(Problem))]
+pub struct AppPath<T>(pub T);
+
+// Query extractor whose rejections are reported as problem details
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(Problem))]
+pub struct AppQuery<T>(pub T);
+
+pub async fn not_found_fallback() -> Problem {
+    Problem::not_found("No route matches the requested path")
+}
+
+pub async fn method_not_allowed_fallback() -> Problem {
+    Problem::method_not_allowed()
+}
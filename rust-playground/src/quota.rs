@@ -0,0 +1,387 @@
+use axum::{
+    Extension,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, warn};
+
+use crate::problem::Problem;
+use crate::routes::{Auth, RouteTable};
+use crate::{ApiResponse, AppState};
+
+const API_KEY_HEADER: &str = "x-api-key";
+// How often changed usage counters are written to USAGE_FILE
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+// The authenticated client a request is counted against
+#[derive(Debug, Clone)]
+pub struct Caller(pub String);
+
+pub struct QuotaConfig {
+    // API key -> client name
+    keys: HashMap<String, String>,
+    daily_limit: u64,
+    monthly_limit: u64,
+}
+
+impl QuotaConfig {
+    // API_KEYS is a comma-separated list of client:key pairs
+    pub fn from_env() -> Self {
+        let keys = std::env::var("API_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| {
+                let (client, key) = pair.trim().split_once(':')?;
+                Some((key.trim().to_string(), client.trim().to_string()))
+            })
+            .filter(|(key, client)| !key.is_empty() && !client.is_empty())
+            .collect();
+        Self {
+            keys,
+            daily_limit: env_or("QUOTA_DAILY", 1_000),
+            monthly_limit: env_or("QUOTA_MONTHLY", 20_000),
+        }
+    }
+}
+
+fn env_or(key: &str, default: u64) -> u64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// Quota periods a request is counted against, e.g. day "2026-10-14" and month "2026-10"
+#[derive(Debug, Clone)]
+pub struct UsagePeriod {
+    pub day: String,
+    pub month: String,
+}
+
+// Request counters for one client; counts reset when their period rolls over
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub day: String,
+    pub day_count: u64,
+    pub month: String,
+    pub month_count: u64,
+}
+
+impl UsageRecord {
+    fn roll(&mut self, period: &UsagePeriod) {
+        if self.day != period.day {
+            self.day = period.day.clone();
+            self.day_count = 0;
+        }
+        if self.month != period.month {
+            self.month = period.month.clone();
+            self.month_count = 0;
+        }
+    }
+}
+
+// Per-client API usage counters, optionally persisted to USAGE_FILE so quotas survive restarts
+pub struct UsageStore {
+    records: Mutex<HashMap<String, UsageRecord>>,
+    path: Option<PathBuf>,
+    dirty: AtomicBool,
+}
+
+impl UsageStore {
+    pub fn from_env() -> Self {
+        let path = std::env::var_os("USAGE_FILE").map(PathBuf::from);
+        let records = path
+            .as_ref()
+            .and_then(|p| std::fs::read(p).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            records: Mutex::new(records),
+            path,
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn usage(&self, client: &str, period: &UsagePeriod) -> UsageRecord {
+        let mut record = self
+            .records
+            .lock()
+            .unwrap()
+            .get(client)
+            .cloned()
+            .unwrap_or_default();
+        record.roll(period);
+        record
+    }
+
+    // Counts one request if both counters are below their limits. Returns the counters after
+    // counting, or unchanged in Err when a limit is already used up.
+    pub fn try_consume(
+        &self,
+        client: &str,
+        period: &UsagePeriod,
+        daily_limit: u64,
+        monthly_limit: u64,
+    ) -> Result<UsageRecord, UsageRecord> {
+        let mut records = self.records.lock().unwrap();
+        let record = records.entry(client.to_string()).or_default();
+        record.roll(period);
+        if record.day_count >= daily_limit || record.month_count >= monthly_limit {
+            return Err(record.clone());
+        }
+        record.day_count += 1;
+        record.month_count += 1;
+        self.dirty.store(true, Ordering::Relaxed);
+        Ok(record.clone())
+    }
+
+    // Writes the counters to disk if anything changed since the last flush
+    pub fn flush(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let bytes = serde_json::to_vec(&*self.records.lock().unwrap())?;
+        // Write-then-rename so a crash mid-write never leaves a truncated file
+        let tmp = path.with_extension("tmp");
+        let result = std::fs::write(&tmp, bytes).and_then(|_| std::fs::rename(&tmp, path));
+        if result.is_err() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    // Drops clients with no requests since `cutoff_day` (YYYY-MM-DD, so string order is date order)
+    pub fn purge_inactive(&self, cutoff_day: &str) -> usize {
+        let mut records = self.records.lock().unwrap();
+        let before = records.len();
+        records.retain(|_, r| r.day.as_str() >= cutoff_day);
+        let purged = before - records.len();
+        if purged > 0 {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        purged
+    }
+
+    pub fn persistent(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+pub struct Quotas {
+    config: QuotaConfig,
+    usage: UsageStore,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuotaWindow {
+    used: u64,
+    limit: u64,
+    remaining: u64,
+    resets_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    client: String,
+    daily: QuotaWindow,
+    monthly: QuotaWindow,
+}
+
+fn current_period(now: DateTime<Utc>) -> UsagePeriod {
+    UsagePeriod {
+        day: now.format("%Y-%m-%d").to_string(),
+        month: now.format("%Y-%m").to_string(),
+    }
+}
+
+fn next_midnight(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + ChronoDuration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight exists")
+        .and_utc()
+}
+
+fn next_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = match now.month() {
+        12 => (now.year() + 1, 1),
+        m => (now.year(), m + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .expect("first of month exists")
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight exists")
+        .and_utc()
+}
+
+impl Quotas {
+    pub fn new(config: QuotaConfig, usage: UsageStore) -> Self {
+        Self { config, usage }
+    }
+
+    // Persists counters in the background; a no-op store when USAGE_FILE isn't set
+    pub fn spawn_flusher(self: &Arc<Self>) {
+        if !self.usage.persistent() {
+            return;
+        }
+        let quotas = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = quotas.usage.flush() {
+                    error!("Failed to persist API usage: {}", e);
+                }
+            }
+        });
+    }
+
+    pub fn purge_inactive(&self, cutoff: DateTime<Utc>) -> usize {
+        self.usage
+            .purge_inactive(&cutoff.format("%Y-%m-%d").to_string())
+    }
+
+    fn report(&self, client: &str, record: &UsageRecord, now: DateTime<Utc>) -> UsageReport {
+        let window = |used: u64, limit: u64, resets_at| QuotaWindow {
+            used,
+            limit,
+            remaining: limit.saturating_sub(used),
+            resets_at,
+        };
+        UsageReport {
+            client: client.to_string(),
+            daily: window(
+                record.day_count,
+                self.config.daily_limit,
+                next_midnight(now),
+            ),
+            monthly: window(
+                record.month_count,
+                self.config.monthly_limit,
+                next_month(now),
+            ),
+        }
+    }
+}
+
+impl UsageReport {
+    // The window closest to running out drives the X-RateLimit-* headers
+    fn tightest(&self) -> &QuotaWindow {
+        if self.daily.remaining <= self.monthly.remaining {
+            &self.daily
+        } else {
+            &self.monthly
+        }
+    }
+
+    fn apply_headers(&self, headers: &mut HeaderMap) {
+        let window = self.tightest();
+        headers.insert("x-ratelimit-limit", HeaderValue::from(window.limit));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(window.remaining));
+        headers.insert(
+            "x-ratelimit-reset",
+            HeaderValue::from(window.resets_at.timestamp()),
+        );
+    }
+}
+
+// Identifies callers by API key and counts their requests against daily and monthly quotas.
+// Requests without a key are anonymous and not metered.
+pub async fn enforce_quota(
+    State(quotas): State<Arc<Quotas>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = req.headers().get(API_KEY_HEADER) else {
+        return next.run(req).await;
+    };
+    let Some(client) = key
+        .to_str()
+        .ok()
+        .and_then(|k| quotas.config.keys.get(k))
+        .cloned()
+    else {
+        warn!(
+            "Rejected request with unknown API key to {}",
+            req.uri().path()
+        );
+        return Problem::unauthorized("Unknown API key").into_response();
+    };
+
+    let now = Utc::now();
+    let consumed = quotas.usage.try_consume(
+        &client,
+        &current_period(now),
+        quotas.config.daily_limit,
+        quotas.config.monthly_limit,
+    );
+    let report = match consumed {
+        Ok(record) => quotas.report(&client, &record, now),
+        Err(record) => {
+            let report = quotas.report(&client, &record, now);
+            let window = report.tightest();
+            warn!("Client {} exceeded its API quota", client);
+            let retry_after = (window.resets_at - now).num_seconds().max(1);
+            let mut response = Problem::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "quota-exceeded",
+                "Quota Exceeded",
+            )
+            .with_detail(format!(
+                "API quota of {} requests used up until {}",
+                window.limit, window.resets_at
+            ))
+            .into_response();
+            report.apply_headers(response.headers_mut());
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            return response;
+        }
+    };
+
+    req.extensions_mut().insert(Caller(client));
+    let mut response = next.run(req).await;
+    report.apply_headers(response.headers_mut());
+    response
+}
+
+// Route layer for endpoints that only make sense for an identified caller
+pub async fn require_caller(req: Request, next: Next) -> Response {
+    if req.extensions().get::<Caller>().is_none() {
+        return Problem::unauthorized(format!("Send an API key in the {} header", API_KEY_HEADER))
+            .into_response();
+    }
+    next.run(req).await
+}
+
+pub async fn my_usage(
+    State(quotas): State<Arc<Quotas>>,
+    Extension(Caller(client)): Extension<Caller>,
+) -> Json<ApiResponse<UsageReport>> {
+    let now = Utc::now();
+    let record = quotas.usage.usage(&client, &current_period(now));
+    Json(ApiResponse::success(quotas.report(&client, &record, now)))
+}
+
+pub fn routes() -> RouteTable<AppState> {
+    RouteTable::new()
+        .route(
+            Method::GET,
+            "/me/usage",
+            my_usage,
+            "API usage and remaining quota for the caller",
+        )
+        .guard(Auth::ApiKey, middleware::from_fn(require_caller))
+}
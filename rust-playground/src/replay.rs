@@ -0,0 +1,332 @@
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{Json, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use tower::ServiceExt;
+use tracing::{error, info, warn};
+
+use crate::ApiResponse;
+use crate::deadline;
+use crate::openapi::{self, MAX_BODY_BYTES};
+use crate::problem::{AppPath, Problem};
+
+// Bodies beyond this are cut off in the recording (and can't be replayed)
+const MAX_RECORDED_BODY: usize = 256 * 1024;
+const RECORDING_ID_HEADER: &str = "x-recording-id";
+const REPLAY_OF_HEADER: &str = "x-replay-of";
+// Credentials never reach the disk
+const SCRUBBED_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "set-cookie",
+    "x-admin-token",
+    "x-api-key",
+    "x-cluster-token",
+];
+const REDACTED: &str = "[redacted]";
+
+// UTF-8 bodies are stored as text so recordings stay readable; anything else as raw bytes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecordedBody {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(text.to_string()),
+            Err(_) => RecordedBody::Bytes(bytes.to_vec()),
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            RecordedBody::Text(text) => text.into_bytes(),
+            RecordedBody::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Recording {
+    id: u64,
+    recorded_at: DateTime<Utc>,
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+fn recorded_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SCRUBBED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+// Buffers a whole body, flagging whether it's too large to record in full
+async fn buffer(body: Body, limit: usize) -> Result<(Vec<u8>, bool), Response> {
+    match to_bytes(body, limit).await {
+        Ok(bytes) => {
+            let truncated = bytes.len() > MAX_RECORDED_BODY;
+            Ok((bytes.to_vec(), truncated))
+        }
+        Err(_) => Err(openapi::payload_too_large()),
+    }
+}
+
+fn clip(bytes: &[u8], truncated: bool) -> RecordedBody {
+    RecordedBody::new(if truncated {
+        &bytes[..MAX_RECORDED_BODY]
+    } else {
+        bytes
+    })
+}
+
+// Opt-in request/response recorder. With RECORD_DIR set, every exchange is written to one of
+// RECORD_CAPACITY slot files, overwriting the oldest once the ring is full.
+pub struct Recorder {
+    dir: Option<PathBuf>,
+    capacity: u64,
+    next_id: AtomicU64,
+    // The full application, used to re-execute recordings; set once the app is built
+    target: OnceLock<Router>,
+}
+
+impl Recorder {
+    pub fn from_env() -> Self {
+        let dir = std::env::var_os("RECORD_DIR").map(PathBuf::from);
+        let capacity = std::env::var("RECORD_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|c| *c > 0)
+            .unwrap_or(100);
+
+        if let Some(dir) = &dir
+            && let Err(e) = std::fs::create_dir_all(dir)
+        {
+            error!("Cannot create RECORD_DIR {}: {}", dir.display(), e);
+        }
+        // Continue numbering after the newest recording left by a previous run
+        let last_id = dir
+            .as_ref()
+            .and_then(|d| std::fs::read_dir(d).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| std::fs::read(entry.ok()?.path()).ok())
+            .filter_map(|bytes| serde_json::from_slice::<Recording>(&bytes).ok())
+            .map(|r| r.id)
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            dir,
+            capacity,
+            next_id: AtomicU64::new(last_id + 1),
+            target: OnceLock::new(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    pub fn set_target(&self, app: Router) {
+        let _ = self.target.set(app);
+    }
+
+    fn slot(&self, id: u64) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("slot-{}.json", id % self.capacity)))
+    }
+
+    async fn load(&self, id: u64) -> Option<Recording> {
+        let bytes = tokio::fs::read(self.slot(id)?).await.ok()?;
+        let recording: Recording = serde_json::from_slice(&bytes).ok()?;
+        // The slot may have been reused by a newer recording
+        (recording.id == id).then_some(recording)
+    }
+}
+
+pub async fn record_exchange(
+    State(recorder): State<Arc<Recorder>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !recorder.enabled() || req.uri().path().starts_with("/admin/replay/") {
+        return next.run(req).await;
+    }
+
+    let (parts, body) = req.into_parts();
+    let (request_bytes, request_truncated) = match buffer(body, MAX_BODY_BYTES).await {
+        Ok(buffered) => buffered,
+        Err(response) => return response,
+    };
+    let request = RecordedRequest {
+        method: parts.method.to_string(),
+        uri: parts.uri.to_string(),
+        headers: recorded_headers(&parts.headers),
+        body: clip(&request_bytes, request_truncated),
+        truncated: request_truncated,
+    };
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(request_bytes)))
+        .await;
+    let (mut parts, body) = response.into_parts();
+    let (response_bytes, response_truncated) = match buffer(body, usize::MAX).await {
+        Ok(buffered) => buffered,
+        Err(response) => return response,
+    };
+
+    let id = recorder.next_id.fetch_add(1, Ordering::Relaxed);
+    parts
+        .headers
+        .insert(RECORDING_ID_HEADER, HeaderValue::from(id));
+    let recording = Recording {
+        id,
+        recorded_at: Utc::now(),
+        request,
+        response: RecordedResponse {
+            status: parts.status.as_u16(),
+            headers: recorded_headers(&parts.headers),
+            body: clip(&response_bytes, response_truncated),
+            truncated: response_truncated,
+        },
+    };
+    if let Some(path) = recorder.slot(id) {
+        // Written off the request path; a lost recording shouldn't fail the request
+        tokio::spawn(async move {
+            let bytes = serde_json::to_vec_pretty(&recording).unwrap_or_default();
+            if let Err(e) = tokio::fs::write(&path, bytes).await {
+                warn!("Failed to write recording {}: {}", id, e);
+            }
+        });
+    }
+
+    Response::from_parts(parts, Body::from(response_bytes))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayReport {
+    recording_id: u64,
+    original_status: u16,
+    status_matches: bool,
+    body_matches: bool,
+    replayed: RecordedResponse,
+}
+
+fn recording_not_found(id: u64) -> Problem {
+    Problem::not_found(format!(
+        "Recording {} does not exist or has been overwritten",
+        id
+    ))
+    .with_instance(format!("/admin/replay/{}", id))
+}
+
+// Re-executes a recorded request against the running app. Scrubbed credentials are not
+// restored, so requests that needed them run without.
+pub async fn replay(
+    State(recorder): State<Arc<Recorder>>,
+    AppPath(id): AppPath<u64>,
+) -> Result<Json<ApiResponse<ReplayReport>>, Problem> {
+    if !recorder.enabled() {
+        return Err(Problem::not_found(
+            "Recording is disabled (set RECORD_DIR to enable it)",
+        ));
+    }
+    let recording = recorder
+        .load(id)
+        .await
+        .ok_or_else(|| recording_not_found(id))?;
+    if recording.request.truncated {
+        return Err(Problem::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "recording-truncated",
+            "Recording Truncated",
+        )
+        .with_detail("The recorded request body was too large to store in full"));
+    }
+    let Some(app) = recorder.target.get().cloned() else {
+        return Err(Problem::service_unavailable("Replay target not ready"));
+    };
+
+    let method = Method::from_bytes(recording.request.method.as_bytes())
+        .map_err(|_| Problem::bad_request("Recording has an invalid method"))?;
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(&recording.request.uri)
+        .header(REPLAY_OF_HEADER, id);
+    for (name, value) in &recording.request.headers {
+        // An absolute deadline from the original request has long passed
+        if value == REDACTED || name == deadline::DEADLINE_HEADER {
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            builder = builder.header(name, value);
+        }
+    }
+    let request = builder
+        .body(Body::from(recording.request.body.clone().into_bytes()))
+        .map_err(|e| Problem::bad_request(format!("Recording can't be rebuilt: {}", e)))?;
+
+    info!(
+        "Replaying recording {}: {} {}",
+        id, recording.request.method, recording.request.uri
+    );
+    let response = app.oneshot(request).await.unwrap_or_else(|e| match e {});
+    let (parts, body) = response.into_parts();
+    let (bytes, truncated) = buffer(body, usize::MAX)
+        .await
+        .map_err(|_| Problem::service_unavailable("Failed to read the replayed response"))?;
+    let replayed = RecordedResponse {
+        status: parts.status.as_u16(),
+        headers: recorded_headers(&parts.headers),
+        body: clip(&bytes, truncated),
+        truncated,
+    };
+
+    Ok(Json(ApiResponse::success(ReplayReport {
+        recording_id: id,
+        original_status: recording.response.status,
+        status_matches: recording.response.status == replayed.status,
+        body_matches: recording.response.body == replayed.body,
+        replayed,
+    })))
+}
@@ -0,0 +1,151 @@
+use axum::{extract::State, response::Json};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::info;
+
+use crate::ApiResponse;
+use crate::cluster::Cluster;
+use crate::quota::Quotas;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Days are read from the environment; 0 disables the policy, and so does a count too big
+// to be a duration, since nothing would ever be old enough
+fn env_days(key: &str, default: u64) -> Option<Duration> {
+    let days: u64 = std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default);
+    if days == 0 {
+        return None;
+    }
+    Some(Duration::from_secs(days.checked_mul(DAY.as_secs())?))
+}
+
+type PurgeFn = Box<dyn Fn(DateTime<Utc>) -> usize + Send + Sync>;
+
+// Deletes data older than `max_age`; `purge` receives the cutoff and returns rows removed
+struct Policy {
+    name: &'static str,
+    max_age: Duration,
+    purge: PurgeFn,
+    stats: Mutex<PolicyStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PolicyStats {
+    runs: u64,
+    purged_total: u64,
+    purged_last_run: u64,
+    last_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PolicyReport {
+    name: &'static str,
+    max_age_days: u64,
+    #[serde(flatten)]
+    stats: PolicyStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetentionMetrics {
+    interval_secs: u64,
+    policies: Vec<PolicyReport>,
+}
+
+// Background scheduler that periodically applies the retention policies
+pub struct Retention {
+    interval: Duration,
+    policies: Vec<Policy>,
+}
+
+impl Retention {
+    pub fn from_env(cluster: Arc<Cluster>, quotas: Arc<Quotas>) -> Self {
+        let interval = Duration::from_secs(
+            std::env::var("RETENTION_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|s| *s > 0)
+                .unwrap_or(3600),
+        );
+
+        let mut policies = Vec::new();
+        let mut add = |name, max_age: Option<Duration>, purge: PurgeFn| {
+            if let Some(max_age) = max_age {
+                policies.push(Policy {
+                    name,
+                    max_age,
+                    purge,
+                    stats: Mutex::new(PolicyStats::default()),
+                });
+            }
+        };
+        add(
+            "cluster-tombstones",
+            env_days("TOMBSTONE_RETENTION_DAYS", 7),
+            Box::new(move |cutoff| cluster.purge_tombstones(cutoff.into())),
+        );
+        add(
+            "inactive-api-usage",
+            env_days("USAGE_RETENTION_DAYS", 62),
+            Box::new(move |cutoff| quotas.purge_inactive(cutoff)),
+        );
+
+        Self { interval, policies }
+    }
+
+    fn run_once(&self) {
+        let now = Utc::now();
+        for policy in &self.policies {
+            // An age reaching back past the earliest representable time purges nothing
+            let cutoff = TimeDelta::from_std(policy.max_age)
+                .ok()
+                .and_then(|age| now.checked_sub_signed(age))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC);
+            let purged = (policy.purge)(cutoff) as u64;
+            if purged > 0 {
+                info!("Retention {}: purged {} row(s)", policy.name, purged);
+            }
+            let mut stats = policy.stats.lock().unwrap();
+            stats.runs += 1;
+            stats.purged_total += purged;
+            stats.purged_last_run = purged;
+            stats.last_run_at = Some(now);
+        }
+    }
+
+    pub fn spawn(self: &Arc<Self>) {
+        let retention = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(retention.interval);
+            loop {
+                interval.tick().await;
+                retention.run_once();
+            }
+        });
+    }
+
+    fn metrics(&self) -> RetentionMetrics {
+        RetentionMetrics {
+            interval_secs: self.interval.as_secs(),
+            policies: self
+                .policies
+                .iter()
+                .map(|p| PolicyReport {
+                    name: p.name,
+                    max_age_days: p.max_age.as_secs() / DAY.as_secs(),
+                    stats: p.stats.lock().unwrap().clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub async fn retention_metrics(
+    State(retention): State<Arc<Retention>>,
+) -> Json<ApiResponse<RetentionMetrics>> {
+    Json(ApiResponse::success(retention.metrics()))
+}
@@ -0,0 +1,110 @@
+use axum::{
+    Extension, Router,
+    extract::Request,
+    handler::Handler,
+    http::Method,
+    response::{IntoResponse, Json},
+    routing::{MethodFilter, Route, on},
+};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tower::{Layer, Service};
+
+use crate::ApiResponse;
+use crate::load_shed;
+
+// Credentials a route demands, as enforced by the route layer it was registered under
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Auth {
+    None,
+    ApiKey,
+    AdminToken,
+    ClusterToken,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteInfo {
+    method: String,
+    path: &'static str,
+    description: &'static str,
+    auth: Auth,
+    // Whether the load shedder may reject the route under pressure
+    load_shed: bool,
+}
+
+pub type RouteList = Arc<Vec<RouteInfo>>;
+
+// Builds the Router and its route table side by side, so the table can't drift from what's
+// actually served
+pub struct RouteTable<S> {
+    router: Router<S>,
+    routes: Vec<RouteInfo>,
+}
+
+impl<S: Clone + Send + Sync + 'static> RouteTable<S> {
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            routes: Vec::new(),
+        }
+    }
+
+    pub fn route<H, T>(
+        mut self,
+        method: Method,
+        path: &'static str,
+        handler: H,
+        description: &'static str,
+    ) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone()).expect("route uses a standard method");
+        self.router = self.router.route(path, on(filter, handler));
+        self.routes.push(RouteInfo {
+            method: method.to_string(),
+            path,
+            description,
+            auth: Auth::None,
+            load_shed: !load_shed::is_exempt(path),
+        });
+        self
+    }
+
+    // Like Router::route_layer: guards every route added so far and records the requirement
+    pub fn guard<L>(mut self, auth: Auth, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.router = self.router.route_layer(layer);
+        for route in &mut self.routes {
+            route.auth = auth;
+        }
+        self
+    }
+
+    pub fn merge(mut self, other: RouteTable<S>) -> Self {
+        self.router = self.router.merge(other.router);
+        self.routes.extend(other.routes);
+        self
+    }
+
+    // The router, with the table attached so GET /admin/routes can serve it
+    pub fn into_parts(self) -> (Router<S>, RouteList) {
+        let routes = Arc::new(self.routes);
+        (self.router.layer(Extension(routes.clone())), routes)
+    }
+}
+
+pub async fn list_routes(
+    Extension(routes): Extension<RouteList>,
+) -> Json<ApiResponse<Vec<RouteInfo>>> {
+    Json(ApiResponse::success(routes.to_vec()))
+}
@@ -0,0 +1,118 @@
+use axum::{extract::State, response::Json};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::problem::Problem;
+use crate::{ApiResponse, Database, User};
+
+// Width of each age histogram bucket, in years
+const AGE_BUCKET_WIDTH: u32 = 10;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserStats {
+    total_users: usize,
+    age: Option<AgeSummary>,
+    age_histogram: Vec<AgeBucket>,
+    email_domains: Vec<DomainCount>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AgeSummary {
+    min: u32,
+    max: u32,
+    mean: f64,
+    median: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AgeBucket {
+    // Inclusive bounds, e.g. 20..=29
+    from: u32,
+    to: u32,
+    count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DomainCount {
+    domain: String,
+    count: usize,
+}
+
+fn age_summary(ages: &[u32]) -> Option<AgeSummary> {
+    let (&min, &max) = (ages.first()?, ages.last()?);
+    let mean = ages.iter().map(|&a| a as f64).sum::<f64>() / ages.len() as f64;
+    let mid = ages.len() / 2;
+    let median = if ages.len().is_multiple_of(2) {
+        (ages[mid - 1] as f64 + ages[mid] as f64) / 2.0
+    } else {
+        ages[mid] as f64
+    };
+    Some(AgeSummary {
+        min,
+        max,
+        mean,
+        median,
+    })
+}
+
+fn age_histogram(ages: &[u32]) -> Vec<AgeBucket> {
+    let mut buckets: BTreeMap<u32, usize> = BTreeMap::new();
+    for age in ages {
+        *buckets.entry(age / AGE_BUCKET_WIDTH).or_default() += 1;
+    }
+    buckets
+        .into_iter()
+        .map(|(bucket, count)| {
+            // The last bucket stops at u32::MAX rather than wrapping past it
+            let from = bucket.saturating_mul(AGE_BUCKET_WIDTH);
+            AgeBucket {
+                from,
+                to: from.saturating_add(AGE_BUCKET_WIDTH - 1),
+                count,
+            }
+        })
+        .collect()
+}
+
+fn email_domains<'a>(users: impl Iterator<Item = &'a User>) -> Vec<DomainCount> {
+    let mut domains: BTreeMap<String, usize> = BTreeMap::new();
+    for user in users {
+        if let Some((_, domain)) = user.email.rsplit_once('@') {
+            *domains.entry(domain.to_lowercase()).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<DomainCount> = domains
+        .into_iter()
+        .map(|(domain, count)| DomainCount { domain, count })
+        .collect();
+    // Most common first; BTreeMap order keeps ties alphabetical
+    counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+    counts
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/stats",
+    responses(
+        (status = 200, description = "Aggregate user statistics", body = ApiResponse<UserStats>),
+        (status = 503, description = "Storage unavailable")
+    )
+)]
+pub async fn user_stats(
+    State(db): State<Database>,
+) -> Result<Json<ApiResponse<UserStats>>, Problem> {
+    info!("Computing user statistics");
+    let users = db.list()?;
+
+    let mut ages: Vec<u32> = users.iter().map(|u| u.age).collect();
+    ages.sort_unstable();
+
+    Ok(Json(ApiResponse::success(UserStats {
+        total_users: users.len(),
+        age: age_summary(&ages),
+        age_histogram: age_histogram(&ages),
+        email_domains: email_domains(users.iter()),
+    })))
+}
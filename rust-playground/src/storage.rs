@@ -0,0 +1,634 @@
+use chrono::{DateTime, Utc};
+use rstar::RTree;
+use rstar::primitives::GeomWithData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::User;
+use crate::geo::{self, NearbyUser};
+use crate::lifecycle::UserStatus;
+
+// Field-level changes applied by an update; None leaves the field untouched
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UserPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<UserStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+}
+
+impl UserPatch {
+    pub fn apply_to(&self, user: &mut User) {
+        if let Some(name) = &self.name {
+            user.name = name.clone();
+        }
+        if let Some(email) = &self.email {
+            user.email = email.clone();
+        }
+        if let Some(age) = self.age {
+            user.age = age;
+        }
+        if let Some(status) = self.status {
+            user.status = status;
+        }
+        if let Some(latitude) = self.latitude {
+            user.latitude = Some(latitude);
+        }
+        if let Some(longitude) = self.longitude {
+            user.longitude = Some(longitude);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StorageError {
+    // The backend failed the call, e.g. it's unreachable or its state is corrupt
+    Unavailable(String),
+    // The circuit breaker is open and the backend isn't being called at all
+    CircuitOpen { retry_after: Duration },
+    // The request's deadline passed before the call was made
+    DeadlineExceeded,
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Unavailable(reason) => write!(f, "Storage unavailable: {}", reason),
+            StorageError::CircuitOpen { retry_after } => write!(
+                f,
+                "Storage unavailable, not retrying for another {}s",
+                retry_after.as_secs_f64().ceil().max(1.0)
+            ),
+            StorageError::DeadlineExceeded => write!(f, "Request deadline exceeded"),
+        }
+    }
+}
+
+pub type StoreResult<T> = Result<T, StorageError>;
+
+// A panic while holding the lock leaves the state suspect; report that instead of panicking too
+fn poisoned() -> StorageError {
+    StorageError::Unavailable("state lock poisoned".to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LockReport {
+    reads: u64,
+    writes: u64,
+    // Acquisitions that had to wait for another holder
+    contended: u64,
+    contention_rate: f64,
+    wait_ms_total: f64,
+    wait_ms_max: f64,
+}
+
+// RwLock that counts acquisitions and how often, and how long, callers had to wait
+struct InstrumentedLock<T> {
+    lock: RwLock<T>,
+    reads: AtomicU64,
+    writes: AtomicU64,
+    contended: AtomicU64,
+    wait_ns: AtomicU64,
+    max_wait_ns: AtomicU64,
+}
+
+impl<T> InstrumentedLock<T> {
+    fn new(value: T) -> Self {
+        Self {
+            lock: RwLock::new(value),
+            reads: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+            contended: AtomicU64::new(0),
+            wait_ns: AtomicU64::new(0),
+            max_wait_ns: AtomicU64::new(0),
+        }
+    }
+
+    fn waited(&self, started: Instant) {
+        let waited = started.elapsed().as_nanos() as u64;
+        self.contended.fetch_add(1, Ordering::Relaxed);
+        self.wait_ns.fetch_add(waited, Ordering::Relaxed);
+        self.max_wait_ns.fetch_max(waited, Ordering::Relaxed);
+    }
+
+    fn read(&self) -> StoreResult<RwLockReadGuard<'_, T>> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        match self.lock.try_read() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::WouldBlock) => {
+                let started = Instant::now();
+                let guard = self.lock.read().map_err(|_| poisoned())?;
+                self.waited(started);
+                Ok(guard)
+            }
+            Err(TryLockError::Poisoned(_)) => Err(poisoned()),
+        }
+    }
+
+    fn write(&self) -> StoreResult<RwLockWriteGuard<'_, T>> {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        match self.lock.try_write() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::WouldBlock) => {
+                let started = Instant::now();
+                let guard = self.lock.write().map_err(|_| poisoned())?;
+                self.waited(started);
+                Ok(guard)
+            }
+            Err(TryLockError::Poisoned(_)) => Err(poisoned()),
+        }
+    }
+
+    fn report(&self) -> LockReport {
+        let reads = self.reads.load(Ordering::Relaxed);
+        let writes = self.writes.load(Ordering::Relaxed);
+        let contended = self.contended.load(Ordering::Relaxed);
+        LockReport {
+            reads,
+            writes,
+            contended,
+            contention_rate: contended as f64 / (reads + writes).max(1) as f64,
+            wait_ms_total: self.wait_ns.load(Ordering::Relaxed) as f64 / 1e6,
+            wait_ms_max: self.max_wait_ns.load(Ordering::Relaxed) as f64 / 1e6,
+        }
+    }
+}
+
+// What GET /admin/capacity reports for the active backend
+#[derive(Debug, Clone, Serialize)]
+pub struct CapacityReport {
+    backend: &'static str,
+    users: usize,
+    // Events in the log, for backends that keep one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<usize>,
+    // Rough size of the stored data: entries plus their strings, ignoring allocator overhead
+    estimated_bytes: usize,
+    locks: LockReport,
+}
+
+fn user_heap_bytes(user: &User) -> usize {
+    user.name.capacity() + user.email.capacity()
+}
+
+// Slots a HashMap has allocated, each holding a key, a value and a control byte
+fn map_bytes<K, V>(map: &HashMap<K, V>) -> usize {
+    map.capacity() * (size_of::<K>() + size_of::<V>() + 1)
+}
+
+// Storage backend for users; handlers only talk to this trait
+pub trait UserStore: Send + Sync {
+    fn list(&self) -> StoreResult<Vec<User>>;
+    fn get(&self, id: Uuid) -> StoreResult<Option<User>>;
+    fn insert(&self, user: User) -> StoreResult<()>;
+    fn update(&self, id: Uuid, patch: UserPatch) -> StoreResult<Option<User>>;
+    // Patches a user with whatever `decide` makes of its current state, deciding and writing
+    // under one write lock so nothing changes in between; `decide` returning None leaves it
+    // alone. None if there's no such user, otherwise the user before and, if patched, after.
+    fn update_if(
+        &self,
+        id: Uuid,
+        decide: &dyn Fn(&User) -> Option<UserPatch>,
+    ) -> StoreResult<Option<(User, Option<User>)>>;
+    fn delete(&self, id: Uuid) -> StoreResult<Option<User>>;
+    // Patches `keep` with what `patch_for` takes from it and `merge`, and deletes `merge`,
+    // all under one write lock. Err is the ID of a user that doesn't exist.
+    fn merge(
+        &self,
+        keep: Uuid,
+        merge: Uuid,
+        patch_for: &dyn Fn(&User, &User) -> UserPatch,
+    ) -> StoreResult<Result<Merged, Uuid>>;
+    // Removes every user, returning how many were removed
+    fn clear(&self) -> StoreResult<usize>;
+    fn len(&self) -> StoreResult<usize>;
+    fn capacity(&self) -> StoreResult<CapacityReport>;
+
+    fn is_empty(&self) -> StoreResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    // Event stream for one user; None if the backend doesn't keep history
+    fn history(&self, _id: Uuid) -> StoreResult<Option<Vec<StoredEvent>>> {
+        Ok(None)
+    }
+
+    // Users with a location within `radius_km` of the point, nearest first. The default
+    // scans every user; backends with a spatial index override it.
+    fn nearby(&self, lat: f64, lon: f64, radius_km: f64) -> StoreResult<Vec<NearbyUser>> {
+        Ok(geo::within_radius(self.list()?, lat, lon, radius_km))
+    }
+}
+
+// The kept user before and after a merge, and the one merged into it
+pub struct Merged {
+    pub before: User,
+    pub after: User,
+    pub removed: User,
+}
+
+pub fn from_env() -> Box<dyn UserStore> {
+    match std::env::var("STORAGE_BACKEND").as_deref() {
+        Ok("event-sourced") => Box::new(EventSourcedStore::new()),
+        _ => Box::new(InMemoryStore::new()),
+    }
+}
+
+// R-tree entry: [longitude, latitude] in degrees, tagged with the user it belongs to
+type LocationEntry = GeomWithData<[f64; 2], Uuid>;
+
+fn location_entry(user: &User) -> Option<LocationEntry> {
+    Some(GeomWithData::new(
+        [user.longitude?, user.latitude?],
+        user.id,
+    ))
+}
+
+struct InMemoryState {
+    users: HashMap<Uuid, User>,
+    // Spatial index over users that have a location; kept in step with `users`
+    locations: RTree<LocationEntry>,
+}
+
+impl InMemoryState {
+    fn unindex(&mut self, user: &User) {
+        if let Some(entry) = location_entry(user) {
+            self.locations.remove(&entry);
+        }
+    }
+
+    fn index(&mut self, user: &User) {
+        if let Some(entry) = location_entry(user) {
+            self.locations.insert(entry);
+        }
+    }
+
+    fn patch(&mut self, mut user: User, patch: &UserPatch) -> User {
+        self.unindex(&user);
+        patch.apply_to(&mut user);
+        self.index(&user);
+        self.users.insert(user.id, user.clone());
+        user
+    }
+}
+
+// HashMap behind a lock (the original playground backend) plus an R-tree for location queries
+pub struct InMemoryStore {
+    state: InstrumentedLock<InMemoryState>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self {
+            state: InstrumentedLock::new(InMemoryState {
+                users: HashMap::new(),
+                locations: RTree::new(),
+            }),
+        }
+    }
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserStore for InMemoryStore {
+    fn list(&self) -> StoreResult<Vec<User>> {
+        Ok(self.state.read()?.users.values().cloned().collect())
+    }
+
+    fn get(&self, id: Uuid) -> StoreResult<Option<User>> {
+        Ok(self.state.read()?.users.get(&id).cloned())
+    }
+
+    fn insert(&self, user: User) -> StoreResult<()> {
+        let mut state = self.state.write()?;
+        if let Some(previous) = state.users.remove(&user.id) {
+            state.unindex(&previous);
+        }
+        state.index(&user);
+        state.users.insert(user.id, user);
+        Ok(())
+    }
+
+    fn update(&self, id: Uuid, patch: UserPatch) -> StoreResult<Option<User>> {
+        let mut state = self.state.write()?;
+        let Some(user) = state.users.get(&id).cloned() else {
+            return Ok(None);
+        };
+        Ok(Some(state.patch(user, &patch)))
+    }
+
+    fn update_if(
+        &self,
+        id: Uuid,
+        decide: &dyn Fn(&User) -> Option<UserPatch>,
+    ) -> StoreResult<Option<(User, Option<User>)>> {
+        let mut state = self.state.write()?;
+        let Some(before) = state.users.get(&id).cloned() else {
+            return Ok(None);
+        };
+        let Some(patch) = decide(&before) else {
+            return Ok(Some((before, None)));
+        };
+        let after = state.patch(before.clone(), &patch);
+        Ok(Some((before, Some(after))))
+    }
+
+    fn delete(&self, id: Uuid) -> StoreResult<Option<User>> {
+        let mut state = self.state.write()?;
+        let user = state.users.remove(&id);
+        if let Some(user) = &user {
+            state.unindex(user);
+        }
+        Ok(user)
+    }
+
+    fn merge(
+        &self,
+        keep: Uuid,
+        merge: Uuid,
+        patch_for: &dyn Fn(&User, &User) -> UserPatch,
+    ) -> StoreResult<Result<Merged, Uuid>> {
+        let mut state = self.state.write()?;
+        let Some(before) = state.users.get(&keep).cloned() else {
+            return Ok(Err(keep));
+        };
+        let Some(removed) = state.users.remove(&merge) else {
+            return Ok(Err(merge));
+        };
+        state.unindex(&removed);
+        let after = state.patch(before.clone(), &patch_for(&before, &removed));
+        Ok(Ok(Merged {
+            before,
+            after,
+            removed,
+        }))
+    }
+
+    fn clear(&self) -> StoreResult<usize> {
+        let mut state = self.state.write()?;
+        let removed = state.users.len();
+        state.users.clear();
+        state.locations = RTree::new();
+        Ok(removed)
+    }
+
+    fn len(&self) -> StoreResult<usize> {
+        Ok(self.state.read()?.users.len())
+    }
+
+    fn capacity(&self) -> StoreResult<CapacityReport> {
+        let state = self.state.read()?;
+        let estimated_bytes = map_bytes(&state.users)
+            + state.users.values().map(user_heap_bytes).sum::<usize>()
+            + state.locations.size() * size_of::<LocationEntry>();
+        Ok(CapacityReport {
+            backend: "in-memory",
+            users: state.users.len(),
+            events: None,
+            estimated_bytes,
+            locks: self.state.report(),
+        })
+    }
+
+    // Narrows candidates with bounding boxes in degree space, then checks the real distance
+    fn nearby(&self, lat: f64, lon: f64, radius_km: f64) -> StoreResult<Vec<NearbyUser>> {
+        let state = self.state.read()?;
+        let candidates = geo::bounding_boxes(lat, lon, radius_km)
+            .into_iter()
+            .flat_map(|envelope| state.locations.locate_in_envelope(&envelope))
+            .filter_map(|entry| state.users.get(&entry.data).cloned())
+            .collect();
+        Ok(geo::within_radius(candidates, lat, lon, radius_km))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type")]
+pub enum UserEvent {
+    #[serde(rename = "UserCreated")]
+    Created { user: User },
+    #[serde(rename = "UserUpdated")]
+    Updated { id: Uuid, changes: UserPatch },
+    #[serde(rename = "UserDeleted")]
+    Deleted { id: Uuid },
+}
+
+impl UserEvent {
+    fn user_id(&self) -> Uuid {
+        match self {
+            UserEvent::Created { user } => user.id,
+            UserEvent::Updated { id, .. } | UserEvent::Deleted { id } => *id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StoredEvent {
+    sequence: u64,
+    recorded_at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: UserEvent,
+}
+
+// Folds one event into the materialized view
+fn apply(users: &mut HashMap<Uuid, User>, event: &UserEvent) {
+    match event {
+        UserEvent::Created { user } => {
+            users.insert(user.id, user.clone());
+        }
+        UserEvent::Updated { id, changes } => {
+            if let Some(user) = users.get_mut(id) {
+                changes.apply_to(user);
+            }
+        }
+        UserEvent::Deleted { id } => {
+            users.remove(id);
+        }
+    }
+}
+
+struct EventSourcedState {
+    log: Vec<StoredEvent>,
+    // Projection of `log`; always equal to replaying it from the start
+    users: HashMap<Uuid, User>,
+}
+
+impl EventSourcedState {
+    fn append(&mut self, event: UserEvent) {
+        apply(&mut self.users, &event);
+        self.log.push(StoredEvent {
+            sequence: self.log.len() as u64 + 1,
+            recorded_at: Utc::now(),
+            event,
+        });
+    }
+}
+
+// Experimental backend: the append-only event log is the source of truth and users are
+// materialized from it
+pub struct EventSourcedStore {
+    state: InstrumentedLock<EventSourcedState>,
+}
+
+impl EventSourcedStore {
+    pub fn new() -> Self {
+        Self {
+            state: InstrumentedLock::new(EventSourcedState {
+                log: Vec::new(),
+                users: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl Default for EventSourcedStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserStore for EventSourcedStore {
+    fn list(&self) -> StoreResult<Vec<User>> {
+        Ok(self.state.read()?.users.values().cloned().collect())
+    }
+
+    fn get(&self, id: Uuid) -> StoreResult<Option<User>> {
+        Ok(self.state.read()?.users.get(&id).cloned())
+    }
+
+    fn insert(&self, user: User) -> StoreResult<()> {
+        self.state.write()?.append(UserEvent::Created { user });
+        Ok(())
+    }
+
+    fn update(&self, id: Uuid, patch: UserPatch) -> StoreResult<Option<User>> {
+        let mut state = self.state.write()?;
+        if !state.users.contains_key(&id) {
+            return Ok(None);
+        }
+        state.append(UserEvent::Updated { id, changes: patch });
+        Ok(state.users.get(&id).cloned())
+    }
+
+    fn update_if(
+        &self,
+        id: Uuid,
+        decide: &dyn Fn(&User) -> Option<UserPatch>,
+    ) -> StoreResult<Option<(User, Option<User>)>> {
+        let mut state = self.state.write()?;
+        let Some(before) = state.users.get(&id).cloned() else {
+            return Ok(None);
+        };
+        let Some(patch) = decide(&before) else {
+            return Ok(Some((before, None)));
+        };
+        state.append(UserEvent::Updated { id, changes: patch });
+        let after = state.users.get(&id).cloned();
+        Ok(Some((before, after)))
+    }
+
+    fn delete(&self, id: Uuid) -> StoreResult<Option<User>> {
+        let mut state = self.state.write()?;
+        let user = state.users.get(&id).cloned();
+        if user.is_some() {
+            state.append(UserEvent::Deleted { id });
+        }
+        Ok(user)
+    }
+
+    fn merge(
+        &self,
+        keep: Uuid,
+        merge: Uuid,
+        patch_for: &dyn Fn(&User, &User) -> UserPatch,
+    ) -> StoreResult<Result<Merged, Uuid>> {
+        let mut state = self.state.write()?;
+        let Some(before) = state.users.get(&keep).cloned() else {
+            return Ok(Err(keep));
+        };
+        let Some(removed) = state.users.get(&merge).cloned() else {
+            return Ok(Err(merge));
+        };
+        state.append(UserEvent::Updated {
+            id: keep,
+            changes: patch_for(&before, &removed),
+        });
+        state.append(UserEvent::Deleted { id: merge });
+        let after = state.users[&keep].clone();
+        Ok(Ok(Merged {
+            before,
+            after,
+            removed,
+        }))
+    }
+
+    fn clear(&self) -> StoreResult<usize> {
+        let mut state = self.state.write()?;
+        let ids: Vec<Uuid> = state.users.keys().copied().collect();
+        for id in &ids {
+            state.append(UserEvent::Deleted { id: *id });
+        }
+        Ok(ids.len())
+    }
+
+    fn len(&self) -> StoreResult<usize> {
+        Ok(self.state.read()?.users.len())
+    }
+
+    fn capacity(&self) -> StoreResult<CapacityReport> {
+        let state = self.state.read()?;
+        let log_heap: usize = state
+            .log
+            .iter()
+            .map(|e| match &e.event {
+                UserEvent::Created { user } => user_heap_bytes(user),
+                UserEvent::Updated { changes, .. } => {
+                    changes.name.as_ref().map_or(0, String::capacity)
+                        + changes.email.as_ref().map_or(0, String::capacity)
+                }
+                UserEvent::Deleted { .. } => 0,
+            })
+            .sum();
+        let estimated_bytes = map_bytes(&state.users)
+            + state.users.values().map(user_heap_bytes).sum::<usize>()
+            + state.log.capacity() * size_of::<StoredEvent>()
+            + log_heap;
+        Ok(CapacityReport {
+            backend: "event-sourced",
+            users: state.users.len(),
+            events: Some(state.log.len()),
+            estimated_bytes,
+            locks: self.state.report(),
+        })
+    }
+
+    fn history(&self, id: Uuid) -> StoreResult<Option<Vec<StoredEvent>>> {
+        let state = self.state.read()?;
+        Ok(Some(
+            state
+                .log
+                .iter()
+                .filter(|e| e.event.user_id() == id)
+                .cloned()
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+use crate::User;
+use crate::geo;
+use crate::problem::Problem;
+use axum::http::StatusCode;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleViolation {
+    rule: &'static str,
+    field: &'static str,
+    message: String,
+}
+
+// A business rule a user must satisfy after every create or update
+pub trait Validator: Send + Sync {
+    fn validate(&self, user: &User) -> Result<(), RuleViolation>;
+}
+
+// Which built-in rules are active; lives in the runtime config, so it hot-reloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationConfig {
+    pub email_format: bool,
+    // Empty allows any domain; otherwise e.g. ["example.com"] for corporate addresses only
+    pub allowed_email_domains: Vec<String>,
+    pub min_age: Option<u32>,
+    pub max_age: Option<u32>,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            email_format: true,
+            allowed_email_domains: Vec::new(),
+            min_age: None,
+            max_age: None,
+        }
+    }
+}
+
+impl ValidationConfig {
+    pub fn check(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_age, self.max_age)
+            && min > max
+        {
+            return Err(format!(
+                "validation.min_age {} exceeds max_age {}",
+                min, max
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Coordinates must come as a pair and lie on the globe; always on
+struct LocationRule;
+
+impl Validator for LocationRule {
+    fn validate(&self, user: &User) -> Result<(), RuleViolation> {
+        geo::check_location(user.latitude, user.longitude).map_err(|message| RuleViolation {
+            rule: "location",
+            field: "latitude",
+            message,
+        })
+    }
+}
+
+struct EmailFormat;
+
+impl Validator for EmailFormat {
+    fn validate(&self, user: &User) -> Result<(), RuleViolation> {
+        let valid = match user.email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.is_empty()
+                    && !domain.contains('@')
+                    && !user.email.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(RuleViolation {
+                rule: "email-format",
+                field: "email",
+                message: format!("{:?} is not an email address", user.email),
+            })
+        }
+    }
+}
+
+struct AllowedEmailDomains(Vec<String>);
+
+impl Validator for AllowedEmailDomains {
+    fn validate(&self, user: &User) -> Result<(), RuleViolation> {
+        let domain = user
+            .email
+            .rsplit_once('@')
+            .map(|(_, d)| d.to_lowercase())
+            .unwrap_or_default();
+        if self
+            .0
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&domain))
+        {
+            Ok(())
+        } else {
+            Err(RuleViolation {
+                rule: "email-domain",
+                field: "email",
+                message: format!("Email domain must be one of: {}", self.0.join(", ")),
+            })
+        }
+    }
+}
+
+struct AgeRange {
+    min: Option<u32>,
+    max: Option<u32>,
+}
+
+impl Validator for AgeRange {
+    fn validate(&self, user: &User) -> Result<(), RuleViolation> {
+        let message = match (self.min, self.max) {
+            (Some(min), _) if user.age < min => format!("Users must be at least {}", min),
+            (_, Some(max)) if user.age > max => format!("Users must be at most {}", max),
+            _ => return Ok(()),
+        };
+        Err(RuleViolation {
+            rule: "age-policy",
+            field: "age",
+            message,
+        })
+    }
+}
+
+// The active validators; handlers run every one of them and report all violations together
+pub struct ValidatorRegistry {
+    validators: RwLock<Vec<Arc<dyn Validator>>>,
+}
+
+impl ValidatorRegistry {
+    pub fn new(config: &ValidationConfig) -> Self {
+        Self {
+            validators: RwLock::new(build(config)),
+        }
+    }
+
+    pub fn reconfigure(&self, config: &ValidationConfig) {
+        *self.validators.write().unwrap() = build(config);
+    }
+
+    pub fn check(&self, user: &User) -> Result<(), Problem> {
+        let validators = self.validators.read().unwrap().clone();
+        let violations: Vec<RuleViolation> = validators
+            .iter()
+            .filter_map(|v| v.validate(user).err())
+            .collect();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(Problem::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "validation-failed",
+            "Validation Failed",
+        )
+        .with_detail(format!("{} validation rule(s) failed", violations.len()))
+        .with_extension("violations", violations))
+    }
+}
+
+// New rules are added here, switched on by their config fields
+fn build(config: &ValidationConfig) -> Vec<Arc<dyn Validator>> {
+    let mut validators: Vec<Arc<dyn Validator>> = vec![Arc::new(LocationRule)];
+    if config.email_format {
+        validators.push(Arc::new(EmailFormat));
+    }
+    if !config.allowed_email_domains.is_empty() {
+        validators.push(Arc::new(AllowedEmailDomains(
+            config.allowed_email_domains.clone(),
+        )));
+    }
+    if config.min_age.is_some() || config.max_age.is_some() {
+        validators.push(Arc::new(AgeRange {
+            min: config.min_age,
+            max: config.max_age,
+        }));
+    }
+    validators
+}
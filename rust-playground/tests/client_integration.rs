@@ -0,0 +1,152 @@
+// Runs the server and drives it with the `client` subcommand, whose requests all go through
+// the generated ApiClient, so a client out of step with the handlers fails here
+use serde_json::Value;
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BINARY: &str = env!("CARGO_BIN_EXE_rust-playground");
+
+// The server process, killed when the test is done with it
+struct Server {
+    child: Child,
+    url: String,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn start_server() -> Server {
+    let port = free_port();
+    let child = Command::new(BINARY)
+        .arg("serve")
+        .env("PORT", port.to_string())
+        .env_remove("PEERS")
+        .env_remove("STORAGE_BACKEND")
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    let server = Server {
+        child,
+        url: format!("http://127.0.0.1:{}", port),
+    };
+    let started = Instant::now();
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "server didn't start listening on {}",
+            port
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+    server
+}
+
+fn client(server: &Server, args: &[&str]) -> Output {
+    Command::new(BINARY)
+        .arg("client")
+        .arg("--url")
+        .arg(&server.url)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+// Runs a client command that's expected to succeed and parses what it printed
+fn client_json(server: &Server, args: &[&str]) -> Value {
+    let output = client(server, args);
+    assert!(
+        output.status.success(),
+        "client {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn generated_client_round_trips_a_user() {
+    let server = start_server();
+
+    let health = client(&server, &["health"]);
+    assert!(health.status.success());
+
+    let created = client_json(
+        &server,
+        &[
+            "users",
+            "create",
+            "--name",
+            "Ada Lovelace",
+            "--email",
+            "ada@example.com",
+            "--age",
+            "36",
+            "--lat",
+            "51.5",
+            "--lon",
+            "-0.12",
+        ],
+    );
+    let id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["name"], "Ada Lovelace");
+    assert_eq!(created["status"], "active");
+
+    let fetched = client_json(&server, &["users", "get", &id]);
+    assert_eq!(fetched, created);
+
+    let updated = client_json(&server, &["users", "update", &id, "--age", "37"]);
+    assert_eq!(updated["age"], 37);
+    assert_eq!(updated["email"], "ada@example.com");
+
+    let listed = client_json(&server, &["users", "list"]);
+    assert!(
+        listed
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|user| user["id"] == id.as_str())
+    );
+
+    let nearby = client_json(
+        &server,
+        &[
+            "users",
+            "nearby",
+            "--lat",
+            "51.5",
+            "--lon",
+            "-0.12",
+            "--radius-km",
+            "1",
+        ],
+    );
+    assert!(
+        nearby
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|near| near["user"]["id"] == id.as_str())
+    );
+
+    let suspended = client_json(&server, &["users", "suspend", &id]);
+    assert_eq!(suspended["status"], "suspended");
+
+    assert!(client(&server, &["users", "delete", &id]).status.success());
+    let missing = client(&server, &["users", "get", &id]);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("404"));
+}
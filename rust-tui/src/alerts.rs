@@ -0,0 +1,74 @@
+use crate::config::AlertConfig;
+
+// The metrics that can have a threshold, in left-panel order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Cpu,
+    Memory,
+    Disk,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 3] = [Metric::Cpu, Metric::Memory, Metric::Disk];
+
+    // Index into App::items, which the left panel and the alert state share
+    pub fn item(self) -> usize {
+        match self {
+            Metric::Cpu => 0,
+            Metric::Memory => 1,
+            Metric::Disk => 2,
+        }
+    }
+}
+
+// Which metrics are over their threshold. An alert goes off above the threshold and
+// only clears once the value drops `hysteresis` points below it, so a reading hovering
+// around the line doesn't keep ringing.
+pub struct Alerts {
+    thresholds: [Option<f32>; 3],
+    hysteresis: f32,
+    bell: bool,
+    active: [bool; 3],
+}
+
+impl Alerts {
+    pub fn new(config: &AlertConfig) -> Self {
+        Alerts {
+            thresholds: [config.cpu, config.memory, config.disk],
+            hysteresis: config.hysteresis.max(0.0),
+            bell: config.bell,
+            active: [false; 3],
+        }
+    }
+
+    // Takes the latest percentage for a metric; true when its alert has just gone off
+    pub fn update(&mut self, metric: Metric, value: f32) -> bool {
+        let i = metric.item();
+        let Some(threshold) = self.thresholds[i] else {
+            return false;
+        };
+        let was_active = self.active[i];
+        if value > threshold {
+            self.active[i] = true;
+        } else if value <= threshold - self.hysteresis {
+            self.active[i] = false;
+        }
+        self.active[i] && !was_active
+    }
+
+    pub fn is_active(&self, item: usize) -> bool {
+        self.active.get(item).copied().unwrap_or(false)
+    }
+
+    pub fn any_active(&self) -> bool {
+        self.active.contains(&true)
+    }
+
+    pub fn active(&self) -> [bool; 3] {
+        self.active
+    }
+
+    pub fn bell(&self) -> bool {
+        self.bell
+    }
+}
@@ -0,0 +1,83 @@
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+use crate::theme::ThemeName;
+
+// Shortest and longest sampling intervals, in milliseconds. sysinfo needs about 200ms
+// between CPU refreshes for the usage it reports to mean anything.
+pub const MIN_INTERVAL_MS: u64 = 200;
+pub const MAX_INTERVAL_MS: u64 = 60_000;
+pub const DEFAULT_INTERVAL_MS: u64 = 500;
+pub const DEFAULT_HISTORY_LEN: u64 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RecordFormat {
+    /// One row per value: timestamp, resource, name, metric, value
+    Csv,
+    /// One JSON object per sample
+    Jsonl,
+}
+
+#[derive(Parser)]
+#[command(about = "Live view of CPU, memory, disk, network and processes")]
+pub struct Args {
+    /// UI language, e.g. pt; defaults to the config file, then the locale
+    #[arg(long, value_name = "CODE")]
+    pub lang: Option<String>,
+
+    /// Milliseconds between samples; + and - change it while running. Defaults to the config
+    /// file, then 500
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(MIN_INTERVAL_MS..=MAX_INTERVAL_MS))]
+    pub interval: Option<u64>,
+
+    /// Samples kept for each chart; charts narrower than this show the most recent ones
+    #[arg(long, value_name = "SAMPLES", default_value_t = DEFAULT_HISTORY_LEN, value_parser = clap::value_parser!(u64).range(10..=100_000))]
+    pub history_len: u64,
+
+    /// Color theme; t switches between them while running. Defaults to the config file, then dark
+    #[arg(long, value_enum)]
+    pub theme: Option<ThemeName>,
+
+    /// Alert when CPU usage goes over PCT percent
+    #[arg(long, value_name = "PCT")]
+    pub alert_cpu: Option<f32>,
+
+    /// Alert when memory usage goes over PCT percent
+    #[arg(long, value_name = "PCT")]
+    pub alert_memory: Option<f32>,
+
+    /// Alert when disk usage goes over PCT percent
+    #[arg(long, value_name = "PCT")]
+    pub alert_disk: Option<f32>,
+
+    /// Ring the terminal bell when an alert goes off
+    #[arg(long)]
+    pub bell: bool,
+
+    /// Append every sample to FILE, to look at the session later
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Format for --record; by default CSV for a .csv file, JSON Lines otherwise
+    #[arg(long, value_enum, requires = "record")]
+    pub record_format: Option<RecordFormat>,
+
+    /// Run as an agent without a terminal, serving metrics as JSON at http://ADDR/metrics,
+    /// e.g. 0.0.0.0:9100
+    #[arg(long, value_name = "ADDR", conflicts_with = "connect")]
+    pub serve: Option<String>,
+
+    /// Show the metrics of a --serve agent instead of this machine's, e.g.
+    /// http://server:9100
+    #[arg(long, value_name = "URL")]
+    pub connect: Option<String>,
+
+    /// Print one sample of CPU, memory, disk and network to stdout and exit, without the
+    /// full-screen view
+    #[arg(long, conflicts_with_all = ["serve", "record"])]
+    pub once: bool,
+
+    /// With --once, print the sample as JSON, in the shape --record writes it
+    #[arg(long, requires = "once")]
+    pub json: bool,
+}
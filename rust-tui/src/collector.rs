@@ -0,0 +1,703 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Components, Disks, Networks, System, ThreadKind};
+
+use crate::{
+    BatteryInfo, ChargeState, CpuInfo, DiskInfo, DiskIo, HostInfo, InterfaceDetail, ProcessInfo,
+    SensorInfo,
+};
+
+// Everything read from the system in one pass. Rates are per second over the time since
+// the previous sample; turning samples into histories is left to the UI thread.
+#[derive(Serialize, Deserialize)]
+pub struct Sample {
+    pub cpu_usage: f32,
+    pub cores: Vec<f32>,
+    pub cpu: CpuInfo,
+    pub mem_total: u64,
+    pub mem_used: u64,
+    pub mem_available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    pub disks: Vec<DiskInfo>,
+    pub networks: Vec<(String, u64, u64, String)>, // (name, rx_bps, tx_bps, kind)
+    pub interface_details: HashMap<String, InterfaceDetail>,
+    pub default_iface: Option<String>,
+    pub processes: Vec<ProcessInfo>,
+    pub sensors: Vec<SensorInfo>,
+    pub fans: Vec<(String, u64)>,
+    pub batteries: Vec<BatteryInfo>,
+    pub on_ac: Option<bool>,
+    pub host: HostInfo,
+}
+
+// Running totals for one block device since boot
+#[derive(Clone, Copy)]
+struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    writes: u64,
+    sectors_written: u64,
+}
+
+// A sample, or why one couldn't be taken, e.g. a --connect agent being unreachable
+pub type Update = Result<Sample, String>;
+
+// What the UI thread can change about sampling while it runs
+pub enum Command {
+    Interval(Duration),
+    Pause(bool),
+    // Whether to count each process's sockets, which is only worth it while they're shown
+    CountSockets(bool),
+}
+
+// Owns the sysinfo handles and the counters rates are worked out from, on a thread of its
+// own so a slow refresh (a hung network mount, thousands of processes) doesn't hold up
+// drawing or input
+pub struct Collector {
+    sys: System,
+    disks: Disks,
+    networks: Networks,
+    components: Components,
+    diskstats_prev: HashMap<String, DiskCounters>, // by block device
+    diskstats_at: Option<Instant>,
+    networks_at: Instant,          // when the network counters were last read
+    processes_at: Option<Instant>, // when per-process I/O was last read
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Collector {
+            sys: System::new_all(),
+            disks: Disks::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
+            diskstats_prev: HashMap::new(),
+            diskstats_at: None,
+            networks_at: Instant::now(),
+            processes_at: None,
+        }
+    }
+
+    pub fn spawn(mut self, interval: Duration) -> (Sender<Command>, Receiver<Update>) {
+        spawn_sampling(interval, move |with_sockets| Ok(self.collect(with_sockets)))
+    }
+
+    fn collect(&mut self, with_sockets: bool) -> Sample {
+        let networks = self.collect_networks();
+        let interface_details = self.interface_details();
+        let sys = &mut self.sys;
+        sys.refresh_cpu();
+        sys.refresh_memory();
+
+        // Linux lists each thread of a process as well, and those shouldn't be killed on
+        // their own. Disk rates come from the bytes sysinfo counts since each process's
+        // last refresh.
+        sys.refresh_processes();
+        let now = Instant::now();
+        let secs = self
+            .processes_at
+            .map(|t| now.duration_since(t).as_secs_f64())
+            .filter(|s| *s > 0.0);
+        self.processes_at = Some(now);
+        let rate = |bytes: u64| secs.map_or(0, |secs| (bytes as f64 / secs) as u64);
+        let sockets = if with_sockets { socket_inodes() } else { None };
+        let processes = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind() != Some(ThreadKind::Userland))
+            .map(|p| ProcessInfo {
+                pid: p.pid().as_u32(),
+                name: p.name().to_string(),
+                start_time: p.start_time(),
+                cpu: p.cpu_usage(),
+                memory: p.memory(),
+                disk_read: rate(p.disk_usage().read_bytes),
+                disk_write: rate(p.disk_usage().written_bytes),
+                sockets: sockets
+                    .as_ref()
+                    .map(|inodes| count_sockets(p.pid().as_u32(), inodes)),
+            })
+            .collect();
+
+        // Temperatures; sensors that can't be read report NaN and are left out
+        self.components.refresh();
+        let mut sensors: Vec<SensorInfo> = self
+            .components
+            .list()
+            .iter()
+            .filter(|c| !c.temperature().is_nan())
+            .map(|c| SensorInfo {
+                label: c.label().to_string(),
+                temperature: c.temperature(),
+                max: c.max(),
+                critical: c.critical(),
+            })
+            .collect();
+        sensors.sort_by(|a, b| a.label.cmp(&b.label));
+        let (batteries, on_ac) = read_power_supplies();
+
+        Sample {
+            cpu_usage: sys.global_cpu_info().cpu_usage(),
+            cores: sys.cpus().iter().map(|c| c.cpu_usage()).collect(),
+            cpu: read_cpu(sys),
+            mem_total: sys.total_memory(),
+            mem_used: sys.used_memory(),
+            mem_available: sys.available_memory(),
+            swap_total: sys.total_swap(),
+            swap_used: sys.used_swap(),
+            disks: self.collect_disks(),
+            networks,
+            interface_details,
+            default_iface: find_default_interface(),
+            processes,
+            sensors,
+            fans: read_fans(),
+            batteries,
+            on_ac,
+            host: read_host(),
+        }
+    }
+
+    // Every mount, hidden ones included; I/O rates come from the change in the kernel's counters
+    // since the last refresh
+    fn collect_disks(&mut self) -> Vec<DiskInfo> {
+        self.disks.refresh();
+        let diskstats = read_diskstats();
+        let now = Instant::now();
+        let elapsed = self
+            .diskstats_at
+            .map(|t| now.duration_since(t).as_secs_f64());
+        let disks = self
+            .disks
+            .list()
+            .iter()
+            .map(|d| {
+                let device = d.name().to_string_lossy().to_string();
+                let io = block_device_name(&device).and_then(|name| {
+                    let now = diskstats.get(&name)?;
+                    let prev = self.diskstats_prev.get(&name)?;
+                    let secs = elapsed.filter(|s| *s > 0.0)?;
+                    let rate =
+                        |now: u64, prev: u64| (now.saturating_sub(prev) as f64 / secs) as u64;
+                    // Sectors in /proc/diskstats are always 512 bytes
+                    Some(DiskIo {
+                        read_bps: rate(now.sectors_read, prev.sectors_read) * 512,
+                        write_bps: rate(now.sectors_written, prev.sectors_written) * 512,
+                        read_iops: rate(now.reads, prev.reads),
+                        write_iops: rate(now.writes, prev.writes),
+                    })
+                });
+                DiskInfo {
+                    mount: d.mount_point().to_string_lossy().to_string(),
+                    device,
+                    fs_type: d.file_system().to_string_lossy().to_string(),
+                    total: d.total_space(),
+                    available: d.available_space(),
+                    io,
+                }
+            })
+            .collect();
+        self.diskstats_prev = diskstats;
+        self.diskstats_at = Some(now);
+        disks
+    }
+
+    // Interfaces other than loopback, hidden ones included, with speeds in bytes/sec
+    fn collect_networks(&mut self) -> Vec<(String, u64, u64, String)> {
+        self.networks.refresh();
+        let now = Instant::now();
+        let secs = now.duration_since(self.networks_at).as_secs_f64();
+        self.networks_at = now;
+        let per_sec = |bytes: u64| {
+            if secs > 0.0 {
+                (bytes as f64 / secs) as u64
+            } else {
+                0
+            }
+        };
+
+        let mut networks = Vec::new();
+        for (name, net) in self.networks.list() {
+            // Skip loopback interface
+            if name == "lo" {
+                continue;
+            }
+            // net.received()/transmitted() give bytes since the last refresh
+            networks.push((
+                name.clone(),
+                per_sec(net.received()),
+                per_sec(net.transmitted()),
+                interface_kind(name),
+            ));
+        }
+        networks
+    }
+
+    // What the interface detail shows for each interface, as of the last network refresh
+    fn interface_details(&self) -> HashMap<String, InterfaceDetail> {
+        let mut addresses = interface_addresses();
+        let mut details = HashMap::new();
+        for (name, net) in self.networks.list() {
+            if name == "lo" {
+                continue;
+            }
+            let sys_class =
+                |file: &str| read_number(&Path::new("/sys/class/net").join(name).join(file));
+            details.insert(
+                name.clone(),
+                InterfaceDetail {
+                    addresses: addresses.remove(name).unwrap_or_default(),
+                    mac: net.mac_address().to_string(),
+                    mtu: sys_class("mtu").map(|mtu| mtu as u64),
+                    // Virtual links and links that are down report -1
+                    speed: sys_class("speed")
+                        .filter(|speed| *speed > 0.0)
+                        .map(|speed| speed as u64),
+                    total_received: net.total_received(),
+                    total_transmitted: net.total_transmitted(),
+                },
+            );
+        }
+        details
+    }
+}
+
+// sysinfo reads each core's current frequency from cpufreq where there is one, and from
+// /proc/cpuinfo otherwise, where it's often the nominal speed
+fn read_cpu(sys: &System) -> CpuInfo {
+    CpuInfo {
+        model: sys
+            .cpus()
+            .first()
+            .map_or(String::new(), |c| c.brand().trim().to_string()),
+        physical_cores: sys.physical_core_count(),
+        frequencies: sys.cpus().iter().map(|c| c.frequency()).collect(),
+        frequency_range: read_frequency_range(sys.cpus().len()),
+    }
+}
+
+// The lowest and highest clock any core can run at, in MHz; cpufreq reports kHz
+fn read_frequency_range(cores: usize) -> Option<(u64, u64)> {
+    let limits: Vec<(f64, f64)> = (0..cores)
+        .filter_map(|i| {
+            let dir = Path::new("/sys/devices/system/cpu")
+                .join(format!("cpu{}", i))
+                .join("cpufreq");
+            Some((
+                read_number(&dir.join("cpuinfo_min_freq"))?,
+                read_number(&dir.join("cpuinfo_max_freq"))?,
+            ))
+        })
+        .collect();
+    let min = limits.iter().map(|(min, _)| *min).reduce(f64::min)?;
+    let max = limits.iter().map(|(_, max)| *max).reduce(f64::max)?;
+    Some(((min / 1000.0) as u64, (max / 1000.0) as u64))
+}
+
+fn read_host() -> HostInfo {
+    let load = System::load_average();
+    HostInfo {
+        name: System::host_name(),
+        os: System::long_os_version(),
+        kernel: System::kernel_version(),
+        uptime: System::uptime(),
+        load_average: [load.one, load.five, load.fifteen],
+    }
+}
+
+// Calls `sample` straight away, then once per interval, on a thread of its own until the
+// UI thread hangs up. `sample` is told whether to count sockets.
+pub fn spawn_sampling<F>(interval: Duration, mut sample: F) -> (Sender<Command>, Receiver<Update>)
+where
+    F: FnMut(bool) -> Update + Send + 'static,
+{
+    let (command_tx, commands) = mpsc::channel();
+    let (updates, update_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut interval = interval;
+        let mut paused = false;
+        let mut with_sockets = false;
+        let mut last_sample = Instant::now();
+        let mut next_sample = last_sample;
+        loop {
+            let now = Instant::now();
+            if !paused && now >= next_sample {
+                if updates.send(sample(with_sockets)).is_err() {
+                    return;
+                }
+                last_sample = now;
+                next_sample = now + interval;
+            }
+            let command = if paused {
+                commands.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                commands.recv_timeout(next_sample.saturating_duration_since(Instant::now()))
+            };
+            match command {
+                Ok(Command::Interval(new)) => {
+                    interval = new;
+                    next_sample = last_sample + interval;
+                }
+                // Resuming samples at once; rates and CPU usage then cover the whole pause
+                Ok(Command::Pause(pause)) => {
+                    paused = pause;
+                    next_sample = Instant::now();
+                }
+                Ok(Command::CountSockets(count)) => with_sockets = count,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+    (command_tx, update_rx)
+}
+
+// Attempt to detect default interface (the one used for the default route)
+fn find_default_interface() -> Option<String> {
+    if let Ok(content) = fs::read_to_string("/proc/net/route") {
+        for line in content.lines().skip(1) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 {
+                let iface = parts[0];
+                let dest = parts[1];
+                if dest == "00000000" {
+                    return Some(iface.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+// IPv4 and IPv6 addresses with their prefix lengths, by interface. /proc has no list of
+// IPv4 addresses per interface: the local ones come from the routing trie, and each goes
+// to the interface whose directly connected network holds it.
+fn interface_addresses() -> HashMap<String, Vec<String>> {
+    let mut addresses: HashMap<String, Vec<String>> = HashMap::new();
+
+    // (interface, network, mask) for the routes without a gateway
+    let mut networks: Vec<(String, u32, u32)> = Vec::new();
+    let hex = |field: &str| {
+        u32::from_str_radix(field, 16)
+            .ok()
+            .map(|n| u32::from(Ipv4Addr::from(n.to_le_bytes())))
+    };
+    if let Ok(content) = fs::read_to_string("/proc/net/route") {
+        for line in content.lines().skip(1) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 8 {
+                continue;
+            }
+            let flags = u32::from_str_radix(parts[3], 16).unwrap_or(0);
+            if let (Some(network), Some(mask)) = (hex(parts[1]), hex(parts[7])) {
+                // RTF_GATEWAY
+                if flags & 0x2 == 0 && mask != 0 {
+                    networks.push((parts[0].to_string(), network, mask));
+                }
+            }
+        }
+    }
+    let mut local: Vec<Ipv4Addr> = Vec::new();
+    if let Ok(content) = fs::read_to_string("/proc/net/fib_trie") {
+        let mut last: Option<Ipv4Addr> = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(address) = line.strip_prefix("|-- ") {
+                last = address.parse().ok();
+            } else if line == "/32 host LOCAL" {
+                if let Some(address) = last.filter(|a| !local.contains(a)) {
+                    local.push(address);
+                }
+            }
+        }
+    }
+    for address in local {
+        let bits = u32::from(address);
+        let network = networks
+            .iter()
+            .filter(|(_, network, mask)| bits & mask == *network)
+            .max_by_key(|(_, _, mask)| mask.count_ones());
+        if let Some((name, _, mask)) = network {
+            addresses.entry(name.clone()).or_default().push(format!(
+                "{}/{}",
+                address,
+                mask.count_ones()
+            ));
+        }
+    }
+
+    // address, interface index, prefix length, scope, flags, name
+    if let Ok(content) = fs::read_to_string("/proc/net/if_inet6") {
+        for line in content.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 6 {
+                continue;
+            }
+            if let (Ok(address), Ok(prefix)) = (
+                u128::from_str_radix(parts[0], 16),
+                u8::from_str_radix(parts[2], 16),
+            ) {
+                addresses
+                    .entry(parts[5].to_string())
+                    .or_default()
+                    .push(format!("{}/{}", Ipv6Addr::from(address), prefix));
+            }
+        }
+    }
+    addresses
+}
+
+// Wi-Fi, Ethernet or Unknown
+fn interface_kind(name: &str) -> String {
+    // Prefer checking sysfs on Linux to detect wireless interfaces reliably
+    #[cfg(target_os = "linux")]
+    {
+        let wireless_path = format!("/sys/class/net/{}/wireless", name);
+        if Path::new(&wireless_path).exists() {
+            "Wi-Fi".to_string()
+        } else {
+            // If the device directory exists it's likely a physical interface (Ethernet)
+            let device_path = format!("/sys/class/net/{}/device", name);
+            if Path::new(&device_path).exists() {
+                "Ethernet".to_string()
+            } else {
+                // Fallback to name heuristics
+                if name.contains("wl")
+                    || name.to_lowercase().contains("wifi")
+                    || name.to_lowercase().contains("wlan")
+                {
+                    "Wi-Fi".to_string()
+                } else if name.contains("en")
+                    || name.to_lowercase().contains("eth")
+                    || name.to_lowercase().contains("enp")
+                {
+                    "Ethernet".to_string()
+                } else {
+                    "Unknown".to_string()
+                }
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        // Non-Linux fallback heuristics
+        if name.contains("wl")
+            || name.to_lowercase().contains("wifi")
+            || name.to_lowercase().contains("wlan")
+        {
+            "Wi-Fi".to_string()
+        } else if name.contains("en")
+            || name.to_lowercase().contains("eth")
+            || name.to_lowercase().contains("enp")
+        {
+            "Ethernet".to_string()
+        } else {
+            "Unknown".to_string()
+        }
+    }
+}
+
+// Completed reads and writes, and the sectors they moved, per block device, from
+// /proc/diskstats
+fn read_diskstats() -> HashMap<String, DiskCounters> {
+    let mut stats = HashMap::new();
+    if let Ok(content) = fs::read_to_string("/proc/diskstats") {
+        for line in content.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 10 {
+                let field = |i: usize| parts[i].parse::<u64>();
+                if let (Ok(reads), Ok(sectors_read), Ok(writes), Ok(sectors_written)) =
+                    (field(3), field(5), field(7), field(9))
+                {
+                    stats.insert(
+                        parts[2].to_string(),
+                        DiskCounters {
+                            reads,
+                            sectors_read,
+                            writes,
+                            sectors_written,
+                        },
+                    );
+                }
+            }
+        }
+    }
+    stats
+}
+
+// Fan speeds from the hwmon drivers in /sys, labelled as the driver labels them
+fn read_fans() -> Vec<(String, u64)> {
+    let mut fans = Vec::new();
+    let Ok(entries) = fs::read_dir("/sys/class/hwmon") else {
+        return fans;
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let chip = fs::read_to_string(dir.join("name")).unwrap_or_default();
+        let Ok(files) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let name = file.file_name().to_string_lossy().to_string();
+            let Some(fan) = name.strip_suffix("_input").filter(|n| n.starts_with("fan")) else {
+                continue;
+            };
+            let Some(rpm) = fs::read_to_string(file.path())
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+            else {
+                continue;
+            };
+            let label = fs::read_to_string(dir.join(format!("{}_label", fan)))
+                .map(|l| l.trim().to_string())
+                .unwrap_or_else(|_| format!("{} {}", chip.trim(), fan));
+            fans.push((label, rpm));
+        }
+    }
+    fans.sort();
+    fans
+}
+
+fn read_number(path: &Path) -> Option<f64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// Batteries and whether the AC adapter is plugged in, from /sys/class/power_supply.
+// Drivers report energy in µWh and power in µW, or charge in µAh and current in µA.
+fn read_power_supplies() -> (Vec<BatteryInfo>, Option<bool>) {
+    let mut batteries = Vec::new();
+    let mut on_ac = None;
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return (batteries, on_ac);
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let kind = fs::read_to_string(dir.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Mains" => {
+                let online = read_number(&dir.join("online")).is_some_and(|v| v > 0.0);
+                on_ac = Some(on_ac.unwrap_or(false) || online);
+            }
+            "Battery" => {
+                let Some(percent) = read_number(&dir.join("capacity")) else {
+                    continue;
+                };
+                let state = match fs::read_to_string(dir.join("status"))
+                    .unwrap_or_default()
+                    .trim()
+                {
+                    "Charging" => ChargeState::Charging,
+                    "Discharging" => ChargeState::Discharging,
+                    "Full" => ChargeState::Full,
+                    "Not charging" => ChargeState::NotCharging,
+                    _ => ChargeState::Unknown,
+                };
+                // Some drivers report a negative flow while discharging
+                let read_flow = |file: &str| {
+                    read_number(&dir.join(file))
+                        .map(f64::abs)
+                        .filter(|f| *f > 0.0)
+                };
+                let (now, full, flow, watts) = match read_number(&dir.join("energy_now")) {
+                    Some(energy) => {
+                        let power = read_flow("power_now");
+                        (
+                            Some(energy),
+                            read_number(&dir.join("energy_full")),
+                            power,
+                            power.map(|p| p / 1e6),
+                        )
+                    }
+                    None => {
+                        let current = read_flow("current_now");
+                        let voltage = read_number(&dir.join("voltage_now"));
+                        let watts = current
+                            .zip(voltage)
+                            .map(|(current, voltage)| current * voltage / 1e12);
+                        (
+                            read_number(&dir.join("charge_now")),
+                            read_number(&dir.join("charge_full")),
+                            current,
+                            watts,
+                        )
+                    }
+                };
+                let remaining = match state {
+                    ChargeState::Discharging => now,
+                    ChargeState::Charging => full.zip(now).map(|(full, now)| (full - now).max(0.0)),
+                    _ => None,
+                };
+                let time_left = remaining
+                    .zip(flow)
+                    .map(|(left, flow)| Duration::from_secs_f64(left / flow * 3600.0));
+                batteries.push(BatteryInfo {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    percent: percent as f32,
+                    state,
+                    rate: watts.map(|w| w as f32),
+                    time_left,
+                });
+            }
+            _ => {}
+        }
+    }
+    batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    (batteries, on_ac)
+}
+
+// Inodes of every TCP and UDP socket, from /proc/net; None where there is no /proc
+fn socket_inodes() -> Option<HashSet<u64>> {
+    let mut inodes = HashSet::new();
+    let mut found = false;
+    for table in ["tcp", "tcp6", "udp", "udp6"] {
+        let Ok(contents) = fs::read_to_string(format!("/proc/net/{}", table)) else {
+            continue;
+        };
+        found = true;
+        // After a header line; the inode is the tenth column
+        for line in contents.lines().skip(1) {
+            if let Some(inode) = line.split_whitespace().nth(9).and_then(|i| i.parse().ok()) {
+                inodes.insert(inode);
+            }
+        }
+    }
+    found.then_some(inodes)
+}
+
+// How many of a process's file descriptors are network sockets. Another user's
+// processes can't be looked into without root, and count none.
+fn count_sockets(pid: u32, inodes: &HashSet<u64>) -> usize {
+    let Ok(fds) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return 0;
+    };
+    fds.flatten()
+        .filter_map(|fd| fs::read_link(fd.path()).ok())
+        .filter_map(|target| {
+            let target = target.to_string_lossy().to_string();
+            target
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse::<u64>()
+                .ok()
+        })
+        .filter(|inode| inodes.contains(inode))
+        .count()
+}
+
+// The /proc/diskstats name for a disk's device, e.g. /dev/mapper/root -> dm-0
+fn block_device_name(device: &str) -> Option<String> {
+    if !device.starts_with("/dev/") {
+        return None;
+    }
+    let resolved = fs::canonicalize(device).unwrap_or_else(|_| device.into());
+    resolved
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
@@ -0,0 +1,219 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use toml_edit::DocumentMut;
+
+use crate::theme::ThemeName;
+
+// Read from $XDG_CONFIG_HOME/rust-tui/config.toml (or ~/.config/rust-tui/config.toml)
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Locale code such as "pt"; --lang overrides it
+    pub lang: Option<String>,
+    // One of dark, light, solarized or monochrome; --theme overrides it
+    pub theme: Option<ThemeName>,
+    // The resource selected at startup, e.g. "processes"
+    pub view: Option<View>,
+    // Milliseconds between samples; --interval overrides it. Kept within the range
+    // --interval allows.
+    pub interval: Option<u64>,
+    pub disks: DiskConfig,
+    pub network: NetworkConfig,
+    pub sensors: SensorConfig,
+    pub alerts: AlertConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    // Interface shown in the summary line and listed first, whatever the default route is
+    pub pinned: Option<String>,
+    // Preferred order for the rest; unlisted interfaces follow, default route first
+    pub order: Vec<String>,
+    // Interfaces left out everywhere, e.g. ["docker0", "veth*"]; h adds the selected one
+    pub hidden: Vec<String>,
+    // How the throughput chart's y axis is scaled; L switches between them
+    pub scale: ChartScale,
+    // Top of the throughput chart in bytes/sec; unset, it follows the peak of the history
+    pub max: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartScale {
+    #[default]
+    Linear,
+    // Each step up is ten times the rate, so a trickle and a burst both show
+    Log,
+}
+
+impl ChartScale {
+    pub fn toggle(self) -> Self {
+        match self {
+            ChartScale::Linear => ChartScale::Log,
+            ChartScale::Log => ChartScale::Linear,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    // Mount points left out of the list and the totals, e.g. ["/boot/efi", "/snap/*"]; h
+    // adds the selected one
+    pub hidden: Vec<String>,
+}
+
+// In left-panel order
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum View {
+    Cpu,
+    Memory,
+    Disk,
+    Network,
+    Processes,
+    Sensors,
+    Power,
+}
+
+impl View {
+    // Index into App::items
+    pub fn item(self) -> usize {
+        self as usize
+    }
+}
+
+// Whether a name is in a hidden list: names match exactly, and one ending in * matches
+// everything that starts with the rest
+pub fn is_hidden(hidden: &[String], name: &str) -> bool {
+    hidden
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        })
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SensorConfig {
+    // Temperatures at or above this many °C are shown in red
+    pub warning: f32,
+    // Thresholds for particular sensors, by part of their label, e.g. nvme = 70.0.
+    // Matched case-insensitively; the longest match wins.
+    pub thresholds: HashMap<String, f32>,
+}
+
+impl Default for SensorConfig {
+    fn default() -> Self {
+        SensorConfig {
+            warning: 80.0,
+            thresholds: HashMap::new(),
+        }
+    }
+}
+
+impl SensorConfig {
+    pub fn warning_for(&self, label: &str) -> f32 {
+        let label = label.to_lowercase();
+        self.thresholds
+            .iter()
+            .filter(|(part, _)| label.contains(&part.to_lowercase()))
+            .max_by_key(|(part, _)| part.len())
+            .map_or(self.warning, |(_, threshold)| *threshold)
+    }
+}
+
+// Percentages that raise an alert; a metric without one never does. The --alert-*
+// flags override these.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    pub cpu: Option<f32>,
+    pub memory: Option<f32>,
+    pub disk: Option<f32>,
+    // How far below its threshold a metric must fall for the alert to clear
+    pub hysteresis: f32,
+    // Ring the terminal bell when an alert goes off
+    pub bell: bool,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        AlertConfig {
+            cpu: None,
+            memory: None,
+            disk: None,
+            hysteresis: 5.0,
+            bell: false,
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("rust-tui").join("config.toml"))
+}
+
+// Adds a name to the hidden list of a section ("disks" or "network") in the config file,
+// creating the file if there isn't one. The rest of the file, comments and layout included,
+// is left as it was.
+pub fn save_hidden(section: &str, name: &str) -> Result<(), String> {
+    let path = config_path().ok_or("no home directory")?;
+    let mut doc = match fs::read_to_string(&path) {
+        Ok(content) => content
+            .parse::<DocumentMut>()
+            .map_err(|e| format!("{}: {}", path.display(), e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => DocumentMut::new(),
+        // Anything else, e.g. no permission to read it, and writing would lose what's there
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let section = doc
+        .entry(section)
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| format!("[{}] in {} isn't a table", section, path.display()))?;
+    let hidden = section
+        .entry("hidden")
+        .or_insert(toml_edit::value(toml_edit::Array::new()))
+        .as_array_mut()
+        .ok_or_else(|| format!("hidden in {} isn't a list", path.display()))?;
+    if !hidden.iter().any(|v| v.as_str() == Some(name)) {
+        hidden.push(name);
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    // Renamed into place, so a crash halfway through leaves the old file rather than half of it
+    let temp = path.with_extension("toml.tmp");
+    fs::write(&temp, doc.to_string())
+        .and_then(|()| fs::rename(&temp, &path))
+        .map_err(|e| {
+            fs::remove_file(&temp).ok();
+            format!("{}: {}", path.display(), e)
+        })
+}
+
+impl Config {
+    // A missing file means defaults; a broken one is reported but doesn't stop the app
+    pub fn load() -> Self {
+        let Some(path) = config_path() else {
+            return Config::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("Ignoring {}: {}", path.display(), e);
+                Config::default()
+            }),
+            Err(_) => Config::default(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::hash::{Hash, Hasher};
+
+// The most recent samples of one series, up to a fixed capacity. Pushing over capacity
+// overwrites the oldest sample. Every value is stored twice, capacity apart, so the samples
+// are always one contiguous slice that can be handed to a sparkline as is.
+#[derive(Clone)]
+pub struct History {
+    buf: Vec<u64>,
+    capacity: usize,
+    next: usize, // where the next sample goes; the oldest one once full
+    len: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        History {
+            buf: vec![0; capacity * 2],
+            capacity,
+            next: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: u64) {
+        self.buf[self.next] = value;
+        self.buf[self.next + self.capacity] = value;
+        self.next = (self.next + 1) % self.capacity;
+        self.len = (self.len + 1).min(self.capacity);
+    }
+
+    // Oldest first
+    pub fn as_slice(&self) -> &[u64] {
+        let start = if self.len == self.capacity {
+            self.next
+        } else {
+            0
+        };
+        &self.buf[start..start + self.len]
+    }
+
+    // The `n` most recent samples, for a chart narrower than the history
+    pub fn latest(&self, n: usize) -> &[u64] {
+        let all = self.as_slice();
+        &all[all.len().saturating_sub(n)..]
+    }
+
+    pub fn last(&self) -> Option<u64> {
+        self.as_slice().last().copied()
+    }
+}
+
+// Only the samples count, not where in the buffer they happen to sit
+impl Hash for History {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
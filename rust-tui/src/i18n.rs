@@ -0,0 +1,444 @@
+// UI strings per locale. Labels are joined with their values by the renderer,
+// e.g. "{label}: {value}", so entries here are plain words or whole titles.
+pub struct Strings {
+    pub computer_resources: &'static str,
+    pub resource_graphs: &'static str,
+    pub no_resource_selected: &'static str,
+    pub cpu: &'static str,
+    pub memory: &'static str,
+    pub disk: &'static str,
+    pub network: &'static str,
+    pub no_network: &'static str,
+    pub total: &'static str,
+    pub used: &'static str,
+    pub avail: &'static str,
+    pub swap_total: &'static str,
+    pub swap_used: &'static str,
+    pub cpu_history: &'static str,
+    pub cpu_usage: &'static str,
+    pub per_core_usage: &'static str,
+    pub processor: &'static str,
+    // Follow the core counts, e.g. "4 cores, 8 threads"
+    pub cores: &'static str,
+    pub threads: &'static str,
+    // Followed by the slowest, average and fastest core, then the range the hardware allows
+    pub frequency: &'static str,
+    pub frequency_summary: &'static str,
+    pub hardware: &'static str,
+    pub core: &'static str,
+    pub memory_history: &'static str,
+    pub memory_usage: &'static str,
+    pub breakdown: &'static str,
+    pub disk_history: &'static str,
+    pub disk_usage: &'static str,
+    pub disk_mounts: &'static str,
+    pub disk_mounts_focused: &'static str,
+    pub no_mounts: &'static str,
+    pub mount_detail: &'static str,
+    // Follows the mount point, e.g. "/home % (history)"
+    pub mount_history: &'static str,
+    pub device: &'static str,
+    pub filesystem: &'static str,
+    pub read: &'static str,
+    pub write: &'static str,
+    pub io_unavailable: &'static str,
+    // Follows the operations per second, e.g. "Read: 1.2 MiB/s, 30 IOPS"
+    pub iops: &'static str,
+    pub network_interfaces: &'static str,
+    pub interfaces: &'static str,
+    pub interfaces_focused: &'static str,
+    // Follows the interface name, e.g. "eth0 detail (z: reset session totals, ← back)"
+    pub interface_detail: &'static str,
+    pub mac: &'static str,
+    pub mtu: &'static str,
+    pub link_speed: &'static str,
+    pub addresses: &'static str,
+    pub no_addresses: &'static str,
+    pub total_received: &'static str,
+    pub total_sent: &'static str,
+    pub session_received: &'static str,
+    pub session_sent: &'static str,
+    pub pinned: &'static str,
+    // Follows the interface name, e.g. "eth0 throughput (history)"
+    pub throughput_history: &'static str,
+    // Follow the throughput title, e.g. "eth0 throughput (history), log scale, peak 1.2 MiB/s"
+    pub log_scale: &'static str,
+    pub peak: &'static str,
+    pub received: &'static str,
+    pub sent: &'static str,
+    pub copy_hint: &'static str,
+    pub theme: &'static str,
+    pub help_hint: &'static str,
+    pub paused: &'static str,
+    pub help: &'static str,
+    // (keys, what they do), one row each in the help overlay
+    pub help_keys: &'static [(&'static str, &'static str)],
+    // Follows the copied text, e.g. "Copied: CPU: 12%"
+    pub copied: &'static str,
+    pub clipboard_unavailable: &'static str,
+    // Followed by the sampling interval, e.g. "Refresh: 500 ms (+/-)"
+    pub refresh: &'static str,
+    // Followed by the --record file, or by why recording stopped
+    pub recording: &'static str,
+    pub recording_stopped: &'static str,
+    // Follows the mount or interface h hid, or why it couldn't be saved to the config file,
+    // e.g. "Hidden: docker0"
+    pub hidden: &'static str,
+    pub hidden_not_saved: &'static str,
+    // In the header bar, e.g. "Linux (kernel 6.8.0)", "up 3d 4h 05m", "load 0.52 0.31 0.20"
+    pub kernel: &'static str,
+    pub uptime: &'static str,
+    pub load_average: &'static str,
+    // Followed by the --connect agent, e.g. "Remote: server:9100"
+    pub remote: &'static str,
+    // Precedes why the agent can't be reached, e.g. "Unreachable: connection refused"
+    pub remote_unreachable: &'static str,
+    pub remote_signal: &'static str,
+    pub processes: &'static str,
+    pub process_table: &'static str,
+    pub process_table_focused: &'static str,
+    pub no_processes: &'static str,
+    pub pid: &'static str,
+    pub name: &'static str,
+    pub sockets: &'static str,
+    // Precedes the signal and process, e.g. "Send SIGTERM → 1234 (firefox)?"
+    pub send_signal: &'static str,
+    pub confirm: &'static str,
+    pub confirm_hint: &'static str,
+    // Precedes what was sent, e.g. "Sent: SIGTERM → 1234 (firefox)"
+    pub signal_sent: &'static str,
+    pub process_gone: &'static str,
+    pub process_replaced: &'static str,
+    pub signal_refused: &'static str,
+    pub signal_unsupported: &'static str,
+    pub sensors: &'static str,
+    pub temperatures: &'static str,
+    pub fans: &'static str,
+    pub no_sensors: &'static str,
+    pub max: &'static str,
+    pub critical: &'static str,
+    pub power: &'static str,
+    pub batteries: &'static str,
+    pub no_battery: &'static str,
+    // Follows the battery name, e.g. "BAT0 % (history)"
+    pub battery_history: &'static str,
+    pub charging: &'static str,
+    pub discharging: &'static str,
+    pub full: &'static str,
+    pub not_charging: &'static str,
+    pub unknown: &'static str,
+    pub ac_adapter: &'static str,
+    pub connected: &'static str,
+    pub disconnected: &'static str,
+    // Follow the time remaining, e.g. "2h 15m left", "40m to full"
+    pub until_empty: &'static str,
+    pub until_full: &'static str,
+}
+
+pub static EN: Strings = Strings {
+    computer_resources: " Computer Resources ",
+    resource_graphs: " Resource Graphs ",
+    no_resource_selected: "No resource selected",
+    cpu: "CPU",
+    memory: "Memory",
+    disk: "Disk",
+    network: "Network",
+    no_network: "No network",
+    total: "Total",
+    used: "Used",
+    avail: "Avail",
+    swap_total: "Swap Total",
+    swap_used: "Swap Used",
+    cpu_history: "CPU % (history)",
+    cpu_usage: "CPU Usage",
+    per_core_usage: "Per-Core Usage",
+    processor: "Processor",
+    cores: "cores",
+    threads: "threads",
+    frequency: "Frequency",
+    frequency_summary: "min / avg / max",
+    hardware: "hardware",
+    core: "Core",
+    memory_history: "Memory % (history)",
+    memory_usage: "Memory Usage",
+    breakdown: "Breakdown",
+    disk_history: "Disk % (history)",
+    disk_usage: "Disk Usage",
+    disk_mounts: "Disk Mounts (→ to select)",
+    disk_mounts_focused: "Disk Mounts (↑/↓ select, h hide, ← back)",
+    no_mounts: "No mounts",
+    mount_detail: "Mount Detail",
+    mount_history: "% (history)",
+    device: "Device",
+    filesystem: "Filesystem",
+    read: "Read",
+    write: "Write",
+    io_unavailable: "I/O: n/a",
+    iops: "IOPS",
+    network_interfaces: "Network Interfaces",
+    interfaces: "Interfaces (p: pin next)",
+    interfaces_focused: "Interfaces (↑/↓ select, Enter detail, h hide, ← back)",
+    interface_detail: "detail (z: reset session totals, ← back)",
+    mac: "MAC",
+    mtu: "MTU",
+    link_speed: "Link speed",
+    addresses: "Addresses",
+    no_addresses: "none",
+    total_received: "Received since boot",
+    total_sent: "Sent since boot",
+    session_received: "Received this session",
+    session_sent: "Sent this session",
+    pinned: "[pinned]",
+    throughput_history: "throughput (history)",
+    log_scale: "log scale",
+    peak: "peak",
+    received: "Received",
+    sent: "Sent",
+    copy_hint: "y: copy selection",
+    theme: "Theme",
+    help_hint: "?: help",
+    paused: "PAUSED (space to resume)",
+    help: "Help",
+    help_keys: &[
+        ("↑/↓  j/k", "Select a resource, mount or process"),
+        (
+            "→  Enter  l",
+            "Open the mounts, interfaces, processes or an interface",
+        ),
+        ("←  Esc  h", "Back to the resource list"),
+        (
+            "h",
+            "In the mounts or interfaces, hide the selected one for good",
+        ),
+        ("PgUp/PgDn  Home/End", "Page through processes"),
+        ("s  r", "Sort processes, reverse the order"),
+        ("x  X", "Send SIGTERM, SIGKILL to the selected process"),
+        ("p", "Pin the next network interface"),
+        ("z", "Reset the interfaces' session totals"),
+        ("+  -", "Sample less, more often"),
+        ("Space", "Pause or resume sampling"),
+        ("d", "Dashboard: CPU, memory, disk and network at once"),
+        ("t", "Next color theme"),
+        ("L", "Linear or log scale for network throughput"),
+        ("y", "Copy the selection"),
+        ("Mouse", "Click to select, wheel to scroll"),
+        ("?", "Show or hide this help"),
+        ("q  Esc", "Quit"),
+    ],
+    copied: "Copied",
+    clipboard_unavailable: "Clipboard unavailable",
+    refresh: "Refresh",
+    recording: "Recording to",
+    recording_stopped: "Recording stopped",
+    hidden: "Hidden",
+    hidden_not_saved: "Hidden until exit, not saved",
+    kernel: "kernel",
+    uptime: "up",
+    load_average: "load",
+    remote: "Remote",
+    remote_unreachable: "Unreachable",
+    remote_signal: "Processes on a remote machine can't be signalled",
+    processes: "Processes",
+    process_table: "Processes (→ to select)",
+    process_table_focused:
+        "Processes (↑/↓ select, s: sort, r: reverse, x: SIGTERM, X: SIGKILL, ← back)",
+    no_processes: "No processes",
+    pid: "PID",
+    name: "Name",
+    sockets: "Sockets",
+    send_signal: "Send",
+    confirm: "Confirm",
+    confirm_hint: "y: yes, any other key: cancel",
+    signal_sent: "Sent",
+    process_gone: "The process no longer exists",
+    process_replaced: "the process has exited and its pid is now another's",
+    signal_refused: "not permitted",
+    signal_unsupported: "signal not supported on this system",
+    sensors: "Sensors",
+    temperatures: "Temperatures",
+    fans: "Fans",
+    no_sensors: "No sensors",
+    max: "Max",
+    critical: "Critical",
+    power: "Power",
+    batteries: "Batteries",
+    no_battery: "No battery",
+    battery_history: "% (history)",
+    charging: "Charging",
+    discharging: "Discharging",
+    full: "Full",
+    not_charging: "Not charging",
+    unknown: "Unknown",
+    ac_adapter: "AC adapter",
+    connected: "connected",
+    disconnected: "disconnected",
+    until_empty: "left",
+    until_full: "to full",
+};
+
+pub static PT: Strings = Strings {
+    computer_resources: " Recursos do Computador ",
+    resource_graphs: " Gráficos de Recursos ",
+    no_resource_selected: "Nenhum recurso selecionado",
+    cpu: "CPU",
+    memory: "Memória",
+    disk: "Disco",
+    network: "Rede",
+    no_network: "Sem rede",
+    total: "Total",
+    used: "Usado",
+    avail: "Livre",
+    swap_total: "Swap Total",
+    swap_used: "Swap Usado",
+    cpu_history: "CPU % (histórico)",
+    cpu_usage: "Uso de CPU",
+    per_core_usage: "Uso por Núcleo",
+    processor: "Processador",
+    cores: "núcleos",
+    threads: "threads",
+    frequency: "Frequência",
+    frequency_summary: "mín / méd / máx",
+    hardware: "hardware",
+    core: "Núcleo",
+    memory_history: "Memória % (histórico)",
+    memory_usage: "Uso de Memória",
+    breakdown: "Detalhamento",
+    disk_history: "Disco % (histórico)",
+    disk_usage: "Uso de Disco",
+    disk_mounts: "Pontos de Montagem (→ para selecionar)",
+    disk_mounts_focused: "Pontos de Montagem (↑/↓ selecionar, h ocultar, ← voltar)",
+    no_mounts: "Nenhum ponto de montagem",
+    mount_detail: "Detalhes da Montagem",
+    mount_history: "% (histórico)",
+    device: "Dispositivo",
+    filesystem: "Sistema de arquivos",
+    read: "Leitura",
+    write: "Escrita",
+    io_unavailable: "E/S: n/d",
+    iops: "IOPS",
+    network_interfaces: "Interfaces de Rede",
+    interfaces: "Interfaces (p: fixar próxima)",
+    interfaces_focused: "Interfaces (↑/↓ selecionar, Enter detalhes, h ocultar, ← voltar)",
+    interface_detail: "detalhes (z: zerar totais da sessão, ← voltar)",
+    mac: "MAC",
+    mtu: "MTU",
+    link_speed: "Velocidade do link",
+    addresses: "Endereços",
+    no_addresses: "nenhum",
+    total_received: "Recebido desde o boot",
+    total_sent: "Enviado desde o boot",
+    session_received: "Recebido nesta sessão",
+    session_sent: "Enviado nesta sessão",
+    pinned: "[fixada]",
+    throughput_history: "tráfego (histórico)",
+    log_scale: "escala log",
+    peak: "pico",
+    received: "Recebido",
+    sent: "Enviado",
+    copy_hint: "y: copiar seleção",
+    theme: "Tema",
+    help_hint: "?: ajuda",
+    paused: "PAUSADO (espaço para continuar)",
+    help: "Ajuda",
+    help_keys: &[
+        ("↑/↓  j/k", "Selecionar recurso, montagem ou processo"),
+        (
+            "→  Enter  l",
+            "Abrir montagens, interfaces, processos ou uma interface",
+        ),
+        ("←  Esc  h", "Voltar à lista de recursos"),
+        (
+            "h",
+            "Nas montagens ou interfaces, ocultar a selecionada de vez",
+        ),
+        ("PgUp/PgDn  Home/End", "Paginar os processos"),
+        ("s  r", "Ordenar processos, inverter a ordem"),
+        ("x  X", "Enviar SIGTERM, SIGKILL ao processo selecionado"),
+        ("p", "Fixar a próxima interface de rede"),
+        ("z", "Zerar os totais da sessão das interfaces"),
+        ("+  -", "Amostrar com menos, mais frequência"),
+        ("Espaço", "Pausar ou retomar a amostragem"),
+        ("d", "Painel: CPU, memória, disco e rede juntos"),
+        ("t", "Próximo tema de cores"),
+        ("L", "Escala linear ou log para o tráfego de rede"),
+        ("y", "Copiar a seleção"),
+        ("Mouse", "Clique para selecionar, roda para rolar"),
+        ("?", "Mostrar ou ocultar esta ajuda"),
+        ("q  Esc", "Sair"),
+    ],
+    copied: "Copiado",
+    clipboard_unavailable: "Área de transferência indisponível",
+    refresh: "Atualização",
+    recording: "Gravando em",
+    recording_stopped: "Gravação interrompida",
+    hidden: "Oculto",
+    hidden_not_saved: "Oculto até sair, não salvo",
+    kernel: "kernel",
+    uptime: "ligado há",
+    load_average: "carga",
+    remote: "Remoto",
+    remote_unreachable: "Inacessível",
+    remote_signal: "Não é possível enviar sinais a processos de uma máquina remota",
+    processes: "Processos",
+    process_table: "Processos (→ para selecionar)",
+    process_table_focused:
+        "Processos (↑/↓ selecionar, s: ordenar, r: inverter, x: SIGTERM, X: SIGKILL, ← voltar)",
+    no_processes: "Nenhum processo",
+    pid: "PID",
+    name: "Nome",
+    sockets: "Sockets",
+    send_signal: "Enviar",
+    confirm: "Confirmar",
+    confirm_hint: "y: sim, qualquer outra tecla: cancelar",
+    signal_sent: "Enviado",
+    process_gone: "O processo não existe mais",
+    process_replaced: "o processo terminou e o pid agora é de outro",
+    signal_refused: "não permitido",
+    signal_unsupported: "sinal não suportado neste sistema",
+    sensors: "Sensores",
+    temperatures: "Temperaturas",
+    fans: "Ventoinhas",
+    no_sensors: "Nenhum sensor",
+    max: "Máx",
+    critical: "Crítica",
+    power: "Energia",
+    batteries: "Baterias",
+    no_battery: "Sem bateria",
+    battery_history: "% (histórico)",
+    charging: "Carregando",
+    discharging: "Descarregando",
+    full: "Carregada",
+    not_charging: "Sem carregar",
+    unknown: "Desconhecido",
+    ac_adapter: "Adaptador AC",
+    connected: "conectado",
+    disconnected: "desconectado",
+    until_empty: "restantes",
+    until_full: "até carregar",
+};
+
+// Locale codes accepted by --lang, and the table for each
+const LOCALES: &[(&str, &Strings)] = &[("en", &EN), ("pt", &PT)];
+
+// "pt", "pt_BR.UTF-8" and "pt-BR" all pick the Portuguese table
+pub fn lookup(code: &str) -> Option<&'static Strings> {
+    let lang = code.split(['_', '-', '.']).next()?.to_lowercase();
+    LOCALES
+        .iter()
+        .find(|(name, _)| *name == lang)
+        .map(|(_, strings)| *strings)
+}
+
+pub fn available() -> Vec<&'static str> {
+    LOCALES.iter().map(|(name, _)| *name).collect()
+}
+
+// Without --lang, follow the usual locale variables, falling back to English
+pub fn from_env() -> &'static Strings {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| lookup(&value))
+        .unwrap_or(&EN)
+}
@@ -1,59 +1,320 @@
+use arboard::Clipboard;
+use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    cursor,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend},
+    layout::Rect,
     Terminal,
 };
-use sysinfo::{Disks, Networks, System};
-use std::io;
-use std::fs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Signal, System};
 
+mod alerts;
+mod cli;
+mod collector;
+mod config;
+mod history;
+mod i18n;
+mod record;
+mod remote;
+mod theme;
 mod ui;
+use alerts::{Alerts, Metric};
+use cli::{Args, DEFAULT_INTERVAL_MS, MAX_INTERVAL_MS, MIN_INTERVAL_MS};
+use collector::{Collector, Command, Sample, Update};
+use config::{is_hidden, ChartScale, Config, SensorConfig, View};
+use history::History;
+use i18n::Strings;
+use record::Recorder;
+use remote::Remote;
+use theme::{Theme, ThemeName};
 use ui::UIRenderer;
 
+// How often the screen may be redrawn at most
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(33);
+// Sampling intervals + and - step through, in milliseconds
+const INTERVAL_STEPS: [u64; 9] = [200, 250, 500, 1_000, 2_000, 5_000, 10_000, 30_000, 60_000];
+// How long the result of a copy or a signal stays on screen
+const STATUS_TTL: Duration = Duration::from_secs(3);
+// Rows moved by PageUp/PageDown in the process table
+const PROCESS_PAGE: usize = 10;
+
+#[derive(Hash, Serialize, Deserialize)]
+pub struct DiskInfo {
+    mount: String,
+    device: String,
+    fs_type: String,
+    total: u64,
+    available: u64,
+    io: Option<DiskIo>, // where the kernel reports I/O for the device
+}
+
+// Transfer since the previous refresh, per second
+#[derive(Clone, Copy, Hash, Serialize, Deserialize)]
+pub struct DiskIo {
+    read_bps: u64,
+    write_bps: u64,
+    read_iops: u64,
+    write_iops: u64,
+}
+
+// What the interface detail shows besides the rates, mostly from /sys/class/net
+#[derive(Clone, Hash, Serialize, Deserialize)]
+pub struct InterfaceDetail {
+    addresses: Vec<String>, // with the prefix length, e.g. 192.0.2.2/24
+    mac: String,
+    mtu: Option<u64>,
+    speed: Option<u64>, // Mb/s, where the driver reports it
+    total_received: u64,
+    total_transmitted: u64,
+}
+
+// The processor and its clock speeds, for the CPU view
+#[derive(Hash, Serialize, Deserialize)]
+pub struct CpuInfo {
+    model: String,
+    physical_cores: Option<usize>,
+    frequencies: Vec<u64>,               // MHz, per logical core
+    frequency_range: Option<(u64, u64)>, // (min, max) MHz the hardware allows, from cpufreq
+}
+
+// The machine being shown, for the header bar
+#[derive(Serialize, Deserialize)]
+pub struct HostInfo {
+    name: Option<String>,
+    os: Option<String>, // e.g. Linux (Ubuntu 24.04)
+    kernel: Option<String>,
+    uptime: u64,            // seconds since boot
+    load_average: [f64; 3], // over 1, 5 and 15 minutes
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pid: u32,
+    name: String,
+    start_time: u64, // s since the epoch; with the pid, tells this process from a later one
+    cpu: f32,        // % of one core, so above 100 for multithreaded processes
+    memory: u64,     // resident bytes
+    disk_read: u64,  // bytes/s
+    disk_write: u64, // bytes/s
+    // Open TCP and UDP sockets; only counted on Linux, and only while the Processes
+    // view is showing, as it means reading every process's file descriptors
+    sockets: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProcessSort {
+    Pid,
+    Name,
+    Cpu,
+    Memory,
+    // Disk reads and writes together
+    Io,
+}
+
+impl ProcessSort {
+    fn next(self) -> Self {
+        match self {
+            ProcessSort::Pid => ProcessSort::Name,
+            ProcessSort::Name => ProcessSort::Cpu,
+            ProcessSort::Cpu => ProcessSort::Memory,
+            ProcessSort::Memory => ProcessSort::Io,
+            ProcessSort::Io => ProcessSort::Pid,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SensorInfo {
+    label: String,
+    temperature: f32, // °C
+    max: f32,         // highest seen since the sensor was first read
+    critical: Option<f32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChargeState {
+    Charging,
+    Discharging,
+    Full,
+    // Plugged in but holding its charge, e.g. under a charge limit
+    NotCharging,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BatteryInfo {
+    name: String,
+    percent: f32,
+    state: ChargeState,
+    rate: Option<f32>,           // W, whichever way the charge is going
+    time_left: Option<Duration>, // until empty when discharging, until full when charging
+}
+
+// A signal waiting for the user to confirm it. The name and start time are what the list
+// showed, so a pid reused since then isn't signalled in its place.
+pub struct KillRequest {
+    pid: u32,
+    name: String,
+    start_time: u64,
+    signal: Signal,
+}
+
 pub struct App {
     selected_item: usize,
     items: Vec<String>,
-    cpu_history: Vec<u64>,
-    mem_history: Vec<u64>,
-    disk_history: Vec<u64>,
+    history_len: usize, // samples kept per series
+    cpu_history: History,
+    mem_history: History,
+    disk_history: History,
     disk_available: u64,
     cpu_cores: Vec<f32>,
+    core_history: Vec<History>, // usage % history per core, in core order
+    core_scroll: usize,         // cores scrolled past in the per-core charts
     mem_total: u64,
     mem_used: u64,
     mem_available: u64,
     mem_swap_total: u64,
     mem_swap_used: u64,
-    disks_info: Vec<(String, u64, u64)>, // (mount_point, total, available)
-    networks_info: Vec<(String, u64, u64, String)>, // (name, rx_bps, tx_bps, kind)
+    disks_info: Vec<DiskInfo>,
+    disk_selected: usize,
+    disk_focused: bool, // arrow keys move between mounts instead of resources
+    hidden_mounts: Vec<String>, // config patterns, see config::is_hidden
+    mount_history: HashMap<String, History>, // mount_point -> usage % history
+    mount_io_history: HashMap<String, (History, History)>, // mount_point -> (read, write) bytes/sec history
+    networks_info: Vec<(String, u64, u64, String)>,        // (name, rx_bps, tx_bps, kind)
+    network_history: HashMap<String, (History, History)>,  // name -> (rx, tx) bytes/sec history
+    interface_details: HashMap<String, InterfaceDetail>,
+    transfer_baseline: HashMap<String, (u64, u64)>, // name -> (received, sent) since boot when the session totals last started
+    network_selected: usize,
+    network_focused: bool, // arrow keys move between interfaces instead of resources
+    network_detail: bool,  // the selected interface's detail instead of the interface list
+    hidden_interfaces: Vec<String>,
+    chart_scale: ChartScale,
+    chart_max: Option<u64>, // fixed top of the throughput chart in bytes/sec
+    sample_interval: Duration,
+    strings: &'static Strings,
+    pinned_interface: Option<String>,
+    interface_order: Vec<String>, // preferred order after the pinned interface
+    copy_status: Option<(Result<String, String>, Instant)>, // (copied text or error, when)
+    processes: Vec<ProcessInfo>,
+    process_sort: ProcessSort,
+    process_sort_reversed: bool,
+    process_selected: usize,
+    process_focused: bool, // arrow keys move between processes instead of resources
+    kill_request: Option<KillRequest>,
+    show_help: bool,
+    dashboard: bool, // CPU, memory, disk and network at once instead of the selected resource
+    paused: bool,    // no sampling, so every view holds still
+    kill_status: Option<(Result<String, String>, Instant)>, // (what was sent or error, when)
+    hide_status: Option<(Result<String, String>, Instant)>, // (what was hidden or why it wasn't saved, when)
+    record_status: Option<Result<String, String>>, // (file being recorded to, or why it stopped)
+    cpu_info: Option<CpuInfo>,
+    host: Option<HostInfo>,
+    remote: Option<String>,       // the --connect agent the views are of
+    remote_error: Option<String>, // why the last fetch from it failed
+    sensors: Vec<SensorInfo>,
+    sensor_history: HashMap<String, History>, // label -> °C history
+    fans: Vec<(String, u64)>,                 // (label, RPM)
+    batteries: Vec<BatteryInfo>,
+    battery_history: HashMap<String, History>, // name -> % history
+    on_ac: Option<bool>,                       // None without an AC adapter to ask
+    sensor_config: SensorConfig,
+    alerts: Alerts,
+    theme: &'static Theme, // t cycles through them
     tick: usize,
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(
+        config: Config,
+        strings: &'static Strings,
+        sample_interval: Duration,
+        history_len: usize,
+    ) -> Self {
         App {
-            selected_item: 0,
+            selected_item: config.view.map_or(0, View::item),
             items: vec![
-                "CPU".to_string(),
-                "Memory".to_string(),
-                "Disk".to_string(),
-                "Network".to_string(),
+                strings.cpu.to_string(),
+                strings.memory.to_string(),
+                strings.disk.to_string(),
+                strings.network.to_string(),
+                strings.processes.to_string(),
+                strings.sensors.to_string(),
+                strings.power.to_string(),
             ],
-            cpu_history: Vec::new(),
-            mem_history: Vec::new(),
-            disk_history: Vec::new(),
+            history_len,
+            cpu_history: History::new(history_len),
+            mem_history: History::new(history_len),
+            disk_history: History::new(history_len),
             disk_available: 0,
             cpu_cores: Vec::new(),
+            core_history: Vec::new(),
+            core_scroll: 0,
             mem_total: 0,
             mem_used: 0,
             mem_available: 0,
             mem_swap_total: 0,
             mem_swap_used: 0,
             disks_info: Vec::new(),
+            disk_selected: 0,
+            disk_focused: false,
+            hidden_mounts: config.disks.hidden,
+            mount_history: HashMap::new(),
+            mount_io_history: HashMap::new(),
             networks_info: Vec::new(),
+            network_history: HashMap::new(),
+            interface_details: HashMap::new(),
+            transfer_baseline: HashMap::new(),
+            network_selected: 0,
+            network_focused: false,
+            network_detail: false,
+            hidden_interfaces: config.network.hidden,
+            chart_scale: config.network.scale,
+            chart_max: config.network.max.filter(|max| *max > 0),
+            sample_interval,
+            strings,
+            pinned_interface: config.network.pinned,
+            interface_order: config.network.order,
+            copy_status: None,
+            processes: Vec::new(),
+            process_sort: ProcessSort::Cpu,
+            process_sort_reversed: false,
+            process_selected: 0,
+            process_focused: false,
+            kill_request: None,
+            show_help: false,
+            dashboard: false,
+            paused: false,
+            kill_status: None,
+            hide_status: None,
+            record_status: None,
+            cpu_info: None,
+            host: None,
+            remote: None,
+            remote_error: None,
+            sensors: Vec::new(),
+            sensor_history: HashMap::new(),
+            fans: Vec::new(),
+            batteries: Vec::new(),
+            battery_history: HashMap::new(),
+            on_ac: None,
+            sensor_config: config.sensors,
+            alerts: Alerts::new(&config.alerts),
+            theme: config.theme.unwrap_or(ThemeName::Dark).theme(),
             tick: 0,
         }
     }
@@ -69,32 +330,542 @@ impl App {
             self.selected_item -= 1;
         }
     }
+
+    fn next_disk(&mut self) {
+        if self.disk_selected + 1 < self.disks_info.len() {
+            self.disk_selected += 1;
+        }
+    }
+
+    fn previous_disk(&mut self) {
+        if self.disk_selected > 0 {
+            self.disk_selected -= 1;
+        }
+    }
+
+    fn next_interface(&mut self) {
+        if self.network_selected + 1 < self.networks_info.len() {
+            self.network_selected += 1;
+        }
+    }
+
+    fn previous_interface(&mut self) {
+        if self.network_selected > 0 {
+            self.network_selected -= 1;
+        }
+    }
+
+    // Leaves the selected mount out from now on, and of the config file for next time
+    fn hide_selected_mount(&mut self) {
+        if self.disk_selected >= self.disks_info.len() {
+            return;
+        }
+        let mount = self.disks_info.remove(self.disk_selected).mount;
+        self.mount_history.remove(&mount);
+        self.mount_io_history.remove(&mount);
+        self.disk_selected = self
+            .disk_selected
+            .min(self.disks_info.len().saturating_sub(1));
+        self.disk_focused = !self.disks_info.is_empty();
+        self.hidden_mounts.push(mount.clone());
+        let saved = config::save_hidden("disks", &mount).map(|()| mount);
+        self.hide_status = Some((saved, Instant::now()));
+    }
+
+    // As hide_selected_mount, for the selected interface
+    fn hide_selected_interface(&mut self) {
+        if self.network_selected >= self.networks_info.len() {
+            return;
+        }
+        let (name, _, _, _) = self.networks_info.remove(self.network_selected);
+        self.network_history.remove(&name);
+        self.interface_details.remove(&name);
+        if self.pinned_interface.as_ref() == Some(&name) {
+            self.pinned_interface = None;
+        }
+        self.network_selected = self
+            .network_selected
+            .min(self.networks_info.len().saturating_sub(1));
+        self.network_focused = !self.networks_info.is_empty();
+        self.hidden_interfaces.push(name.clone());
+        let saved = config::save_hidden("network", &name).map(|()| name);
+        self.hide_status = Some((saved, Instant::now()));
+    }
+
+    fn next_process(&mut self, rows: usize) {
+        self.process_selected = self
+            .process_selected
+            .saturating_add(rows)
+            .min(self.processes.len().saturating_sub(1));
+    }
+
+    fn previous_process(&mut self, rows: usize) {
+        self.process_selected = self.process_selected.saturating_sub(rows);
+    }
+
+    // CPU and memory sort busiest first, PID and name ascending; reversing flips either.
+    // The selection stays on the same process.
+    fn sort_processes(&mut self) {
+        let selected_pid = self.processes.get(self.process_selected).map(|p| p.pid);
+        let sort = self.process_sort;
+        self.processes.sort_by(|a, b| {
+            let order = match sort {
+                ProcessSort::Pid => a.pid.cmp(&b.pid),
+                ProcessSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                ProcessSort::Cpu => b.cpu.total_cmp(&a.cpu),
+                ProcessSort::Memory => b.memory.cmp(&a.memory),
+                ProcessSort::Io => (b.disk_read + b.disk_write).cmp(&(a.disk_read + a.disk_write)),
+            };
+            order.then_with(|| a.pid.cmp(&b.pid))
+        });
+        if self.process_sort_reversed {
+            self.processes.reverse();
+        }
+        if let Some(i) =
+            selected_pid.and_then(|pid| self.processes.iter().position(|p| p.pid == pid))
+        {
+            self.process_selected = i;
+        }
+        self.process_selected = self
+            .process_selected
+            .min(self.processes.len().saturating_sub(1));
+        if self.processes.is_empty() {
+            self.process_focused = false;
+        }
+    }
+
+    fn request_kill(&mut self, signal: Signal) {
+        if self.remote.is_some() {
+            self.kill_status = Some((Err(self.strings.remote_signal.to_string()), Instant::now()));
+            return;
+        }
+        if let Some(process) = self.processes.get(self.process_selected) {
+            self.kill_request = Some(KillRequest {
+                pid: process.pid,
+                name: process.name.clone(),
+                start_time: process.start_time,
+                signal,
+            });
+        }
+    }
+
+    // The next step up from the current interval (+), or down (-), within the allowed range
+    fn slower_sampling(&mut self) {
+        let current = self.sample_interval.as_millis() as u64;
+        if let Some(&ms) = INTERVAL_STEPS.iter().find(|&&ms| ms > current) {
+            self.sample_interval = Duration::from_millis(ms);
+        }
+    }
+
+    fn faster_sampling(&mut self) {
+        let current = self.sample_interval.as_millis() as u64;
+        if let Some(&ms) = INTERVAL_STEPS.iter().rev().find(|&&ms| ms < current) {
+            self.sample_interval = Duration::from_millis(ms);
+        }
+    }
+
+    // Feeds the latest sample to the alerts; true when any went off with it
+    fn check_alerts(&mut self) -> bool {
+        let latest = [&self.cpu_history, &self.mem_history, &self.disk_history]
+            .map(|history| history.last().unwrap_or(0) as f32);
+        Metric::ALL
+            .into_iter()
+            .zip(latest)
+            .fold(false, |fired, (metric, value)| {
+                self.alerts.update(metric, value) || fired
+            })
+    }
+
+    // The panel title flashes on every other sample while an alert is active
+    fn alert_flash(&self) -> bool {
+        self.alerts.any_active() && self.tick.is_multiple_of(2)
+    }
+
+    // Hash of everything the current view draws, so unchanged frames can be skipped.
+    // Anything new that ends up on screen needs adding here.
+    fn fingerprint(&self) -> u64 {
+        let mut h = DefaultHasher::new();
+        self.selected_item.hash(&mut h);
+        (self.theme.name, self.show_help, self.paused, self.dashboard).hash(&mut h);
+        (self.alerts.active(), self.alert_flash()).hash(&mut h);
+        self.cpu_history.hash(&mut h);
+        self.mem_history.hash(&mut h);
+        self.disk_history.hash(&mut h);
+        self.disk_available.hash(&mut h);
+        for usage in &self.cpu_cores {
+            usage.to_bits().hash(&mut h);
+        }
+        if self.selected_item == 0 || self.dashboard {
+            (&self.core_history, self.core_scroll, &self.cpu_info).hash(&mut h);
+        }
+        (self.mem_total, self.mem_used, self.mem_available).hash(&mut h);
+        (self.mem_swap_total, self.mem_swap_used).hash(&mut h);
+        self.disks_info.hash(&mut h);
+        (self.disk_selected, self.disk_focused).hash(&mut h);
+        if let Some(disk) = self.disks_info.get(self.disk_selected) {
+            self.mount_history.get(&disk.mount).hash(&mut h);
+            self.mount_io_history.get(&disk.mount).hash(&mut h);
+        }
+        self.networks_info.hash(&mut h);
+        (
+            self.network_selected,
+            self.network_focused,
+            self.network_detail,
+        )
+            .hash(&mut h);
+        self.chart_scale.hash(&mut h);
+        if self.selected_item == 3 || self.dashboard {
+            if let Some((name, _, _, _)) = self.networks_info.get(self.charted_interface()) {
+                self.network_history.get(name).hash(&mut h);
+                if self.network_detail {
+                    self.interface_details.get(name).hash(&mut h);
+                    self.transfer_baseline.get(name).hash(&mut h);
+                }
+            }
+        }
+        self.pinned_interface.hash(&mut h);
+        self.sample_interval.hash(&mut h);
+        self.record_status.hash(&mut h);
+        (&self.remote, &self.remote_error).hash(&mut h);
+        if let Some(host) = &self.host {
+            (&host.name, &host.os, &host.kernel, host.uptime).hash(&mut h);
+            host.load_average.map(f64::to_bits).hash(&mut h);
+        }
+        for sensor in &self.sensors {
+            (
+                &sensor.label,
+                sensor.temperature.to_bits(),
+                sensor.max.to_bits(),
+            )
+                .hash(&mut h);
+            sensor.critical.map(f32::to_bits).hash(&mut h);
+        }
+        if self.selected_item == 5 {
+            for sensor in &self.sensors {
+                self.sensor_history.get(&sensor.label).hash(&mut h);
+            }
+            self.fans.hash(&mut h);
+        }
+        for battery in &self.batteries {
+            (
+                &battery.name,
+                battery.percent.to_bits(),
+                battery.state,
+                battery.time_left,
+            )
+                .hash(&mut h);
+            battery.rate.map(f32::to_bits).hash(&mut h);
+        }
+        self.on_ac.hash(&mut h);
+        if self.selected_item == 6 {
+            for battery in &self.batteries {
+                self.battery_history.get(&battery.name).hash(&mut h);
+            }
+        }
+        self.copy_status
+            .as_ref()
+            .map(|(result, _)| result)
+            .hash(&mut h);
+        self.hide_status
+            .as_ref()
+            .map(|(result, _)| result)
+            .hash(&mut h);
+        self.processes.len().hash(&mut h);
+        if self.selected_item == 4 {
+            for process in &self.processes {
+                (
+                    process.pid,
+                    &process.name,
+                    process.cpu.to_bits(),
+                    process.memory,
+                )
+                    .hash(&mut h);
+                (process.disk_read, process.disk_write, process.sockets).hash(&mut h);
+            }
+            (self.process_sort, self.process_sort_reversed).hash(&mut h);
+            (self.process_selected, self.process_focused).hash(&mut h);
+            if let Some(request) = &self.kill_request {
+                (request.pid, &request.name, request.signal == Signal::Kill).hash(&mut h);
+            }
+            self.kill_status
+                .as_ref()
+                .map(|(result, _)| result)
+                .hash(&mut h);
+        }
+        // The spinner only animates in the Network view
+        if self.selected_item == 3 {
+            self.tick.hash(&mut h);
+        }
+        h.finish()
+    }
+
+    // Which interface the network chart follows: the selected one while the list has focus,
+    // otherwise the first
+    fn charted_interface(&self) -> usize {
+        if self.network_focused {
+            self.network_selected
+        } else {
+            0
+        }
+    }
+
+    // Bytes (received, sent) by an interface since the TUI started or z last reset the count
+    fn session_transfer(&self, name: &str) -> Option<(u64, u64)> {
+        let detail = self.interface_details.get(name)?;
+        let (received, sent) = self
+            .transfer_baseline
+            .get(name)
+            .copied()
+            .unwrap_or_default();
+        Some((
+            detail.total_received.saturating_sub(received),
+            detail.total_transmitted.saturating_sub(sent),
+        ))
+    }
+
+    fn reset_session_transfer(&mut self) {
+        self.transfer_baseline = self
+            .interface_details
+            .iter()
+            .map(|(name, d)| (name.clone(), (d.total_received, d.total_transmitted)))
+            .collect();
+    }
+
+    // Pins the next interface by name, and after the last one unpins
+    fn cycle_pinned_interface(&mut self) {
+        let mut names: Vec<&String> = self.networks_info.iter().map(|(n, _, _, _)| n).collect();
+        names.sort();
+        let current = self
+            .pinned_interface
+            .as_ref()
+            .and_then(|pinned| names.iter().position(|n| *n == pinned));
+        let next = match current {
+            Some(i) => names.get(i + 1),
+            // Nothing pinned, or the pinned interface isn't up
+            None => names.first(),
+        };
+        self.pinned_interface = next.map(|n| n.to_string());
+    }
+
+    // Pinned first, then the configured order, then the default route, then by name.
+    // Sorting on all of it keeps the list from shuffling between ticks.
+    fn order_interfaces(&mut self, default_iface: Option<&str>) {
+        let pinned = self.pinned_interface.as_deref();
+        let order = &self.interface_order;
+        let rank = |name: &str| {
+            if Some(name) == pinned {
+                0
+            } else if let Some(pos) = order.iter().position(|o| o == name) {
+                1 + pos
+            } else if Some(name) == default_iface {
+                1 + order.len()
+            } else {
+                2 + order.len()
+            }
+        };
+        self.networks_info
+            .sort_by(|(a, _, _, _), (b, _, _, _)| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(
-        stdout,
-        EnterAlternateScreen,
-        EnableMouseCapture
-    )?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+// Clicking a resource in the left panel shows it, leaving the dashboard; the wheel scrolls
+// whatever the central panel lists
+fn handle_mouse(app: &mut App, mouse: MouseEvent, screen: Rect) {
+    let central = UIRenderer::create_layout(screen)[1];
+    let over_central = mouse.column >= central.x
+        && mouse.column < central.right()
+        && mouse.row >= central.y
+        && mouse.row < central.bottom();
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(index) = UIRenderer::resource_at(screen, app, mouse.column, mouse.row) {
+                app.selected_item = index;
+                app.disk_focused = false;
+                app.process_focused = false;
+                (app.network_focused, app.network_detail) = (false, false);
+                app.dashboard = false;
+            }
+        }
+        // The dashboard's views are too small to scroll
+        MouseEventKind::ScrollDown if over_central && !app.dashboard => match app.selected_item {
+            0 => {
+                app.core_scroll =
+                    (app.core_scroll + 1).min(UIRenderer::core_scroll_limit(screen, app))
+            }
+            2 => app.next_disk(),
+            3 => app.next_interface(),
+            4 => app.next_process(1),
+            _ => {}
+        },
+        MouseEventKind::ScrollUp if over_central && !app.dashboard => match app.selected_item {
+            0 => {
+                app.core_scroll = app
+                    .core_scroll
+                    .min(UIRenderer::core_scroll_limit(screen, app))
+                    .saturating_sub(1)
+            }
+            2 => app.previous_disk(),
+            3 => app.previous_interface(),
+            4 => app.previous_process(1),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+// Sends the confirmed signal, returning what was sent or why it wasn't. The process is
+// looked up afresh, as the collector thread has the System that listed it.
+fn send_signal(request: &KillRequest, strings: &Strings) -> Result<String, String> {
+    let pid = Pid::from_u32(request.pid);
+    let mut sys = System::new();
+    sys.refresh_process(pid);
+    let process = sys
+        .process(pid)
+        .ok_or_else(|| strings.process_gone.to_string())?;
+    if process.name() != request.name || process.start_time() != request.start_time {
+        return Err(format!(
+            "{} ({}): {}",
+            request.pid, request.name, strings.process_replaced
+        ));
+    }
+    let sent = format!(
+        "{} → {} ({})",
+        ui::signal_name(request.signal),
+        request.pid,
+        request.name
+    );
+    match process.kill_with(request.signal) {
+        Some(true) => Ok(sent),
+        Some(false) => Err(format!("{}: {}", sent, strings.signal_refused)),
+        None => Err(format!("{}: {}", sent, strings.signal_unsupported)),
+    }
+}
 
-    // Create app and run it
-    let app = App::new();
-    let res = run_app(&mut terminal, app);
+// The clipboard is opened on first use and kept: on X11 copied text is only available
+// while the clipboard that set it is alive
+fn copy_to_clipboard(clipboard: &mut Option<Clipboard>, text: &str) -> Result<(), String> {
+    let clipboard = match clipboard {
+        Some(clipboard) => clipboard,
+        None => clipboard.insert(Clipboard::new().map_err(|e| e.to_string())?),
+    };
+    clipboard.set_text(text).map_err(|e| e.to_string())
+}
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
+// Raw mode, the alternate screen and mouse capture for as long as it lives. Dropping it
+// puts the terminal back, on an early return or an unwinding panic alike.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        // Constructed first, so a failure halfway still undoes what was done
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+// Best effort: there's nowhere left to report a failure to
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+        DisableMouseCapture,
+        cursor::Show
+    );
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Read before the terminal switches over, so usage and config errors stay visible
+    let args = Args::parse();
+    let mut config = Config::load();
+    config.alerts.cpu = args.alert_cpu.or(config.alerts.cpu);
+    config.alerts.memory = args.alert_memory.or(config.alerts.memory);
+    config.alerts.disk = args.alert_disk.or(config.alerts.disk);
+    config.alerts.bell |= args.bell;
+    let strings = match args.lang.or_else(|| config.lang.clone()) {
+        Some(code) => i18n::lookup(&code).ok_or_else(|| {
+            format!(
+                "Unknown language '{}' (available: {})",
+                code,
+                i18n::available().join(", ")
+            )
+        })?,
+        None => i18n::from_env(),
+    };
+    let interval = args
+        .interval
+        .or(config.interval)
+        .unwrap_or(DEFAULT_INTERVAL_MS)
+        .clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+    let sample_interval = Duration::from_millis(interval);
+    if let Some(addr) = &args.serve {
+        remote::serve(addr, Collector::new(), sample_interval)
+            .map_err(|e| format!("Cannot serve on {}: {}", addr, e))?;
+        return Ok(());
+    }
+    let remote = args.connect.as_deref().map(Remote::parse).transpose()?;
+    let remote_host = remote.as_ref().map(|r| r.host().to_string());
+    // A local sample's CPU usage and rates need one before it to be worked out from; an
+    // agent has taken that already
+    let (first_usable, source) = match remote {
+        Some(remote) => (0, remote.spawn(sample_interval)),
+        None => (1, Collector::new().spawn(sample_interval)),
+    };
+    let mut app = App::new(config, strings, sample_interval, args.history_len as usize);
+    if args.once {
+        let sample = source
+            .1
+            .iter()
+            .nth(first_usable)
+            .ok_or("The collector stopped before taking a sample")??;
+        apply_sample(&mut app, sample);
+        let text = match args.json {
+            true => record::to_json(&app)?,
+            false => UIRenderer::summary_text(&app),
+        };
+        // A reader that stops early, like head, isn't an error
+        match writeln!(io::stdout(), "{}", text) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => return Ok(()),
+        }
+    }
+    let recorder = match &args.record {
+        Some(path) => Some(
+            Recorder::open(path, args.record_format)
+                .map_err(|e| format!("Cannot record to {}: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+
+    // Setup terminal. A panic restores it before its message is printed, which would
+    // otherwise land on the alternate screen in raw mode and vanish with it.
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    // Run the app, on this machine's metrics or an agent's
+    if let Some(name) = args.theme {
+        app.theme = name.theme();
+    }
+    app.record_status = recorder
+        .as_ref()
+        .map(|r| Ok(r.path().display().to_string()));
+    app.remote = remote_host;
+    let res = run_app(&mut terminal, app, source, recorder);
+    drop(guard);
 
     if let Err(err) = res {
         println!("{:?}", err);
@@ -103,165 +874,408 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
-    let mut sys = System::new_all();
-    let mut disks = Disks::new_with_refreshed_list();
-    let mut networks = Networks::new_with_refreshed_list();
-    const HISTORY_LEN: usize = 100;
+// Takes a sample from the collector into the histories and views
+fn apply_sample(app: &mut App, sample: Sample) {
+    // CPU usage (percentage)
+    app.cpu_history.push(sample.cpu_usage.round() as u64);
 
-    loop {
-        // Refresh system metrics
-        sys.refresh_cpu();
-        sys.refresh_memory();
-
-        // CPU usage (percentage)
-        let cpu_usage = sys.global_cpu_info().cpu_usage();
-        let cpu_pct = cpu_usage.round() as u64;
-        app.cpu_history.push(cpu_pct);
-        if app.cpu_history.len() > HISTORY_LEN {
-            app.cpu_history.remove(0);
-        }
-
-        // Memory usage (percentage)
-        let total_mem = sys.total_memory() as f64;
-        let used_mem = sys.used_memory() as f64;
-        let mem_pct = if total_mem > 0.0 { ((used_mem / total_mem) * 100.0).round() as u64 } else { 0 };
-        app.mem_history.push(mem_pct);
-        if app.mem_history.len() > HISTORY_LEN {
-            app.mem_history.remove(0);
-        }
-
-        // Per-core CPU usage
-        app.cpu_cores = sys.cpus().iter().map(|c| c.cpu_usage()).collect();
-
-        // Memory breakdown
-        app.mem_total = sys.total_memory();
-        app.mem_used = sys.used_memory();
-        app.mem_available = sys.available_memory();
-        app.mem_swap_total = sys.total_swap();
-        app.mem_swap_used = sys.used_swap();
-
-        // Disk usage: refresh disks and compute aggregate usage/available
-        disks.refresh();
-        let mut total_disk: u64 = 0;
-        let mut avail_disk: u64 = 0;
-        app.disks_info.clear();
-        for d in disks.list() {
-            total_disk = total_disk.saturating_add(d.total_space());
-            avail_disk = avail_disk.saturating_add(d.available_space());
-            let mount = d.mount_point().to_string_lossy().to_string();
-            app.disks_info.push((mount, d.total_space(), d.available_space()));
-        }
-        let used_disk = total_disk.saturating_sub(avail_disk);
-        let disk_pct = if total_disk > 0 {
-            ((used_disk as f64 / total_disk as f64) * 100.0).round() as u64
+    // Memory usage (percentage)
+    let mem_pct = if sample.mem_total > 0 {
+        ((sample.mem_used as f64 / sample.mem_total as f64) * 100.0).round() as u64
+    } else {
+        0
+    };
+    app.mem_history.push(mem_pct);
+
+    // Processes, kept in the chosen order
+    app.processes = sample.processes;
+    app.sort_processes();
+
+    // Temperatures, with a history each
+    app.sensors = sample.sensors;
+    for sensor in &app.sensors {
+        let history = app
+            .sensor_history
+            .entry(sensor.label.clone())
+            .or_insert_with(|| History::new(app.history_len));
+        history.push(sensor.temperature.max(0.0).round() as u64);
+    }
+    let labels: Vec<&String> = app.sensors.iter().map(|s| &s.label).collect();
+    app.sensor_history
+        .retain(|label, _| labels.contains(&label));
+    app.fans = sample.fans;
+
+    // Batteries, with a charge history each
+    (app.batteries, app.on_ac) = (sample.batteries, sample.on_ac);
+    for battery in &app.batteries {
+        let history = app
+            .battery_history
+            .entry(battery.name.clone())
+            .or_insert_with(|| History::new(app.history_len));
+        history.push(battery.percent.round() as u64);
+    }
+    let names: Vec<&String> = app.batteries.iter().map(|b| &b.name).collect();
+    app.battery_history.retain(|name, _| names.contains(&name));
+
+    // Per-core CPU usage
+    app.cpu_cores = sample.cores;
+    app.core_history
+        .resize_with(app.cpu_cores.len(), || History::new(app.history_len));
+    for (history, usage) in app.core_history.iter_mut().zip(&app.cpu_cores) {
+        history.push(usage.round() as u64);
+    }
+
+    // Memory breakdown
+    app.mem_total = sample.mem_total;
+    app.mem_used = sample.mem_used;
+    app.mem_available = sample.mem_available;
+    app.mem_swap_total = sample.swap_total;
+    app.mem_swap_used = sample.swap_used;
+
+    // Disk usage and throughput: per-mount histories, then aggregate usage/available
+    app.disks_info = sample.disks;
+    app.disks_info
+        .retain(|d| !is_hidden(&app.hidden_mounts, &d.mount));
+    let mut total_disk: u64 = 0;
+    let mut avail_disk: u64 = 0;
+    for d in &app.disks_info {
+        total_disk = total_disk.saturating_add(d.total);
+        avail_disk = avail_disk.saturating_add(d.available);
+        let used = d.total.saturating_sub(d.available);
+        let pct = if d.total > 0 {
+            ((used as f64 / d.total as f64) * 100.0).round() as u64
         } else {
             0
         };
-        app.disk_history.push(disk_pct);
-        if app.disk_history.len() > HISTORY_LEN {
-            app.disk_history.remove(0);
-        }
-        app.disk_available = avail_disk;
-
-        // Networks: refresh and compute approximate speeds (bytes/sec)
-        networks.refresh();
-        app.networks_info.clear();
-
-        // Attempt to detect default interface (the one used for the default route)
-        fn find_default_interface() -> Option<String> {
-            if let Ok(content) = fs::read_to_string("/proc/net/route") {
-                for line in content.lines().skip(1) {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 2 {
-                        let iface = parts[0];
-                        let dest = parts[1];
-                        if dest == "00000000" {
-                            return Some(iface.to_string());
-                        }
-                    }
-                }
-            }
-            None
+        let history = app
+            .mount_history
+            .entry(d.mount.clone())
+            .or_insert_with(|| History::new(app.history_len));
+        history.push(pct);
+        if let Some(io) = d.io {
+            let (read_history, write_history) = app
+                .mount_io_history
+                .entry(d.mount.clone())
+                .or_insert_with(|| (History::new(app.history_len), History::new(app.history_len)));
+            read_history.push(io.read_bps);
+            write_history.push(io.write_bps);
         }
+    }
+    // Forget mounts that went away, and keep the selection on the list
+    let mounted: Vec<&String> = app.disks_info.iter().map(|d| &d.mount).collect();
+    app.mount_history
+        .retain(|mount, _| mounted.contains(&mount));
+    app.mount_io_history
+        .retain(|mount, _| mounted.contains(&mount));
+    app.disk_selected = app
+        .disk_selected
+        .min(app.disks_info.len().saturating_sub(1));
+    let used_disk = total_disk.saturating_sub(avail_disk);
+    let disk_pct = if total_disk > 0 {
+        ((used_disk as f64 / total_disk as f64) * 100.0).round() as u64
+    } else {
+        0
+    };
+    app.disk_history.push(disk_pct);
+    app.disk_available = avail_disk;
 
-        let default_iface = find_default_interface();
+    // Networks, with (rx, tx) history each
+    app.networks_info = sample.networks;
+    app.networks_info
+        .retain(|(name, _, _, _)| !is_hidden(&app.hidden_interfaces, name));
+    for (name, rx_bps, tx_bps, _) in &app.networks_info {
+        let (rx_history, tx_history) = app
+            .network_history
+            .entry(name.clone())
+            .or_insert_with(|| (History::new(app.history_len), History::new(app.history_len)));
+        rx_history.push(*rx_bps);
+        tx_history.push(*tx_bps);
+    }
+    app.interface_details = sample.interface_details;
+    app.interface_details
+        .retain(|name, _| !is_hidden(&app.hidden_interfaces, name));
+    // Session totals count from the first sample an interface shows up in. Counters that went
+    // backwards were reset along with the interface, so the count starts over from zero.
+    for (name, d) in &app.interface_details {
+        let baseline = app
+            .transfer_baseline
+            .entry(name.clone())
+            .or_insert((d.total_received, d.total_transmitted));
+        if d.total_received < baseline.0 || d.total_transmitted < baseline.1 {
+            *baseline = (0, 0);
+        }
+    }
+    app.transfer_baseline
+        .retain(|name, _| app.interface_details.contains_key(name));
+    // Forget interfaces that went away, and keep the selection on the list
+    let up: Vec<&String> = app.networks_info.iter().map(|(n, _, _, _)| n).collect();
+    app.network_history.retain(|name, _| up.contains(&name));
+    app.network_selected = app
+        .network_selected
+        .min(app.networks_info.len().saturating_sub(1));
+    if app.networks_info.is_empty() {
+        (app.network_focused, app.network_detail) = (false, false);
+    }
 
-        for (name, net) in networks.list() {
-            // Skip loopback interface
-            if name == "lo" {
-                continue;
-            }
+    app.cpu_info = Some(sample.cpu);
+    app.host = Some(sample.host);
 
-            // net.received()/transmitted() give bytes since last refresh; our loop polls ~500ms
-            let rx = net.received();
-            let tx = net.transmitted();
-            // convert to bytes/sec assuming ~500ms interval
-            let rx_bps = rx.saturating_mul(2);
-            let tx_bps = tx.saturating_mul(2);
-            let kind = {
-                // Prefer checking sysfs on Linux to detect wireless interfaces reliably
-                #[cfg(target_os = "linux")]
-                {
-                    use std::path::Path;
-                    let wireless_path = format!("/sys/class/net/{}/wireless", name);
-                    if Path::new(&wireless_path).exists() {
-                        "Wi-Fi".to_string()
-                    } else {
-                        // If the device directory exists it's likely a physical interface (Ethernet)
-                        let device_path = format!("/sys/class/net/{}/device", name);
-                        if Path::new(&device_path).exists() {
-                            "Ethernet".to_string()
-                        } else {
-                            // Fallback to name heuristics
-                            if name.contains("wl") || name.to_lowercase().contains("wifi") || name.to_lowercase().contains("wlan") {
-                                "Wi-Fi".to_string()
-                            } else if name.contains("en") || name.to_lowercase().contains("eth") || name.to_lowercase().contains("enp") {
-                                "Ethernet".to_string()
-                            } else {
-                                "Unknown".to_string()
-                            }
-                        }
-                    }
-                }
-                #[cfg(not(target_os = "linux"))]
-                {
-                    // Non-Linux fallback heuristics
-                    if name.contains("wl") || name.to_lowercase().contains("wifi") || name.to_lowercase().contains("wlan") {
-                        "Wi-Fi".to_string()
-                    } else if name.contains("en") || name.to_lowercase().contains("eth") || name.to_lowercase().contains("enp") {
-                        "Ethernet".to_string()
-                    } else {
-                        "Unknown".to_string()
-                    }
-                }
-            };
-            app.networks_info.push((name.clone(), rx_bps, tx_bps, kind));
-        }
+    // The left panel summarises whichever interface ends up first
+    app.order_interfaces(sample.default_iface.as_deref());
 
-        // If we detected a default interface, move it to the front so the left panel shows it
-        if let Some(def) = default_iface {
-            if let Some(pos) = app.networks_info.iter().position(|(n, _rx, _tx, _k)| n == &def) {
-                if pos > 0 {
-                    let entry = app.networks_info.remove(pos);
-                    app.networks_info.insert(0, entry);
-                }
+    // Animation tick for simple indicator
+    app.tick = app.tick.wrapping_add(1);
+}
+
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    (commands, updates): (Sender<Command>, Receiver<Update>),
+    mut recorder: Option<Recorder>,
+) -> io::Result<()> {
+    // Sampling runs on the collector's (or the agent's) thread and clock; this one only
+    // takes in samples, handles input and draws when what's on screen would change
+    let mut counting_sockets = false;
+    let mut last_frame: Option<(Instant, u64)> = None; // (drawn at, fingerprint drawn)
+    let mut clipboard: Option<Clipboard> = None;
+
+    loop {
+        // Socket counts are only worth their cost while the process table is showing
+        if counting_sockets != (app.selected_item == 4) {
+            counting_sockets = app.selected_item == 4;
+            let _ = commands.send(Command::CountSockets(counting_sockets));
+        }
+        let update = match updates.try_recv() {
+            Ok(update) => Some(update),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                return Err(io::Error::other("the sampling thread stopped"))
             }
+        };
+        // One that was on its way when sampling paused is dropped, so the views hold still.
+        // A failed fetch from an agent leaves the last sample up.
+        if let Some(Err(e)) = &update {
+            app.remote_error = Some(e.clone());
+        }
+        if let Some(Ok(sample)) = update.filter(|_| !app.paused) {
+            app.remote_error = None;
+            apply_sample(&mut app, sample);
+            if app.check_alerts() && app.alerts.bell() {
+                let mut stdout = io::stdout();
+                stdout.write_all(b"\x07")?;
+                stdout.flush()?;
+            }
+            // A failed write stops the recording, not the session
+            if let Some(Err(e)) = recorder.as_mut().map(|r| r.write(&app)) {
+                app.record_status = Some(Err(e.to_string()));
+                recorder = None;
+            }
+        }
+        let now = Instant::now();
+        if app
+            .copy_status
+            .as_ref()
+            .is_some_and(|(_, at)| now.duration_since(*at) >= STATUS_TTL)
+        {
+            app.copy_status = None;
+        }
+        if app
+            .kill_status
+            .as_ref()
+            .is_some_and(|(_, at)| now.duration_since(*at) >= STATUS_TTL)
+        {
+            app.kill_status = None;
+        }
+        if app
+            .hide_status
+            .as_ref()
+            .is_some_and(|(_, at)| now.duration_since(*at) >= STATUS_TTL)
+        {
+            app.hide_status = None;
         }
 
-        // Animation tick for simple indicator
-        app.tick = app.tick.wrapping_add(1);
+        // Draw UI, at most once per MIN_FRAME_INTERVAL and only when the view changed
+        let fingerprint = app.fingerprint();
+        let mut redraw_in = None;
+        match last_frame {
+            Some((_, drawn)) if drawn == fingerprint => {}
+            Some((at, _)) if now.duration_since(at) < MIN_FRAME_INTERVAL => {
+                redraw_in = Some(MIN_FRAME_INTERVAL - now.duration_since(at));
+            }
+            _ => {
+                terminal.draw(|f| UIRenderer::render(f, &app))?;
+                last_frame = Some((now, fingerprint));
+            }
+        }
 
-        // Draw UI
-        terminal.draw(|f| UIRenderer::render(f, &app))?;
+        // Wait for input until a pending frame is due, or a frame's time at most, then look
+        // for a new sample again
+        let timeout = redraw_in.unwrap_or(MIN_FRAME_INTERVAL);
 
         // Handle input events
-        if crossterm::event::poll(std::time::Duration::from_millis(500))? {
-            if let Event::Key(key) = event::read()? {
+        if crossterm::event::poll(timeout)? {
+            let event = event::read()?;
+            // The fingerprint doesn't cover the terminal size
+            if let Event::Resize(_, _) = event {
+                last_frame = None;
+            }
+            // Ignored while a signal waits for confirmation, which takes a key, and under the help
+            if let Event::Mouse(mouse) = event {
+                if app.kill_request.is_none() && !app.show_help {
+                    let size = terminal.size()?;
+                    handle_mouse(&mut app, mouse, Rect::new(0, 0, size.width, size.height));
+                }
+            }
+            if let Event::Key(key) = event {
+                // A pending signal takes the next key: y sends it, anything else cancels
+                if let Some(request) = app.kill_request.take() {
+                    if key.code == KeyCode::Char('y') {
+                        let result = send_signal(&request, app.strings);
+                        app.kill_status = Some((result, Instant::now()));
+                    }
+                    continue;
+                }
+                // The help stays up over any pane until ? or Esc closes it
+                if app.show_help {
+                    match key.code {
+                        KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Char('?') | KeyCode::Esc => app.show_help = false,
+                        _ => {}
+                    }
+                    continue;
+                }
+                if key.code == KeyCode::Char('?') {
+                    app.show_help = true;
+                    continue;
+                }
+                // Copies whatever is selected, in the pane that has focus
+                if key.code == KeyCode::Char('y') {
+                    let text = UIRenderer::selection_text(&app);
+                    let result = copy_to_clipboard(&mut clipboard, &text).map(|()| text);
+                    app.copy_status = Some((result, Instant::now()));
+                    continue;
+                }
+                // The sampling interval can be changed from any pane; '=' is '+' without shift
+                // on most layouts
+                if matches!(
+                    key.code,
+                    KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-')
+                ) {
+                    if key.code == KeyCode::Char('-') {
+                        app.faster_sampling();
+                    } else {
+                        app.slower_sampling();
+                    }
+                    let _ = commands.send(Command::Interval(app.sample_interval));
+                    continue;
+                }
+                if key.code == KeyCode::Char(' ') {
+                    app.paused = !app.paused;
+                    let _ = commands.send(Command::Pause(app.paused));
+                    continue;
+                }
+                if key.code == KeyCode::Char('t') {
+                    app.theme = app.theme.name.next().theme();
+                    continue;
+                }
+                if key.code == KeyCode::Char('L') {
+                    app.chart_scale = app.chart_scale.toggle();
+                    continue;
+                }
+                if key.code == KeyCode::Char('d') {
+                    app.dashboard = !app.dashboard;
+                    app.disk_focused = false;
+                    app.process_focused = false;
+                    (app.network_focused, app.network_detail) = (false, false);
+                    continue;
+                }
+                // Inside the Disk view the arrows move between mounts until the pane is left;
+                // h hides the selected one
+                if app.disk_focused {
+                    match key.code {
+                        KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Esc | KeyCode::Left => app.disk_focused = false,
+                        KeyCode::Down | KeyCode::Char('j') => app.next_disk(),
+                        KeyCode::Up | KeyCode::Char('k') => app.previous_disk(),
+                        KeyCode::Char('h') => app.hide_selected_mount(),
+                        _ => {}
+                    }
+                    continue;
+                }
+                // Inside the Network view the arrows move between interfaces; Enter opens the
+                // selected one's detail, which Esc leaves for the list again; h hides the selected
+                // one and z restarts the session totals
+                if app.network_focused {
+                    match key.code {
+                        KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') if app.network_detail => {
+                            app.network_detail = false
+                        }
+                        KeyCode::Esc | KeyCode::Left => app.network_focused = false,
+                        KeyCode::Char('h') => app.hide_selected_interface(),
+                        KeyCode::Char('z') => app.reset_session_transfer(),
+                        KeyCode::Right | KeyCode::Enter | KeyCode::Char('l') => {
+                            app.network_detail = true
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => app.next_interface(),
+                        KeyCode::Up | KeyCode::Char('k') => app.previous_interface(),
+                        KeyCode::Char('p') => app.cycle_pinned_interface(),
+                        _ => {}
+                    }
+                    continue;
+                }
+                if app.process_focused {
+                    match key.code {
+                        KeyCode::Char('q') => return Ok(()),
+                        KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') => {
+                            app.process_focused = false
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => app.next_process(1),
+                        KeyCode::Up | KeyCode::Char('k') => app.previous_process(1),
+                        KeyCode::PageDown => app.next_process(PROCESS_PAGE),
+                        KeyCode::PageUp => app.previous_process(PROCESS_PAGE),
+                        KeyCode::End => app.next_process(usize::MAX),
+                        KeyCode::Home => app.previous_process(usize::MAX),
+                        KeyCode::Char('s') => {
+                            app.process_sort = app.process_sort.next();
+                            app.sort_processes();
+                        }
+                        KeyCode::Char('r') => {
+                            app.process_sort_reversed = !app.process_sort_reversed;
+                            app.sort_processes();
+                        }
+                        KeyCode::Char('x') => app.request_kill(Signal::Term),
+                        KeyCode::Char('X') => app.request_kill(Signal::Kill),
+                        _ => {}
+                    }
+                    continue;
+                }
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Esc => {
                         return Ok(());
                     }
+                    // From the dashboard, the selected resource's own view
+                    KeyCode::Right | KeyCode::Enter | KeyCode::Char('l') if app.dashboard => {
+                        app.dashboard = false;
+                    }
+                    KeyCode::Right | KeyCode::Enter | KeyCode::Char('l')
+                        if app.selected_item == 2 =>
+                    {
+                        app.disk_focused = !app.disks_info.is_empty();
+                    }
+                    KeyCode::Right | KeyCode::Enter | KeyCode::Char('l')
+                        if app.selected_item == 3 =>
+                    {
+                        app.network_focused = !app.networks_info.is_empty();
+                    }
+                    KeyCode::Right | KeyCode::Enter | KeyCode::Char('l')
+                        if app.selected_item == 4 =>
+                    {
+                        app.process_focused = !app.processes.is_empty();
+                    }
+                    KeyCode::Char('p') => {
+                        app.cycle_pinned_interface();
+                    }
                     KeyCode::Down | KeyCode::Char('j') => {
                         app.next_item();
                     }
@@ -0,0 +1,276 @@
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cli::RecordFormat;
+use crate::App;
+
+// Appends one sample per tick to the --record file, flushed each time so a session that
+// ends abruptly still leaves everything up to its last tick
+pub struct Recorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    format: RecordFormat,
+}
+
+#[derive(Serialize)]
+struct Sample<'a> {
+    timestamp: String,
+    cpu_pct: u64,
+    cores_pct: &'a [f32],
+    memory: MemorySample,
+    disks: Vec<DiskSample<'a>>,
+    networks: Vec<NetworkSample<'a>>,
+}
+
+#[derive(Serialize)]
+struct MemorySample {
+    total: u64,
+    used: u64,
+    available: u64,
+    swap_total: u64,
+    swap_used: u64,
+}
+
+#[derive(Serialize)]
+struct DiskSample<'a> {
+    mount: &'a str,
+    total: u64,
+    available: u64,
+    // Missing where the kernel doesn't report I/O for the device
+    read_bps: Option<u64>,
+    write_bps: Option<u64>,
+    read_iops: Option<u64>,
+    write_iops: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct NetworkSample<'a> {
+    name: &'a str,
+    rx_bps: u64,
+    tx_bps: u64,
+}
+
+// UTC, to the millisecond, e.g. 2024-05-01T12:00:00.250Z
+fn utc_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's days_from_civil, inverted)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+impl<'a> Sample<'a> {
+    fn of(app: &'a App) -> Self {
+        Sample {
+            timestamp: utc_timestamp(SystemTime::now()),
+            cpu_pct: app.cpu_history.last().unwrap_or(0),
+            cores_pct: &app.cpu_cores,
+            memory: MemorySample {
+                total: app.mem_total,
+                used: app.mem_used,
+                available: app.mem_available,
+                swap_total: app.mem_swap_total,
+                swap_used: app.mem_swap_used,
+            },
+            disks: app
+                .disks_info
+                .iter()
+                .map(|d| DiskSample {
+                    mount: &d.mount,
+                    total: d.total,
+                    available: d.available,
+                    read_bps: d.io.map(|io| io.read_bps),
+                    write_bps: d.io.map(|io| io.write_bps),
+                    read_iops: d.io.map(|io| io.read_iops),
+                    write_iops: d.io.map(|io| io.write_iops),
+                })
+                .collect(),
+            networks: app
+                .networks_info
+                .iter()
+                .map(|(name, rx, tx, _)| NetworkSample {
+                    name,
+                    rx_bps: *rx,
+                    tx_bps: *tx,
+                })
+                .collect(),
+        }
+    }
+}
+
+// What --once --json prints: the object a JSON Lines recording has for each tick
+pub fn to_json(app: &App) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&Sample::of(app))
+}
+
+impl Recorder {
+    // Without a format, a .csv file gets CSV and anything else JSON Lines
+    pub fn open(path: &Path, format: Option<RecordFormat>) -> io::Result<Self> {
+        let format = format.unwrap_or_else(|| {
+            let csv = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+            if csv {
+                RecordFormat::Csv
+            } else {
+                RecordFormat::Jsonl
+            }
+        });
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let empty = file.metadata()?.len() == 0;
+        let mut recorder = Recorder {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+            format,
+        };
+        // Appending to an earlier recording keeps its header
+        if empty && format == RecordFormat::Csv {
+            writeln!(recorder.out, "timestamp,resource,name,metric,value")?;
+            recorder.out.flush()?;
+        }
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write(&mut self, app: &App) -> io::Result<()> {
+        let sample = Sample::of(app);
+        match self.format {
+            RecordFormat::Jsonl => {
+                serde_json::to_writer(&mut self.out, &sample)?;
+                writeln!(self.out)?;
+            }
+            RecordFormat::Csv => self.write_csv(&sample)?,
+        }
+        self.out.flush()
+    }
+
+    // One row per value, so disks and interfaces coming and going don't shift columns.
+    // A pivot table turns it back into one column per metric.
+    fn write_csv(&mut self, sample: &Sample) -> io::Result<()> {
+        let mut rows: Vec<(&str, String, &str, String)> = vec![(
+            "cpu",
+            String::new(),
+            "usage_pct",
+            sample.cpu_pct.to_string(),
+        )];
+        for (i, usage) in sample.cores_pct.iter().enumerate() {
+            rows.push((
+                "cpu",
+                format!("core {}", i),
+                "usage_pct",
+                format!("{:.1}", usage),
+            ));
+        }
+        let memory = &sample.memory;
+        for (metric, value) in [
+            ("total_bytes", memory.total),
+            ("used_bytes", memory.used),
+            ("available_bytes", memory.available),
+            ("swap_total_bytes", memory.swap_total),
+            ("swap_used_bytes", memory.swap_used),
+        ] {
+            rows.push(("memory", String::new(), metric, value.to_string()));
+        }
+        for disk in &sample.disks {
+            rows.push((
+                "disk",
+                disk.mount.to_string(),
+                "total_bytes",
+                disk.total.to_string(),
+            ));
+            rows.push((
+                "disk",
+                disk.mount.to_string(),
+                "available_bytes",
+                disk.available.to_string(),
+            ));
+            if let (Some(read), Some(write)) = (disk.read_bps, disk.write_bps) {
+                rows.push((
+                    "disk",
+                    disk.mount.to_string(),
+                    "read_bytes_per_sec",
+                    read.to_string(),
+                ));
+                rows.push((
+                    "disk",
+                    disk.mount.to_string(),
+                    "write_bytes_per_sec",
+                    write.to_string(),
+                ));
+            }
+            if let (Some(read), Some(write)) = (disk.read_iops, disk.write_iops) {
+                rows.push((
+                    "disk",
+                    disk.mount.to_string(),
+                    "read_ops_per_sec",
+                    read.to_string(),
+                ));
+                rows.push((
+                    "disk",
+                    disk.mount.to_string(),
+                    "write_ops_per_sec",
+                    write.to_string(),
+                ));
+            }
+        }
+        for net in &sample.networks {
+            rows.push((
+                "network",
+                net.name.to_string(),
+                "rx_bytes_per_sec",
+                net.rx_bps.to_string(),
+            ));
+            rows.push((
+                "network",
+                net.name.to_string(),
+                "tx_bytes_per_sec",
+                net.tx_bps.to_string(),
+            ));
+        }
+        for (resource, name, metric, value) in rows {
+            writeln!(
+                self.out,
+                "{},{},{},{},{}",
+                sample.timestamp,
+                resource,
+                csv_field(&name),
+                metric,
+                value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Mount points and interface names can hold commas or quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,195 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::collector::{spawn_sampling, Collector, Command, Sample, Update};
+
+// How long a --connect fetch may take before the agent counts as unreachable
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// What --serve reads of a request line and headers, and how many clients it answers at once;
+// the read timeout is per read, so without these a client trickling bytes could tie up memory
+// and threads for as long as it liked
+const MAX_REQUEST_HEAD: u64 = 8 * 1024;
+const MAX_CONNECTIONS: usize = 32;
+
+// One of the MAX_CONNECTIONS, given back when the connection's thread is done with it
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn take(active: &Arc<AtomicUsize>) -> Option<Self> {
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(ConnectionSlot(Arc::clone(active)))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// --serve: samples this machine on the usual clock, without a terminal, and answers
+// GET /metrics (or /) with the latest sample as JSON until interrupted
+pub fn serve(addr: &str, collector: Collector, interval: Duration) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    eprintln!(
+        "Serving metrics on http://{}/metrics (Ctrl-C to stop)",
+        listener.local_addr()?
+    );
+
+    let latest: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let (_commands, updates) = collector.spawn(interval);
+    let writer = Arc::clone(&latest);
+    thread::spawn(move || {
+        for sample in updates.into_iter().flatten() {
+            if let Ok(json) = serde_json::to_string(&sample) {
+                *writer.lock().unwrap() = Some(json);
+            }
+        }
+    });
+
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        // Over the limit the connection is just closed; the client can try again
+        let Some(slot) = ConnectionSlot::take(&active) else {
+            continue;
+        };
+        let latest = Arc::clone(&latest);
+        thread::spawn(move || {
+            let _slot = slot;
+            // A client that hangs up halfway is its own problem
+            let _ = respond(stream, &latest);
+        });
+    }
+    Ok(())
+}
+
+fn respond(mut stream: TcpStream, latest: &Mutex<Option<String>>) -> io::Result<()> {
+    stream.set_read_timeout(Some(FETCH_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_HEAD));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers say nothing this server cares about
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = path.split('?').next().unwrap_or(path);
+    let latest = latest.lock().unwrap().clone();
+    let (status, content_type, body) = match (method, path, latest) {
+        ("GET", "/" | "/metrics", Some(json)) => ("200 OK", "application/json", json),
+        ("GET", "/" | "/metrics", None) => (
+            "503 Service Unavailable",
+            "text/plain",
+            "No sample yet\n".to_string(),
+        ),
+        ("GET", _, _) => ("404 Not Found", "text/plain", "Try /metrics\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Only GET is supported\n".to_string(),
+        ),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+// --connect: where a --serve agent answers, from a URL like http://host:9100/metrics
+pub struct Remote {
+    host: String, // host:port, as the Host header wants it
+    path: String,
+}
+
+impl Remote {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("Only http:// URLs can be connected to, not '{}'", url))?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/metrics"),
+        };
+        if host.is_empty() {
+            return Err(format!("No host in '{}'", url));
+        }
+        // Port 80 where none is given, as a browser would
+        let host = if host
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+        {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+        Ok(Remote {
+            host,
+            path: path.to_string(),
+        })
+    }
+
+    // host:port, for showing what the views are of
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    // Fetches a sample once per interval in place of the collector. The agent samples on
+    // its own clock, so rates cover its interval rather than this one. Socket counts stay
+    // on the agent's side.
+    pub fn spawn(self, interval: Duration) -> (Sender<Command>, Receiver<Update>) {
+        spawn_sampling(interval, move |_| self.fetch())
+    }
+
+    fn fetch(&self) -> Result<Sample, String> {
+        let addr = self
+            .host
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or_else(|| format!("{} has no address", self.host))?;
+        let mut stream =
+            TcpStream::connect_timeout(&addr, FETCH_TIMEOUT).map_err(|e| e.to_string())?;
+        stream
+            .set_read_timeout(Some(FETCH_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+            self.path, self.host
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(|e| e.to_string())?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("malformed response")?;
+        let status = head.lines().next().unwrap_or("");
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(status.to_string());
+        }
+        serde_json::from_str(body).map_err(|e| e.to_string())
+    }
+}
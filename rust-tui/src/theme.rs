@@ -0,0 +1,146 @@
+use clap::ValueEnum;
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    Dark,
+    Light,
+    Solarized,
+    Monochrome,
+}
+
+impl ThemeName {
+    // The order 't' cycles through
+    pub fn next(self) -> Self {
+        match self {
+            ThemeName::Dark => ThemeName::Light,
+            ThemeName::Light => ThemeName::Solarized,
+            ThemeName::Solarized => ThemeName::Monochrome,
+            ThemeName::Monochrome => ThemeName::Dark,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeName::Dark => "dark",
+            ThemeName::Light => "light",
+            ThemeName::Solarized => "solarized",
+            ThemeName::Monochrome => "monochrome",
+        }
+    }
+
+    pub fn theme(self) -> &'static Theme {
+        match self {
+            ThemeName::Dark => &DARK,
+            ThemeName::Light => &LIGHT,
+            ThemeName::Solarized => &SOLARIZED,
+            ThemeName::Monochrome => &MONOCHROME,
+        }
+    }
+}
+
+// Colors by what they're used for. The background and text colors are painted under
+// everything, so a theme looks the same whatever the terminal's own colors are;
+// Color::Reset keeps the terminal's.
+pub struct Theme {
+    pub name: ThemeName,
+    pub background: Color,
+    pub text: Color,
+    pub border: Color,
+    pub header: Color,
+    pub selected: Style,
+    pub dim: Color,
+    pub ok: Color,
+    // Errors, and readings past their warning threshold
+    pub alert: Color,
+    pub cpu: Color,
+    pub memory: Color,
+    pub disk: Color,
+    pub received: Color,
+    pub sent: Color,
+    pub sensor: Color,
+}
+
+impl Theme {
+    pub fn base(&self) -> Style {
+        Style::new().fg(self.text).bg(self.background)
+    }
+}
+
+pub static DARK: Theme = Theme {
+    name: ThemeName::Dark,
+    background: Color::Reset,
+    text: Color::White,
+    border: Color::Cyan,
+    header: Color::Cyan,
+    selected: Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    dim: Color::DarkGray,
+    ok: Color::Green,
+    alert: Color::Red,
+    cpu: Color::Magenta,
+    memory: Color::Green,
+    disk: Color::Yellow,
+    received: Color::Green,
+    sent: Color::Blue,
+    sensor: Color::LightBlue,
+};
+
+pub static LIGHT: Theme = Theme {
+    name: ThemeName::Light,
+    background: Color::White,
+    text: Color::Black,
+    border: Color::Blue,
+    header: Color::Blue,
+    selected: Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+    dim: Color::Gray,
+    ok: Color::Rgb(0, 128, 0),
+    alert: Color::Red,
+    cpu: Color::Magenta,
+    memory: Color::Rgb(0, 128, 0),
+    disk: Color::Rgb(176, 112, 0),
+    received: Color::Rgb(0, 128, 0),
+    sent: Color::Blue,
+    sensor: Color::Rgb(0, 112, 160),
+};
+
+// Ethan Schoonover's Solarized, dark variant
+pub static SOLARIZED: Theme = Theme {
+    name: ThemeName::Solarized,
+    background: Color::Rgb(0, 43, 54),
+    text: Color::Rgb(131, 148, 150),
+    border: Color::Rgb(38, 139, 210),
+    header: Color::Rgb(42, 161, 152),
+    selected: Style::new()
+        .fg(Color::Rgb(181, 137, 0))
+        .add_modifier(Modifier::BOLD),
+    dim: Color::Rgb(88, 110, 117),
+    ok: Color::Rgb(133, 153, 0),
+    alert: Color::Rgb(220, 50, 47),
+    cpu: Color::Rgb(211, 54, 130),
+    memory: Color::Rgb(133, 153, 0),
+    disk: Color::Rgb(181, 137, 0),
+    received: Color::Rgb(133, 153, 0),
+    sent: Color::Rgb(38, 139, 210),
+    sensor: Color::Rgb(108, 113, 196),
+};
+
+// The terminal's own colors only; selection is shown in reverse video
+pub static MONOCHROME: Theme = Theme {
+    name: ThemeName::Monochrome,
+    background: Color::Reset,
+    text: Color::Reset,
+    border: Color::Reset,
+    header: Color::Reset,
+    selected: Style::new().add_modifier(Modifier::REVERSED.union(Modifier::BOLD)),
+    dim: Color::Reset,
+    ok: Color::Reset,
+    alert: Color::Reset,
+    cpu: Color::Reset,
+    memory: Color::Reset,
+    disk: Color::Reset,
+    received: Color::Reset,
+    sent: Color::Reset,
+    sensor: Color::Reset,
+};
@@ -1,31 +1,72 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, Paragraph, Sparkline},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, Paragraph, Row,
+        Sparkline, Table, TableState,
+    },
     Frame,
 };
+use std::time::Duration;
+use sysinfo::Signal;
 
-use crate::App;
+use crate::config::ChartScale;
+use crate::{
+    App, BatteryInfo, ChargeState, DiskInfo, KillRequest, ProcessInfo, ProcessSort, SensorInfo,
+};
+
+// Rows taken by each sensor's chart in the Sensors view, borders included
+const SENSOR_HEIGHT: u16 = 4;
+// Rows taken by each core's chart in the CPU view: a title line over two rows of bars,
+// and the narrowest a column of them may get
+const CORE_HEIGHT: u16 = 3;
+const CORE_MIN_WIDTH: u16 = 16;
+// A discharging battery's chart turns to the alert color at or below this charge
+const LOW_BATTERY_PCT: f32 = 10.0;
+
+pub fn signal_name(signal: Signal) -> &'static str {
+    match signal {
+        Signal::Term => "SIGTERM",
+        Signal::Kill => "SIGKILL",
+        _ => "signal",
+    }
+}
 
 pub struct UIRenderer;
 
 impl UIRenderer {
     pub fn render(f: &mut Frame, app: &App) {
+        // The theme's background and text colors, under whatever the panels draw
+        f.render_widget(Block::default().style(app.theme.base()), f.area());
         Self::render_layout(f, app);
+        if app.paused {
+            Self::render_paused(f, app, f.area());
+        }
+        if app.show_help {
+            Self::render_help(f, app, f.area());
+        }
     }
 
     fn render_layout(f: &mut Frame, app: &App) {
-        let chunks = Self::create_layout(f);
+        let chunks = Self::create_layout(f.area());
 
+        Self::render_header(f, app, chunks[2]);
         Self::render_left_panel(f, app, chunks[0]);
         Self::render_central_panel(f, app, chunks[1]);
     }
 
-    fn create_layout(f: &mut Frame) -> Vec<Rect> {
-        Layout::default()
-            .direction(Direction::Horizontal)
+    // The left and central panels, then the header bar above them; also used to tell what
+    // the mouse is pointing at
+    pub fn create_layout(area: Rect) -> Vec<Rect> {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
             .margin(1)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        let mut chunks = Layout::default()
+            .direction(Direction::Horizontal)
             .constraints(
                 [
                     Constraint::Percentage(30), // Left panel
@@ -33,129 +74,652 @@ impl UIRenderer {
                 ]
                 .as_ref(),
             )
-            .split(f.area())
-            .to_vec()
+            .split(rows[1])
+            .to_vec();
+        chunks.push(rows[0]);
+        chunks
     }
 
-    fn render_left_panel(f: &mut Frame, app: &App, area: Rect) {
-        let panel_block = Block::default()
-            .title(" Computer Resources ")
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::Cyan));
+    // Host name, OS and kernel, uptime and load averages of the machine being shown
+    fn render_header(f: &mut Frame, app: &App, area: Rect) {
+        let t = app.strings;
+        let Some(host) = &app.host else {
+            return;
+        };
+        let mut parts = Vec::new();
+        if let Some(name) = &host.name {
+            parts.push(name.clone());
+        }
+        match (&host.os, &host.kernel) {
+            (Some(os), Some(kernel)) => parts.push(format!("{} ({} {})", os, t.kernel, kernel)),
+            (Some(os), None) => parts.push(os.clone()),
+            (None, Some(kernel)) => parts.push(format!("{} {}", t.kernel, kernel)),
+            (None, None) => {}
+        }
+        parts.push(format!("{} {}", t.uptime, Self::format_uptime(host.uptime)));
+        let [one, five, fifteen] = host.load_average;
+        parts.push(format!(
+            "{} {:.2} {:.2} {:.2}",
+            t.load_average, one, five, fifteen
+        ));
+        let header = Paragraph::new(Span::styled(
+            format!(" {}", parts.join("  │  ")),
+            Style::default().fg(app.theme.header),
+        ));
+        f.render_widget(header, area);
+    }
 
-        // Show CPU, Memory, Disk with current percentages
-        let cpu = app.cpu_history.last().cloned().unwrap_or(0);
-        let mem = app.mem_history.last().cloned().unwrap_or(0);
+    // The resource listed at a screen position in the left panel, if any
+    pub fn resource_at(screen: Rect, app: &App, column: u16, row: u16) -> Option<usize> {
+        let inner = Block::default()
+            .borders(Borders::ALL)
+            .inner(Self::create_layout(screen)[0]);
+        let inside =
+            column >= inner.x && column < inner.right() && row >= inner.y && row < inner.bottom();
+        let index = row.checked_sub(inner.y)? as usize;
+        (inside && index < app.items.len()).then_some(index)
+    }
 
-        let disk = app.disk_history.last().cloned().unwrap_or(0);
+    // How many cores the per-core charts can be scrolled past, for the terminal's size
+    pub fn core_scroll_limit(screen: Rect, app: &App) -> usize {
+        let central = Self::create_layout(screen)[1];
+        let (rows, columns) = Self::core_grid(Self::core_grid_area(central), app.cpu_cores.len());
+        app.cpu_cores.len().saturating_sub(rows * columns)
+    }
 
-        // Determine network summary (pick first active interface if any)
-        let net_summary = if let Some((_name, rx, tx, kind)) = app.networks_info.first() {
-            format!("{}: {} / {}", kind, Self::format_bytes(*rx), Self::format_bytes(*tx))
+    fn render_left_panel(f: &mut Frame, app: &App, area: Rect) {
+        let t = app.strings;
+        let theme = app.theme;
+        let title_style = if app.alert_flash() {
+            Style::default()
+                .fg(theme.alert)
+                .add_modifier(Modifier::REVERSED)
         } else {
-            "No network".to_string()
+            Style::default()
         };
+        let panel_block = Block::default()
+            .title(Span::styled(t.computer_resources, title_style))
+            .borders(Borders::ALL)
+            .style(Style::default().fg(theme.border));
 
-        let lines = vec![
-            Line::from(vec![Span::styled(
-                format!("CPU: {}%", cpu),
-                if app.selected_item == 0 {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(Color::White)
-                },
-            )]),
-            Line::from(vec![Span::styled(
-                format!("Memory: {}%", mem),
-                if app.selected_item == 1 {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        let mut lines: Vec<Line> = (0..app.items.len())
+            .map(|i| {
+                let style = if app.selected_item == i {
+                    theme.selected
                 } else {
-                    Style::default().fg(Color::White)
-                },
-            )]),
-            Line::from(vec![Span::styled(
-                format!("Disk: {}%  Avail: {}", disk, Self::format_bytes(app.disk_available)),
-                if app.selected_item == 2 {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(Color::White)
-                },
-            )]),
-            Line::from(vec![Span::styled(
-                format!("Network: {}", net_summary),
-                if app.selected_item == 3 {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                    Style::default().fg(theme.text)
+                };
+                let style = if app.alerts.is_active(i) {
+                    style.fg(theme.alert)
                 } else {
-                    Style::default().fg(Color::White)
-                },
-            )]),
-        ];
+                    style
+                };
+                Line::from(vec![Span::styled(Self::resource_line(app, i), style)])
+            })
+            .collect();
+
+        lines.push(Line::from(""));
+        lines.push(match &app.copy_status {
+            Some((Ok(text), _)) => Line::from(Span::styled(
+                format!("{}: {}", t.copied, text),
+                Style::default().fg(theme.ok),
+            )),
+            Some((Err(e), _)) => Line::from(Span::styled(
+                format!("{}: {}", t.clipboard_unavailable, e),
+                Style::default().fg(theme.alert),
+            )),
+            None => Line::from(Span::styled(t.copy_hint, Style::default().fg(theme.dim))),
+        });
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{}: {} (+/-)",
+                t.refresh,
+                Self::format_interval(app.sample_interval)
+            ),
+            Style::default().fg(theme.dim),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("{}: {} (t)", t.theme, theme.name.label()),
+            Style::default().fg(theme.dim),
+        )));
+        lines.push(Line::from(Span::styled(
+            t.help_hint,
+            Style::default().fg(theme.dim),
+        )));
+        match &app.hide_status {
+            Some((Ok(name), _)) => lines.push(Line::from(Span::styled(
+                format!("{}: {}", t.hidden, name),
+                Style::default().fg(theme.ok),
+            ))),
+            Some((Err(e), _)) => lines.push(Line::from(Span::styled(
+                format!("{}: {}", t.hidden_not_saved, e),
+                Style::default().fg(theme.alert),
+            ))),
+            None => {}
+        }
+        match &app.record_status {
+            Some(Ok(path)) => lines.push(Line::from(Span::styled(
+                format!("{} {}", t.recording, path),
+                Style::default().fg(theme.alert),
+            ))),
+            Some(Err(e)) => lines.push(Line::from(Span::styled(
+                format!("{}: {}", t.recording_stopped, e),
+                Style::default().fg(theme.alert),
+            ))),
+            None => {}
+        }
+        if let Some(host) = &app.remote {
+            match &app.remote_error {
+                Some(e) => {
+                    let style = Style::default().fg(theme.alert);
+                    lines.push(Line::from(Span::styled(
+                        format!("{}: {}", t.remote, host),
+                        style,
+                    )));
+                    lines.push(Line::from(Span::styled(
+                        format!("{}: {}", t.remote_unreachable, e),
+                        style,
+                    )));
+                }
+                None => lines.push(Line::from(Span::styled(
+                    format!("{}: {}", t.remote, host),
+                    Style::default().fg(theme.dim),
+                ))),
+            }
+        }
 
         let content = Paragraph::new(lines).block(panel_block);
         f.render_widget(content, area);
     }
 
+    // A charge history per battery, above a status line for each battery and the AC adapter
+    fn render_power_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
+        let t = app.strings;
+        let theme = app.theme;
+        let status_lines = app.batteries.len() as u16 + u16::from(app.on_ac.is_some());
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(status_lines.max(1) + 2),
+            ])
+            .split(area);
+
+        if app.batteries.is_empty() {
+            let empty = Paragraph::new(t.no_battery)
+                .block(Block::default().title(t.batteries).borders(Borders::ALL));
+            f.render_widget(empty, chunks[0]);
+        } else {
+            let charts = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![
+                    Constraint::Ratio(1, app.batteries.len() as u32);
+                    app.batteries.len()
+                ])
+                .split(chunks[0]);
+            for (battery, chart) in app.batteries.iter().zip(charts.iter()) {
+                let history = app.battery_history.get(&battery.name).map_or(&[][..], |h| {
+                    h.latest(chart.width.saturating_sub(2) as usize)
+                });
+                let color = if battery.state == ChargeState::Discharging
+                    && battery.percent <= LOW_BATTERY_PCT
+                {
+                    theme.alert
+                } else {
+                    theme.ok
+                };
+                let spark = Sparkline::default()
+                    .block(
+                        Block::default()
+                            .title(format!("{} {}", battery.name, t.battery_history))
+                            .borders(Borders::ALL),
+                    )
+                    .data(history)
+                    .max(100)
+                    .style(Style::default().fg(color));
+                f.render_widget(spark, *chart);
+            }
+        }
+
+        let mut lines: Vec<Line> = app
+            .batteries
+            .iter()
+            .map(|b| Line::from(Self::battery_line(app, b)))
+            .collect();
+        if let Some(on_ac) = app.on_ac {
+            let state = if on_ac { t.connected } else { t.disconnected };
+            lines.push(Line::from(format!("{}: {}", t.ac_adapter, state)));
+        }
+        if lines.is_empty() {
+            lines.push(Line::from(t.no_battery));
+        }
+        let status =
+            Paragraph::new(lines).block(Block::default().title(t.power).borders(Borders::ALL));
+        f.render_widget(status, chunks[1]);
+    }
+
+    // A resource's usual color, or the alert color while it's over its threshold
+    fn alert_color(app: &App, item: usize, color: Color) -> Color {
+        if app.alerts.is_active(item) {
+            app.theme.alert
+        } else {
+            color
+        }
+    }
+
+    // The left-panel summary for one resource: CPU, Memory, Disk, Network, Processes, Sensors
+    fn resource_line(app: &App, index: usize) -> String {
+        let t = app.strings;
+        match index {
+            0 => format!("{}: {}%", t.cpu, app.cpu_history.last().unwrap_or(0)),
+            1 => format!("{}: {}%", t.memory, app.mem_history.last().unwrap_or(0)),
+            2 => format!(
+                "{}: {}%  {}: {}",
+                t.disk,
+                app.disk_history.last().unwrap_or(0),
+                t.avail,
+                Self::format_bytes(app.disk_available)
+            ),
+            4 => format!("{}: {}", t.processes, app.processes.len()),
+            // The hottest reading
+            5 => match app
+                .sensors
+                .iter()
+                .max_by(|a, b| a.temperature.total_cmp(&b.temperature))
+            {
+                Some(sensor) => format!("{}: {:.0}°C", t.sensors, sensor.temperature),
+                None => format!("{}: {}", t.sensors, t.no_sensors),
+            },
+            // The first battery, or just the adapter on a machine without one
+            6 => match (app.batteries.first(), app.on_ac) {
+                (Some(battery), _) => format!(
+                    "{}: {:.0}%  {}",
+                    t.power,
+                    battery.percent,
+                    Self::charge_state(app, battery.state)
+                ),
+                (None, Some(true)) => format!("{}: {} {}", t.power, t.ac_adapter, t.connected),
+                (None, _) => format!("{}: {}", t.power, t.no_battery),
+            },
+            _ => {
+                // Summarise the first interface, which is the pinned one if any
+                let net_summary = if let Some((_name, rx, tx, kind)) = app.networks_info.first() {
+                    format!(
+                        "{}: {} / {}",
+                        kind,
+                        Self::format_bytes(*rx),
+                        Self::format_bytes(*tx)
+                    )
+                } else {
+                    t.no_network.to_string()
+                };
+                format!("{}: {}", t.network, net_summary)
+            }
+        }
+    }
+
+    fn interface_line((name, rx, tx, kind): &(String, u64, u64, String)) -> String {
+        format!(
+            "{} ({})  ↓ {}  ↑ {}",
+            name,
+            kind,
+            Self::format_bytes(*rx),
+            Self::format_bytes(*tx)
+        )
+    }
+
+    fn mount_line(app: &App, disk: &DiskInfo) -> String {
+        let t = app.strings;
+        let used = disk.total.saturating_sub(disk.available);
+        format!(
+            "{} ({}, {})  {}: {}  {}: {}  {}: {}",
+            disk.mount,
+            disk.device,
+            disk.fs_type,
+            t.used,
+            Self::format_bytes(used),
+            t.avail,
+            Self::format_bytes(disk.available),
+            t.total,
+            Self::format_bytes(disk.total)
+        )
+    }
+
+    fn sensor_line(app: &App, sensor: &SensorInfo) -> String {
+        let t = app.strings;
+        let critical = match sensor.critical {
+            Some(critical) => format!(", {}: {:.0}°C", t.critical, critical),
+            None => String::new(),
+        };
+        format!(
+            "{}  {:.1}°C  ({}: {:.0}°C{})",
+            sensor.label, sensor.temperature, t.max, sensor.max, critical
+        )
+    }
+
+    fn charge_state(app: &App, state: ChargeState) -> &'static str {
+        let t = app.strings;
+        match state {
+            ChargeState::Charging => t.charging,
+            ChargeState::Discharging => t.discharging,
+            ChargeState::Full => t.full,
+            ChargeState::NotCharging => t.not_charging,
+            ChargeState::Unknown => t.unknown,
+        }
+    }
+
+    // e.g. "BAT0  85%  Discharging  12.3 W  2h 15m left"
+    fn battery_line(app: &App, battery: &BatteryInfo) -> String {
+        let t = app.strings;
+        let mut line = format!(
+            "{}  {:.0}%  {}",
+            battery.name,
+            battery.percent,
+            Self::charge_state(app, battery.state)
+        );
+        if let Some(rate) = battery.rate {
+            line.push_str(&format!("  {:.1} W", rate));
+        }
+        if let Some(left) = battery.time_left {
+            let until = if battery.state == ChargeState::Charging {
+                t.until_full
+            } else {
+                t.until_empty
+            };
+            line.push_str(&format!("  {} {}", Self::format_duration(left), until));
+        }
+        line
+    }
+
+    fn process_line(process: &ProcessInfo) -> String {
+        format!(
+            "{} {}  {:.1}%  {}",
+            process.pid,
+            process.name,
+            process.cpu,
+            Self::format_bytes(process.memory)
+        )
+    }
+
+    // Plain text for what's selected: the focused mount or process, the first (pinned)
+    // interface in the Network view, otherwise the resource's summary line
+    pub fn selection_text(app: &App) -> String {
+        if app.disk_focused {
+            if let Some(disk) = app.disks_info.get(app.disk_selected) {
+                return Self::mount_line(app, disk);
+            }
+        }
+        if app.process_focused {
+            if let Some(process) = app.processes.get(app.process_selected) {
+                return Self::process_line(process);
+            }
+        }
+        if app.selected_item == 3 {
+            if let Some(iface) = app.networks_info.get(app.charted_interface()) {
+                return Self::interface_line(iface);
+            }
+        }
+        Self::resource_line(app, app.selected_item)
+    }
+
+    // What --once prints: the CPU, memory, disk and network summary lines, each followed by
+    // its mounts or interfaces
+    pub fn summary_text(app: &App) -> String {
+        let mut lines = Vec::new();
+        for index in 0..4 {
+            lines.push(Self::resource_line(app, index));
+            match index {
+                2 => lines.extend(
+                    app.disks_info
+                        .iter()
+                        .map(|disk| format!("  {}", Self::mount_line(app, disk))),
+                ),
+                3 => lines.extend(
+                    app.networks_info
+                        .iter()
+                        .map(|iface| format!("  {}", Self::interface_line(iface))),
+                ),
+                _ => {}
+            }
+        }
+        lines.join("\n")
+    }
+
     fn render_central_panel(f: &mut Frame, app: &App, area: Rect) {
+        let theme = app.theme;
         let panel_block = Block::default()
-            .title(" Resource Graphs ")
+            .title(app.strings.resource_graphs)
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::Cyan));
+            .style(Style::default().fg(theme.border));
+        if app.dashboard {
+            Self::render_dashboard(f, app, area, panel_block);
+            return;
+        }
         // Render a detailed view for the selected resource using more space
         match app.selected_item {
             0 => Self::render_cpu_view(f, app, area, panel_block),
             1 => Self::render_mem_view(f, app, area, panel_block),
             2 => Self::render_disk_view(f, app, area, panel_block),
             3 => Self::render_network_view(f, app, area, panel_block),
+            4 => Self::render_process_view(f, app, area, panel_block),
+            5 => Self::render_sensor_view(f, app, area, panel_block),
+            6 => Self::render_power_view(f, app, area, panel_block),
             _ => {
-                let empty = Paragraph::new("No resource selected").block(panel_block);
+                let empty = Paragraph::new(app.strings.no_resource_selected).block(panel_block);
                 f.render_widget(empty, area);
             }
         }
     }
 
+    // CPU, memory, disk and network in a 2x2 grid, each drawn by its own view
+    fn render_dashboard(f: &mut Frame, app: &App, area: Rect, panel_block: Block) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let halves = |row: Rect| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(row)
+        };
+        let (top, bottom) = (halves(rows[0]), halves(rows[1]));
+        Self::render_cpu_view(f, app, top[0], panel_block.clone());
+        Self::render_mem_view(f, app, top[1], panel_block.clone());
+        Self::render_disk_view(f, app, bottom[0], panel_block.clone());
+        Self::render_network_view(f, app, bottom[1], panel_block);
+    }
+
     fn render_cpu_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
-        // CPU detailed: big sparkline + gauge + per-core list
-        let chunks = Layout::default()
+        let t = app.strings;
+        let theme = app.theme;
+        // CPU detailed: big sparkline + gauge + processor info + per-core charts
+        let chunks = Self::cpu_layout(area);
+
+        let spark = Sparkline::default()
+            .block(Block::default().title(t.cpu_history).borders(Borders::ALL))
+            .data(
+                app.cpu_history
+                    .latest(chunks[0].width.saturating_sub(2) as usize),
+            )
+            .style(Style::default().fg(theme.cpu));
+        f.render_widget(spark, chunks[0]);
+
+        let current = app.cpu_history.last().unwrap_or(0) as f64 / 100.0;
+        let gauge = Gauge::default()
+            .block(Block::default().title(t.cpu_usage).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Self::alert_color(app, 0, theme.cpu)))
+            .ratio(current)
+            .label(format!("{}%", app.cpu_history.last().unwrap_or(0)));
+        f.render_widget(gauge, chunks[1]);
+
+        let info = Paragraph::new(Self::cpu_info_lines(app))
+            .block(Block::default().title(t.processor).borders(Borders::ALL));
+        f.render_widget(info, chunks[2]);
+
+        f.render_widget(
+            Block::default()
+                .title(t.per_core_usage)
+                .borders(Borders::ALL),
+            chunks[3],
+        );
+        Self::render_core_grid(f, app, Self::core_grid_area(area));
+    }
+
+    fn cpu_layout(area: Rect) -> Vec<Rect> {
+        Layout::default()
             .direction(Direction::Vertical)
             .margin(0)
             .constraints([
                 Constraint::Length(6),
                 Constraint::Length(3),
+                Constraint::Length(4),
                 Constraint::Min(1),
             ])
-            .split(area);
+            .split(area)
+            .to_vec()
+    }
 
-        let cpu_data: Vec<u64> = app.cpu_history.clone();
-        let spark = Sparkline::default()
-            .block(Block::default().title("CPU % (history)").borders(Borders::ALL))
-            .data(&cpu_data)
-            .style(Style::default().fg(Color::Magenta));
-        f.render_widget(spark, chunks[0]);
+    // Inside the per-core block of the CPU view
+    fn core_grid_area(central: Rect) -> Rect {
+        Block::default()
+            .borders(Borders::ALL)
+            .inner(Self::cpu_layout(central)[3])
+    }
 
-        let current = app.cpu_history.last().cloned().unwrap_or(0) as f64 / 100.0;
-        let gauge = Gauge::default()
-            .block(Block::default().title("CPU Usage").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Magenta))
-            .ratio(current)
-            .label(format!("{}%", app.cpu_history.last().cloned().unwrap_or(0)));
-        f.render_widget(gauge, chunks[1]);
+    // The model and core counts, then the slowest, average and fastest core right now, e.g.
+    // "Frequency: min / avg / max 0.80 / 2.10 / 3.40 GHz (hardware 0.40-4.70 GHz)"
+    fn cpu_info_lines(app: &App) -> Vec<Line<'static>> {
+        let t = app.strings;
+        let Some(cpu) = &app.cpu_info else {
+            return Vec::new();
+        };
+        let logical = cpu.frequencies.len();
+        let cores = match cpu.physical_cores {
+            Some(physical) => format!("{} {}, {} {}", physical, t.cores, logical, t.threads),
+            None => format!("{} {}", logical, t.threads),
+        };
+        let model = if cpu.model.is_empty() {
+            t.unknown
+        } else {
+            cpu.model.as_str()
+        };
+        let mut lines = vec![Line::from(format!("{}  ·  {}", model, cores))];
 
-        // Per-core CPU usage
-        let core_lines: Vec<Line> = app
-            .cpu_cores
+        // Virtual machines often report no frequency at all
+        let known: Vec<u64> = cpu
+            .frequencies
             .iter()
-            .enumerate()
-            .map(|(idx, usage)| {
-                Line::from(format!("  Core {}: {:.1}%", idx, usage))
-            })
+            .copied()
+            .filter(|mhz| *mhz > 0)
             .collect();
+        if let (Some(min), Some(max)) = (known.iter().min(), known.iter().max()) {
+            let avg = known.iter().sum::<u64>() / known.len() as u64;
+            let ghz = |mhz: u64| mhz as f64 / 1000.0;
+            let range = match cpu.frequency_range {
+                Some((low, high)) => {
+                    format!(" ({} {:.2}-{:.2} GHz)", t.hardware, ghz(low), ghz(high))
+                }
+                None => String::new(),
+            };
+            lines.push(Line::from(format!(
+                "{}: {} {:.2} / {:.2} / {:.2} GHz{}",
+                t.frequency,
+                t.frequency_summary,
+                ghz(*min),
+                ghz(avg),
+                ghz(*max),
+                range
+            )));
+        }
+        lines
+    }
 
-        let info = Paragraph::new(core_lines)
-            .block(Block::default().title("Per-Core Usage").borders(Borders::ALL));
-        f.render_widget(info, chunks[2]);
+    fn format_ghz(mhz: u64) -> String {
+        format!("{:.2} GHz", mhz as f64 / 1000.0)
+    }
+
+    // Rows and columns of per-core charts that fit. When they can't all fit, the bottom
+    // line is left for listing the rest.
+    fn core_grid(area: Rect, count: usize) -> (usize, usize) {
+        let max_columns = (area.width / CORE_MIN_WIDTH).max(1) as usize;
+        let mut rows = (area.height / CORE_HEIGHT).max(1) as usize;
+        if count > rows * max_columns {
+            rows = (area.height.saturating_sub(1) / CORE_HEIGHT).max(1) as usize;
+        }
+        (rows, count.div_ceil(rows).clamp(1, max_columns))
+    }
+
+    // A small history chart per core, filled in column by column from the scroll position.
+    // Cores past the last chart are listed as plain percentages.
+    fn render_core_grid(f: &mut Frame, app: &App, area: Rect) {
+        let t = app.strings;
+        let theme = app.theme;
+        let count = app.cpu_cores.len();
+        let (rows, columns) = Self::core_grid(area, count);
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+            .split(area);
+
+        let start = app.core_scroll.min(count.saturating_sub(rows * columns));
+        let end = count.min(start + rows * columns);
+        for (slot, (idx, usage)) in app
+            .cpu_cores
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(end - start)
+            .enumerate()
+        {
+            let column = column_areas[slot / rows];
+            let cell = Rect {
+                x: column.x,
+                y: column.y + (slot % rows) as u16 * CORE_HEIGHT,
+                width: column.width.saturating_sub(1),
+                height: CORE_HEIGHT.min(column.height),
+            };
+            // The most recent samples that fit, so the chart ends at the current reading
+            let shown = app
+                .core_history
+                .get(idx)
+                .map_or(&[][..], |h| h.latest(cell.width as usize));
+            let frequency = match app
+                .cpu_info
+                .as_ref()
+                .and_then(|cpu| cpu.frequencies.get(idx))
+            {
+                Some(mhz) if *mhz > 0 => format!(" {}", Self::format_ghz(*mhz)),
+                _ => String::new(),
+            };
+            let spark = Sparkline::default()
+                .block(
+                    Block::default()
+                        .title(format!("{} {}: {:.1}%{}", t.core, idx, usage, frequency)),
+                )
+                .data(shown)
+                .max(100)
+                .style(Style::default().fg(theme.cpu));
+            f.render_widget(spark, cell);
+        }
+        if end < count {
+            let rest: Vec<String> = app.cpu_cores[end..]
+                .iter()
+                .enumerate()
+                .map(|(i, usage)| format!("{} {}: {:.0}%", t.core, end + i, usage))
+                .collect();
+            let last_row = Rect {
+                y: area.y + area.height.saturating_sub(1),
+                height: 1.min(area.height),
+                ..area
+            };
+            f.render_widget(Paragraph::new(rest.join("  ")), last_row);
+        }
     }
 
     fn render_mem_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
+        let t = app.strings;
+        let theme = app.theme;
         // Memory detailed: sparkline + gauge + breakdown
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -167,34 +731,58 @@ impl UIRenderer {
             ])
             .split(area);
 
-        let mem_data: Vec<u64> = app.mem_history.clone();
         let spark = Sparkline::default()
-            .block(Block::default().title("Memory % (history)").borders(Borders::ALL))
-            .data(&mem_data)
-            .style(Style::default().fg(Color::Green));
+            .block(
+                Block::default()
+                    .title(t.memory_history)
+                    .borders(Borders::ALL),
+            )
+            .data(
+                app.mem_history
+                    .latest(chunks[0].width.saturating_sub(2) as usize),
+            )
+            .style(Style::default().fg(theme.memory));
         f.render_widget(spark, chunks[0]);
 
-        let current = app.mem_history.last().cloned().unwrap_or(0) as f64 / 100.0;
+        let current = app.mem_history.last().unwrap_or(0) as f64 / 100.0;
         let gauge = Gauge::default()
-            .block(Block::default().title("Memory Usage").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Green))
+            .block(Block::default().title(t.memory_usage).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Self::alert_color(app, 1, theme.memory)))
             .ratio(current)
-            .label(format!("{}%", app.mem_history.last().cloned().unwrap_or(0)));
+            .label(format!("{}%", app.mem_history.last().unwrap_or(0)));
         f.render_widget(gauge, chunks[1]);
 
         // Memory breakdown
         let info = Paragraph::new(vec![
-            Line::from(format!("Total: {}", Self::format_bytes(app.mem_total))),
-            Line::from(format!("Used:  {}", Self::format_bytes(app.mem_used))),
-            Line::from(format!("Avail: {}", Self::format_bytes(app.mem_available))),
-            Line::from(format!("Swap Total: {}", Self::format_bytes(app.mem_swap_total))),
-            Line::from(format!("Swap Used : {}", Self::format_bytes(app.mem_swap_used))),
+            Line::from(format!(
+                "{}: {}",
+                t.total,
+                Self::format_bytes(app.mem_total)
+            )),
+            Line::from(format!("{}: {}", t.used, Self::format_bytes(app.mem_used))),
+            Line::from(format!(
+                "{}: {}",
+                t.avail,
+                Self::format_bytes(app.mem_available)
+            )),
+            Line::from(format!(
+                "{}: {}",
+                t.swap_total,
+                Self::format_bytes(app.mem_swap_total)
+            )),
+            Line::from(format!(
+                "{}: {}",
+                t.swap_used,
+                Self::format_bytes(app.mem_swap_used)
+            )),
         ])
-        .block(Block::default().title("Breakdown").borders(Borders::ALL));
+        .block(Block::default().title(t.breakdown).borders(Borders::ALL));
         f.render_widget(info, chunks[2]);
     }
 
     fn render_disk_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
+        let t = app.strings;
+        let theme = app.theme;
         // Disk detailed: sparkline + gauge + per-disk list
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -206,82 +794,714 @@ impl UIRenderer {
             ])
             .split(area);
 
-        let disk_data: Vec<u64> = app.disk_history.clone();
         let spark = Sparkline::default()
-            .block(Block::default().title("Disk % (history)").borders(Borders::ALL))
-            .data(&disk_data)
-            .style(Style::default().fg(Color::Yellow));
+            .block(Block::default().title(t.disk_history).borders(Borders::ALL))
+            .data(
+                app.disk_history
+                    .latest(chunks[0].width.saturating_sub(2) as usize),
+            )
+            .style(Style::default().fg(theme.disk));
         f.render_widget(spark, chunks[0]);
 
-        let current = app.disk_history.last().cloned().unwrap_or(0) as f64 / 100.0;
+        let current = app.disk_history.last().unwrap_or(0) as f64 / 100.0;
         let gauge = Gauge::default()
-            .block(Block::default().title("Disk Usage").borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Yellow))
+            .block(Block::default().title(t.disk_usage).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Self::alert_color(app, 2, theme.disk)))
             .ratio(current)
-            .label(format!("{}%", app.disk_history.last().cloned().unwrap_or(0)));
+            .label(format!("{}%", app.disk_history.last().unwrap_or(0)));
         f.render_widget(gauge, chunks[1]);
 
-        // Per-disk listing
+        let lower = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[2]);
+
+        // Per-disk listing; the selected mount is highlighted while the pane has focus
         let disk_lines: Vec<Line> = app
             .disks_info
             .iter()
-            .map(|(mount, total, avail)| {
-                let used = total.saturating_sub(*avail);
-                let pct = if *total > 0 {
-                    ((used as f64 / *total as f64) * 100.0) as u64
+            .enumerate()
+            .map(|(idx, disk)| {
+                let used = disk.total.saturating_sub(disk.available);
+                let pct = if disk.total > 0 {
+                    ((used as f64 / disk.total as f64) * 100.0) as u64
                 } else {
                     0
                 };
-                Line::from(format!(
-                    "{}  {}/{} ({}%)",
-                    mount,
-                    Self::format_bytes(used),
-                    Self::format_bytes(*total),
-                    pct
+                let selected = idx == app.disk_selected;
+                let style = if selected && app.disk_focused {
+                    theme.selected
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                Line::from(Span::styled(
+                    format!(
+                        "{}{}  {}/{} ({}%)",
+                        if selected { "> " } else { "  " },
+                        disk.mount,
+                        Self::format_bytes(used),
+                        Self::format_bytes(disk.total),
+                        pct
+                    ),
+                    style,
                 ))
             })
             .collect();
 
-        let info = Paragraph::new(disk_lines)
-            .block(Block::default().title("Disk Mounts").borders(Borders::ALL));
+        let title = if app.disk_focused {
+            t.disk_mounts_focused
+        } else {
+            t.disk_mounts
+        };
+        let info =
+            Paragraph::new(disk_lines).block(Block::default().title(title).borders(Borders::ALL));
+        f.render_widget(info, lower[0]);
+
+        Self::render_mount_detail(f, app, lower[1]);
+    }
+
+    fn render_mount_detail(f: &mut Frame, app: &App, area: Rect) {
+        let t = app.strings;
+        let theme = app.theme;
+        let Some(disk) = app.disks_info.get(app.disk_selected) else {
+            let empty = Paragraph::new(t.no_mounts)
+                .block(Block::default().title(t.mount_detail).borders(Borders::ALL));
+            f.render_widget(empty, area);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(6),
+                Constraint::Length(5),
+                Constraint::Min(1),
+            ])
+            .split(area);
+
+        let history = app.mount_history.get(&disk.mount).map_or(&[][..], |h| {
+            h.latest(chunks[0].width.saturating_sub(2) as usize)
+        });
+        let spark = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(format!("{} {}", disk.mount, t.mount_history))
+                    .borders(Borders::ALL),
+            )
+            .data(history)
+            .max(100)
+            .style(Style::default().fg(theme.disk));
+        f.render_widget(spark, chunks[0]);
+
+        // Read and write throughput side by side, each titled with its current rate
+        match disk.io {
+            Some(io) => {
+                let halves = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[1]);
+                let histories = app.mount_io_history.get(&disk.mount);
+                let series = [
+                    (
+                        t.read,
+                        io.read_bps,
+                        io.read_iops,
+                        histories.map(|(read, _)| read),
+                        theme.received,
+                    ),
+                    (
+                        t.write,
+                        io.write_bps,
+                        io.write_iops,
+                        histories.map(|(_, write)| write),
+                        theme.sent,
+                    ),
+                ];
+                for ((label, bps, iops, history, color), half) in
+                    series.into_iter().zip(halves.iter())
+                {
+                    let title = format!(
+                        "{}: {}/s, {} {}",
+                        label,
+                        Self::format_bytes(bps),
+                        iops,
+                        t.iops
+                    );
+                    let spark =
+                        Sparkline::default()
+                            .block(Block::default().title(title).borders(Borders::ALL))
+                            .data(history.map_or(&[][..], |h| {
+                                h.latest(half.width.saturating_sub(2) as usize)
+                            }))
+                            .style(Style::default().fg(color));
+                    f.render_widget(spark, *half);
+                }
+            }
+            None => {
+                let empty =
+                    Paragraph::new(t.io_unavailable).block(Block::default().borders(Borders::ALL));
+                f.render_widget(empty, chunks[1]);
+            }
+        }
+
+        let used = disk.total.saturating_sub(disk.available);
+        let info = Paragraph::new(vec![
+            Line::from(format!("{}: {}", t.device, disk.device)),
+            Line::from(format!("{}: {}", t.filesystem, disk.fs_type)),
+            Line::from(format!("{}: {}", t.used, Self::format_bytes(used))),
+            Line::from(format!(
+                "{}: {}",
+                t.avail,
+                Self::format_bytes(disk.available)
+            )),
+            Line::from(format!("{}: {}", t.total, Self::format_bytes(disk.total))),
+        ])
+        .block(Block::default().title(t.mount_detail).borders(Borders::ALL));
         f.render_widget(info, chunks[2]);
     }
 
     fn render_network_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
-        // Network detailed: per-interface speeds and simple animated indicator
+        let t = app.strings;
+        let theme = app.theme;
+        if app.network_detail {
+            Self::render_interface_detail(f, app, area);
+            return;
+        }
+        // Network detailed: animated indicator, history of the first interface, per-interface speeds
+        let list_height = (app.networks_info.len() as u16 + 2)
+            .min(area.height / 3)
+            .max(3);
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(0)
             .constraints([
                 Constraint::Length(3),
                 Constraint::Min(0),
+                Constraint::Length(list_height),
             ])
             .split(area);
 
         // Header with animated spinner
         let spinner = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
         let s = spinner[app.tick % spinner.len()];
-        let header = Paragraph::new(vec![Line::from(format!("Network Interfaces {}", s))])
-            .block(Block::default().borders(Borders::ALL).title("Network"));
+        let header = Paragraph::new(vec![Line::from(format!("{} {}", t.network_interfaces, s))])
+            .block(Block::default().borders(Borders::ALL).title(t.network));
         f.render_widget(header, chunks[0]);
 
-        // Interface list: name, type, rx/s, tx/s
+        // Interface list: name, type, rx/s, tx/s; the selection is marked while it has focus
         let lines: Vec<Line> = app
             .networks_info
             .iter()
-            .map(|(name, rx, tx, kind)| {
-                Line::from(format!(
-                    "{} ({})  ↓ {}  ↑ {}",
-                    name,
-                    kind,
-                    Self::format_bytes(*rx),
-                    Self::format_bytes(*tx)
-                ))
+            .enumerate()
+            .map(|(idx, iface)| {
+                let pinned = app.pinned_interface.as_ref() == Some(&iface.0);
+                let text = format!(
+                    "{}{}",
+                    Self::interface_line(iface),
+                    if pinned {
+                        format!("  {}", t.pinned)
+                    } else {
+                        String::new()
+                    }
+                );
+                match app.network_focused {
+                    true if idx == app.network_selected => {
+                        Line::from(Span::styled(format!("> {}", text), theme.selected))
+                    }
+                    true => Line::from(format!("  {}", text)),
+                    false => Line::from(text),
+                }
             })
             .collect();
 
-        let list = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Interfaces"));
-        f.render_widget(list, chunks[1]);
+        Self::render_network_chart(f, app, chunks[1], app.charted_interface());
+
+        let (title, scroll) = if app.network_focused {
+            // Keep the selection in sight in a list taller than the pane
+            let rows = chunks[2].height.saturating_sub(2).max(1) as usize;
+            (
+                t.interfaces_focused,
+                app.network_selected.saturating_sub(rows - 1) as u16,
+            )
+        } else {
+            (t.interfaces, 0)
+        };
+        let list = Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(list, chunks[2]);
+    }
+
+    // Addresses, link settings and totals of the selected interface, over its own chart
+    fn render_interface_detail(f: &mut Frame, app: &App, area: Rect) {
+        let t = app.strings;
+        let Some((name, _, _, kind)) = app.networks_info.get(app.network_selected) else {
+            let empty = Paragraph::new(t.no_network).block(Block::default().borders(Borders::ALL));
+            f.render_widget(empty, area);
+            return;
+        };
+        let detail = app.interface_details.get(name);
+
+        let mut lines = vec![
+            Line::from(format!(
+                "{}: {}",
+                t.mac,
+                detail.map_or("", |d| d.mac.as_str())
+            )),
+            Line::from(format!(
+                "{}: {}",
+                t.mtu,
+                detail
+                    .and_then(|d| d.mtu)
+                    .map_or(t.unknown.to_string(), |mtu| mtu.to_string())
+            )),
+            Line::from(format!(
+                "{}: {}",
+                t.link_speed,
+                detail
+                    .and_then(|d| d.speed)
+                    .map_or(t.unknown.to_string(), |speed| format!("{} Mb/s", speed))
+            )),
+            Line::from(format!(
+                "{}: {}",
+                t.total_received,
+                Self::format_bytes(detail.map_or(0, |d| d.total_received))
+            )),
+            Line::from(format!(
+                "{}: {}",
+                t.total_sent,
+                Self::format_bytes(detail.map_or(0, |d| d.total_transmitted))
+            )),
+        ];
+        if let Some((received, sent)) = app.session_transfer(name) {
+            lines.push(Line::from(format!(
+                "{}: {}",
+                t.session_received,
+                Self::format_bytes(received)
+            )));
+            lines.push(Line::from(format!(
+                "{}: {}",
+                t.session_sent,
+                Self::format_bytes(sent)
+            )));
+        }
+        match detail.map(|d| d.addresses.as_slice()).unwrap_or_default() {
+            [] => lines.push(Line::from(format!("{}: {}", t.addresses, t.no_addresses))),
+            addresses => {
+                lines.push(Line::from(format!("{}:", t.addresses)));
+                lines.extend(
+                    addresses
+                        .iter()
+                        .map(|address| Line::from(format!("  {}", address))),
+                );
+            }
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(lines.len() as u16 + 2),
+                Constraint::Min(0),
+            ])
+            .split(area);
+        let info = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!("{} ({}) {}", name, kind, t.interface_detail))
+                .borders(Borders::ALL),
+        );
+        f.render_widget(info, chunks[0]);
+        Self::render_network_chart(f, app, chunks[1], app.network_selected);
+    }
+
+    // Received and sent rates of one interface over time, on a shared axis scaled to the
+    // busiest moment shown. Outside the interface list that's the first (pinned) one.
+    fn render_network_chart(f: &mut Frame, app: &App, area: Rect, index: usize) {
+        let t = app.strings;
+        let theme = app.theme;
+        let Some((name, _, _, _)) = app.networks_info.get(index) else {
+            let empty = Paragraph::new(t.no_network).block(Block::default().borders(Borders::ALL));
+            f.render_widget(empty, area);
+            return;
+        };
+        let (rx, tx) = app
+            .network_history
+            .get(name)
+            .map_or((&[][..], &[][..]), |(rx, tx)| {
+                (rx.as_slice(), tx.as_slice())
+            });
+        // At least 1 KiB/s, so an idle link doesn't blow noise up to full height
+        let peak = rx.iter().chain(tx).copied().max().unwrap_or(0);
+        let top = app
+            .chart_max
+            .map_or(peak.max(1024) as f64 * 1.1, |max| max as f64);
+        // On the log scale 1 KiB/s is the floor, at least a power of ten below the top, and y
+        // is the power of ten of the rate
+        let (scale, bottom, top, title): (fn(f64) -> f64, f64, f64, String) = match app.chart_scale
+        {
+            ChartScale::Linear => (|bps| bps, 0.0, top, t.throughput_history.to_string()),
+            ChartScale::Log => (
+                |bps| bps.max(1024.0).log10(),
+                1024.0,
+                top.max(10.0 * 1024.0),
+                format!("{}, {}", t.throughput_history, t.log_scale),
+            ),
+        };
+        // Newest sample at the right edge, where a full history would put it
+        let offset = app.history_len.saturating_sub(rx.len());
+        let points = |history: &[u64]| -> Vec<(f64, f64)> {
+            history
+                .iter()
+                .enumerate()
+                .map(|(i, bps)| ((offset + i) as f64, scale(*bps as f64)))
+                .collect()
+        };
+        let rx_points = points(rx);
+        let tx_points = points(tx);
+
+        let datasets = vec![
+            Dataset::default()
+                .name(format!("↓ {}", t.received))
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.received))
+                .data(&rx_points),
+            Dataset::default()
+                .name(format!("↑ {}", t.sent))
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.sent))
+                .data(&tx_points),
+        ];
+        let rate = |bps: f64| format!("{}/s", Self::format_bytes(bps as u64));
+        // Halfway up, which on the log scale is the geometric mean
+        let middle = match app.chart_scale {
+            ChartScale::Linear => (bottom + top) / 2.0,
+            ChartScale::Log => (bottom * top).sqrt(),
+        };
+        let chart = Chart::new(datasets)
+            .block(
+                Block::default()
+                    .title(format!(
+                        "{} {}, {} {}",
+                        name,
+                        title,
+                        t.peak,
+                        rate(peak as f64)
+                    ))
+                    .borders(Borders::ALL),
+            )
+            .x_axis(Axis::default().bounds([0.0, app.history_len.saturating_sub(1) as f64]))
+            .y_axis(
+                Axis::default()
+                    .bounds([scale(bottom), scale(top)])
+                    .labels(vec![rate(bottom), rate(middle), rate(top)]),
+            );
+        f.render_widget(chart, area);
+    }
+
+    fn render_process_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
+        let t = app.strings;
+        let theme = app.theme;
+        // The sorted column is marked with the direction it sorts in
+        let arrow = |sort: ProcessSort| {
+            if sort != app.process_sort {
+                return "";
+            }
+            let ascending = matches!(sort, ProcessSort::Pid | ProcessSort::Name);
+            if ascending != app.process_sort_reversed {
+                " ▲"
+            } else {
+                " ▼"
+            }
+        };
+        let header = Row::new(vec![
+            Cell::from(format!("{}{}", t.pid, arrow(ProcessSort::Pid))),
+            Cell::from(format!("{}{}", t.name, arrow(ProcessSort::Name))),
+            Cell::from(format!("{}%{}", t.cpu, arrow(ProcessSort::Cpu))),
+            Cell::from(format!("{}{}", t.memory, arrow(ProcessSort::Memory))),
+            Cell::from(format!("{}/s{}", t.read, arrow(ProcessSort::Io))),
+            Cell::from(format!("{}/s{}", t.write, arrow(ProcessSort::Io))),
+            Cell::from(t.sockets),
+        ])
+        .style(
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let rows: Vec<Row> = app
+            .processes
+            .iter()
+            .map(|p| {
+                Row::new(vec![
+                    p.pid.to_string(),
+                    p.name.clone(),
+                    format!("{:.1}", p.cpu),
+                    Self::format_bytes(p.memory),
+                    Self::format_bytes(p.disk_read),
+                    Self::format_bytes(p.disk_write),
+                    p.sockets.map_or_else(|| "-".to_string(), |n| n.to_string()),
+                ])
+                .style(Style::default().fg(theme.text))
+            })
+            .collect();
+
+        let title = if app.process_focused {
+            t.process_table_focused
+        } else {
+            t.process_table
+        };
+        let mut block = Block::default().title(title).borders(Borders::ALL);
+        match &app.kill_status {
+            Some((Ok(sent), _)) => {
+                block = block.title_bottom(Line::styled(
+                    format!(" {}: {} ", t.signal_sent, sent),
+                    Style::default().fg(theme.ok),
+                ));
+            }
+            Some((Err(e), _)) => {
+                block = block.title_bottom(Line::styled(
+                    format!(" {} ", e),
+                    Style::default().fg(theme.alert),
+                ));
+            }
+            None => {}
+        }
+        if app.processes.is_empty() {
+            f.render_widget(Paragraph::new(t.no_processes).block(block), area);
+            return;
+        }
+
+        let highlight = if app.process_focused {
+            theme.selected
+        } else {
+            Style::default()
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Min(16),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .highlight_style(highlight)
+        .highlight_symbol("> ");
+        // Scrolled just far enough to keep the selection in view
+        let mut state = TableState::default().with_selected(Some(app.process_selected));
+        f.render_stateful_widget(table, area, &mut state);
+
+        if let Some(request) = &app.kill_request {
+            Self::render_kill_confirm(f, app, area, request);
+        }
+    }
+
+    fn render_kill_confirm(f: &mut Frame, app: &App, area: Rect, request: &KillRequest) {
+        let t = app.strings;
+        let theme = app.theme;
+        let question = format!(
+            "{} {} → {} ({})?",
+            t.send_signal,
+            signal_name(request.signal),
+            request.pid,
+            request.name
+        );
+        let width = (question.chars().count().max(t.confirm_hint.chars().count()) as u16 + 4)
+            .min(area.width);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + area.height.saturating_sub(4) / 2,
+            width,
+            height: 4.min(area.height),
+        };
+        let body = Paragraph::new(vec![
+            Line::from(question),
+            Line::from(Span::styled(t.confirm_hint, Style::default().fg(theme.dim))),
+        ])
+        .block(
+            Block::default()
+                .title(t.confirm)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(theme.alert)),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(Block::default().style(app.theme.base()), popup);
+        f.render_widget(body, popup);
+    }
+
+    // Across the top margin, above both panels
+    fn render_paused(f: &mut Frame, app: &App, area: Rect) {
+        let header = Rect {
+            height: 1.min(area.height),
+            ..area
+        };
+        let paused = Paragraph::new(Span::styled(
+            format!(" {} ", app.strings.paused),
+            app.theme.selected.add_modifier(Modifier::REVERSED),
+        ))
+        .alignment(Alignment::Center);
+        f.render_widget(paused, header);
+    }
+
+    // Every keybinding over whatever is on screen, which keeps updating underneath
+    fn render_help(f: &mut Frame, app: &App, area: Rect) {
+        let t = app.strings;
+        let theme = app.theme;
+        let key_width = t
+            .help_keys
+            .iter()
+            .map(|(keys, _)| keys.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines: Vec<Line> = t
+            .help_keys
+            .iter()
+            .map(|(keys, action)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}  ", keys, width = key_width),
+                        theme.selected,
+                    ),
+                    Span::raw(*action),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{}: {}",
+                t.refresh,
+                Self::format_interval(app.sample_interval)
+            ),
+            Style::default().fg(theme.dim),
+        )));
+
+        let width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4;
+        let height = lines.len() as u16 + 2;
+        let popup = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width: width.min(area.width),
+            height: height.min(area.height),
+        };
+        let body = Paragraph::new(lines).block(
+            Block::default()
+                .title(format!(" {} ", t.help))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border)),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(Block::default().style(theme.base()), popup);
+        f.render_widget(body, popup);
+    }
+
+    fn render_sensor_view(f: &mut Frame, app: &App, area: Rect, _panel_block: Block) {
+        let t = app.strings;
+        let theme = app.theme;
+        let fan_height = if app.fans.is_empty() {
+            0
+        } else {
+            app.fans.len() as u16 + 2
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(fan_height)])
+            .split(area);
+
+        if app.sensors.is_empty() {
+            let empty = Paragraph::new(t.no_sensors)
+                .block(Block::default().title(t.temperatures).borders(Borders::ALL));
+            f.render_widget(empty, chunks[0]);
+        } else {
+            // One chart per sensor, in a second column once the first is full
+            let per_column = (chunks[0].height / SENSOR_HEIGHT).max(1) as usize;
+            let columns = if app.sensors.len() > per_column { 2 } else { 1 };
+            let column_areas = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, columns); columns as usize])
+                .split(chunks[0]);
+            for (i, sensor) in app
+                .sensors
+                .iter()
+                .enumerate()
+                .take(per_column * columns as usize)
+            {
+                let column = column_areas[i / per_column];
+                let row = (i % per_column) as u16;
+                let chart = Rect {
+                    x: column.x,
+                    y: column.y + row * SENSOR_HEIGHT,
+                    width: column.width,
+                    height: SENSOR_HEIGHT.min(column.height),
+                };
+                let hot = sensor.temperature >= app.sensor_config.warning_for(&sensor.label);
+                let color = if hot { theme.alert } else { theme.sensor };
+                let history = app.sensor_history.get(&sensor.label).map_or(&[][..], |h| {
+                    h.latest(chart.width.saturating_sub(2) as usize)
+                });
+                // On the same scale over time, up to the critical point where there is one
+                let scale = sensor.critical.unwrap_or(100.0).max(sensor.max).round() as u64;
+                let spark = Sparkline::default()
+                    .block(
+                        Block::default()
+                            .title(Span::styled(
+                                Self::sensor_line(app, sensor),
+                                Style::default().fg(color),
+                            ))
+                            .borders(Borders::ALL),
+                    )
+                    .data(history)
+                    .max(scale.max(1))
+                    .style(Style::default().fg(color));
+                f.render_widget(spark, chart);
+            }
+        }
+
+        if !app.fans.is_empty() {
+            let lines: Vec<Line> = app
+                .fans
+                .iter()
+                .map(|(label, rpm)| Line::from(format!("{}: {} RPM", label, rpm)))
+                .collect();
+            let fans =
+                Paragraph::new(lines).block(Block::default().title(t.fans).borders(Borders::ALL));
+            f.render_widget(fans, chunks[1]);
+        }
+    }
+
+    // Hours and minutes, e.g. "2h 15m", or just minutes under an hour
+    fn format_duration(duration: Duration) -> String {
+        let minutes = duration.as_secs() / 60;
+        if minutes < 60 {
+            format!("{}m", minutes)
+        } else {
+            format!("{}h {:02}m", minutes / 60, minutes % 60)
+        }
+    }
+
+    // Days, then as format_duration does, e.g. 3d 4h 05m
+    fn format_uptime(secs: u64) -> String {
+        let (days, rest) = (secs / 86_400, Duration::from_secs(secs % 86_400));
+        if days > 0 {
+            format!("{}d {}", days, Self::format_duration(rest))
+        } else {
+            Self::format_duration(rest)
+        }
+    }
+
+    fn format_interval(interval: Duration) -> String {
+        let ms = interval.as_millis();
+        if ms < 1000 {
+            format!("{} ms", ms)
+        } else {
+            format!("{} s", interval.as_secs_f64())
+        }
     }
 
     fn format_bytes(bytes: u64) -> String {